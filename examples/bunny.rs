@@ -14,6 +14,7 @@ unsafe impl shade::TVertex for MyVertex3 {
 	const VERTEX_LAYOUT: &'static shade::VertexLayout = &shade::VertexLayout {
 		size: std::mem::size_of::<MyVertex3>() as u16,
 		alignment: std::mem::align_of::<MyVertex3>() as u16,
+		instance_divisor: 0,
 		attributes: &[
 			shade::VertexAttribute {
 				format: shade::VertexAttributeFormat::F32,
@@ -164,7 +165,7 @@ fn main() {
 
 	// Create the shader
 	let shader = g.shader_create(None).unwrap();
-	if let Err(_) = g.shader_compile(shader, VERTEX_SHADER, FRAGMENT_SHADER) {
+	if g.shader_compile(shader, VERTEX_SHADER, FRAGMENT_SHADER).is_err() {
 		panic!("Failed to compile shader: {}", g.shader_compile_log(shader).unwrap());
 	}
 
@@ -211,11 +212,12 @@ fn main() {
 		}).unwrap();
 
 		// Rotate the bunny
-		model = model * cvmath::Mat4::rotate(cvmath::Deg(1.0), cvmath::Vec3::Z);
+		model *= cvmath::Mat4::rotate(cvmath::Deg(1.0), cvmath::Vec3::Z);
 
 		// Update the transformation matrices
-		let projection = cvmath::Mat4::perspective_fov(cvmath::Deg(45.0), size.width as f32, size.height as f32, 0.1, 1000.0, (cvmath::RH, cvmath::NO));
-		let view = cvmath::Mat4::look_at(cvmath::Vec3(0.0, 50.0, -200.0), cvmath::Vec3(0.0, 0.0, 0.0), cvmath::Vec3(0.0, 1.0, 0.0), cvmath::RH);
+		let conventions = shade::conventions();
+		let projection = conventions.perspective_fov(cvmath::Deg(45.0), size.width as f32, size.height as f32, 0.1, 1000.0);
+		let view = conventions.look_at(cvmath::Vec3(0.0, 50.0, -200.0), cvmath::Vec3(0.0, 0.0, 0.0));
 		let transform = projection * view * model;
 
 		// Update the uniform buffer with the new transformation matrix
@@ -231,6 +233,9 @@ fn main() {
 			blend_mode: shade::BlendMode::Solid,
 			depth_test: Some(shade::DepthTest::Less),
 			cull_mode: None,
+			fill_mode: shade::FillMode::Solid,
+			alpha_to_coverage: false,
+			stencil: None,
 			prim_type: shade::PrimType::Triangles,
 			shader,
 			vertices: vb,
@@ -15,6 +15,7 @@ unsafe impl shade::TVertex for MyVertex3 {
 	const VERTEX_LAYOUT: &'static shade::VertexLayout = &shade::VertexLayout {
 		size: std::mem::size_of::<MyVertex3>() as u16,
 		alignment: std::mem::align_of::<MyVertex3>() as u16,
+		instance_divisor: 0,
 		attributes: &[
 			shade::VertexAttribute {
 				format: shade::VertexAttributeFormat::F32,
@@ -181,10 +182,13 @@ fn main() {
 
 	// Load the texture
 	let texture = shade::png::load(&mut g, Some("brick 24"), "examples/textures/brick 24 - 256x256.png", &shade::png::TextureProps {
-		filter_min: shade::TextureFilter::Linear,
+		filter_min: shade::TextureFilter::LinearMipmapLinear,
 		filter_mag: shade::TextureFilter::Linear,
 		wrap_u: shade::TextureWrap::ClampEdge,
 		wrap_v: shade::TextureWrap::ClampEdge,
+		mip_levels: shade::MipLevels::Full,
+		anisotropy: 16.0,
+		premultiply: false,
 	}, None).unwrap();
 
 	// Create the vertex and index buffers
@@ -199,7 +203,7 @@ fn main() {
 
 	// Create the shader
 	let shader = g.shader_create(None).unwrap();
-	if let Err(_) = g.shader_compile(shader, VERTEX_SHADER, FRAGMENT_SHADER) {
+	if g.shader_compile(shader, VERTEX_SHADER, FRAGMENT_SHADER).is_err() {
 		panic!("Failed to compile shader: {}", g.shader_compile_log(shader).unwrap());
 	}
 
@@ -246,11 +250,12 @@ fn main() {
 		}).unwrap();
 
 		// Rotate the cube
-		model = model * cvmath::Mat4::rotate(cvmath::Deg(1.0), cvmath::Vec3(0.8, 0.6, 0.1));
+		model *= cvmath::Mat4::rotate(cvmath::Deg(1.0), cvmath::Vec3(0.8, 0.6, 0.1));
 
 		// Update the transformation matrices
-		let projection = cvmath::Mat4::perspective_fov(cvmath::Deg(45.0), size.width as f32, size.height as f32, 0.1, 100.0, (cvmath::RH, cvmath::NO));
-		let view = cvmath::Mat4::look_at(cvmath::Vec3(0.0, 0.0, 4.0), cvmath::Vec3::ZERO, cvmath::Vec3(0.0, 1.0, 0.0), cvmath::RH);
+		let conventions = shade::conventions();
+		let projection = conventions.perspective_fov(cvmath::Deg(45.0), size.width as f32, size.height as f32, 0.1, 100.0);
+		let view = conventions.look_at(cvmath::Vec3(0.0, 0.0, 4.0), cvmath::Vec3::ZERO);
 		let transform = projection * view * model;
 
 		// Update the uniform buffer with the new transformation matrix
@@ -266,6 +271,9 @@ fn main() {
 			blend_mode: shade::BlendMode::Solid,
 			depth_test: Some(shade::DepthTest::Less),
 			cull_mode: None,
+			fill_mode: shade::FillMode::Solid,
+			alpha_to_coverage: false,
+			stencil: None,
 			prim_type: shade::PrimType::Triangles,
 			shader,
 			vertices: vb,
@@ -275,6 +283,7 @@ fn main() {
 			vertex_end: VERTICES.len() as u32,
 			index_start: 0,
 			index_end: INDICES.len() as u32,
+			base_vertex: 0,
 			uniform_index: 0,
 			instances: -1,
 		}).unwrap();
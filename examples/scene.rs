@@ -15,6 +15,7 @@ unsafe impl shade::TVertex for MyVertex3 {
 	const VERTEX_LAYOUT: &'static shade::VertexLayout = &shade::VertexLayout {
 		size: std::mem::size_of::<MyVertex3>() as u16,
 		alignment: std::mem::align_of::<MyVertex3>() as u16,
+		instance_divisor: 0,
 		attributes: &[
 			shade::VertexAttribute {
 				format: shade::VertexAttributeFormat::F32,
@@ -149,13 +150,16 @@ fn main() {
 		filter_mag: shade::TextureFilter::Nearest,
 		wrap_u: shade::TextureWrap::ClampEdge,
 		wrap_v: shade::TextureWrap::ClampEdge,
+		mip_levels: shade::MipLevels::None,
+		anisotropy: 1.0,
+		premultiply: false,
 	}, None).unwrap();
 	let tex_info = g.texture2d_get_info(texture).unwrap();
 	let texture_size = [tex_info.width as f32, tex_info.height as f32];
 
 	// Create the shader
 	let shader = g.shader_create(None).unwrap();
-	if let Err(_) = g.shader_compile(shader, VERTEX_SHADER, FRAGMENT_SHADER) {
+	if g.shader_compile(shader, VERTEX_SHADER, FRAGMENT_SHADER).is_err() {
 		panic!("Failed to compile shader: {}", g.shader_compile_log(shader).unwrap());
 	}
 
@@ -203,12 +207,12 @@ fn main() {
 		let curtime = time::Instant::now().duration_since(time_base).as_secs_f32();
 
 		// Update the camera
-		let projection = cvmath::Mat4::perspective_fov(cvmath::Deg(45.0), size.width as f32, size.height as f32, 0.1, 1000.0, (cvmath::RH, cvmath::NO));
+		let conventions = shade::conventions();
+		let projection = conventions.perspective_fov(cvmath::Deg(45.0), size.width as f32, size.height as f32, 0.1, 1000.0);
 		let view = {
 			let eye = cvmath::Vec3(32.0 + (curtime * 2.0).sin() * 32.0, 100.0 + (curtime * 1.5).sin() * 32.0, -100.0) * 1.5;
 			let target = cvmath::Vec3(96.0 * 0.5, 0.0, 32.0);
-			let up = cvmath::Vec3(0.0, 1.0, 0.0);
-			cvmath::Mat4::look_at(eye, target, up, cvmath::RH)
+			conventions.look_at(eye, target)
 		};
 		let transform = projection * view;
 
@@ -27,17 +27,25 @@ fn main() {
 			filter_mag: shade::TextureFilter::Linear,
 			wrap_u: shade::TextureWrap::ClampEdge,
 			wrap_v: shade::TextureWrap::ClampEdge,
+			mip_levels: shade::MipLevels::None,
+			anisotropy: 1.0,
+			premultiply: false,
 		}, None).unwrap();
 
 		// Compile the shader
 		let shader = g.shader_create(None).unwrap();
-		if let Err(_) = g.shader_compile(shader, shade::gl::MTSDF_VS, shade::gl::MTSDF_FS) {
+		if g.shader_compile(shader, shade::gl::MTSDF_VS, shade::gl::MTSDF_FS).is_err() {
 			panic!("Failed to compile shader: {}", g.shader_compile_log(shader).unwrap());
 		}
 
 		shade::d2::FontResource { font, texture, shader }
 	};
 
+	g.display_update(cvmath::Vec2(size.width as i32, size.height as i32), context.window().scale_factor() as f32);
+
+	// The text is laid out again when the display changes
+	let mut cv = shade::d2::TextBuffer::new();
+
 	// Main loop
 	event_loop.run(move |event, _, control_flow| {
 		match event {
@@ -47,6 +55,7 @@ fn main() {
 			winit::event::Event::WindowEvent { event: winit::event::WindowEvent::Resized(new_size), .. } => {
 				size = new_size;
 				context.resize(new_size);
+				g.display_update(cvmath::Vec2(size.width as i32, size.height as i32), context.window().scale_factor() as f32);
 			}
 			winit::event::Event::RedrawRequested(_) => {
 				// Render the frame
@@ -60,40 +69,44 @@ fn main() {
 					..Default::default()
 				}).unwrap();
 
-				let mut cv = shade::d2::TextBuffer::new();
-				cv.shader = font.shader;
-				cv.blend_mode = shade::BlendMode::Alpha;
-				cv.viewport = cvmath::Rect::c(0, 0, size.width as i32, size.height as i32);
-				cv.push_uniform(shade::d2::TextUniform {
-					transform: cvmath::Transform2::remap(cvmath::Rect::c(0.0, 0.0, size.width as f32, size.height as f32), cvmath::Rect::c(-1.0, 1.0, 1.0, -1.0)),
-					texture: font.texture,
-					outline_width_relative: 0.125,
-					..Default::default()
-				});
-
-				let mut pos = cvmath::Vec2(0.0, 0.0);
-				let mut scribe = shade::d2::Scribe {
-					font_size: 64.0,
-					line_height: 64.0 * 1.5,
-					x_pos: pos.x,
-					top_skew: 8.0,
-					..Default::default()
-				};
-				scribe.set_baseline_relative(0.5);
-
-				cv.text_write(&font, &mut scribe, &mut pos, "Hello, \x1b[font_size=96.0]\x1b[font_width_scale=1.5]\x1b[top_skew=0.0]world!");
-
-				scribe.font_size = 32.0;
-				scribe.line_height = 32.0;
-				scribe.font_width_scale = 1.0;
-				scribe.color = cvmath::Vec4(255, 255, 0, 255);
-				cv.text_box(&font, &scribe, &cvmath::Rect::c(0.0, 0.0, size.width as f32, size.height as f32), shade::d2::BoxAlign::MiddleCenter, "These\nare\nmultiple\nlines.\n");
-
-				scribe.top_skew = 8.0;
-				let rainbow = "\x1b[color=#E81416]R\x1b[color=#FFA500]A\x1b[color=#FAEB36]I\x1b[color=#79C314]N\x1b[color=#487DE7]B\x1b[color=#4B369D]O\x1b[color=#70369D]W";
-				let rainbow_width = scribe.text_width(&mut {cvmath::Vec2::ZERO}, &font.font, rainbow);
-				let mut pos = cvmath::Vec2f((size.width as f32 - rainbow_width) * 0.5, size.height as f32 - scribe.font_size);
-				cv.text_write(&font, &mut scribe, &mut pos, rainbow);
+				if cv.display_generation != Some(g.display_info().generation) {
+					cv.clear();
+					cv.shader = font.shader;
+					cv.blend_mode = shade::BlendMode::Alpha;
+					cv.viewport = cvmath::Rect::c(0, 0, size.width as i32, size.height as i32);
+					cv.push_uniform(shade::d2::TextUniform {
+						transform: cvmath::Transform2::remap(cvmath::Rect::c(0.0, 0.0, size.width as f32, size.height as f32), cvmath::Rect::c(-1.0, 1.0, 1.0, -1.0)),
+						texture: font.texture,
+						outline_width_relative: 0.125,
+						..Default::default()
+					});
+
+					let mut pos = cvmath::Vec2(0.0, 0.0);
+					let mut scribe = shade::d2::Scribe {
+						font_size: 64.0,
+						line_height: 64.0 * 1.5,
+						x_pos: pos.x,
+						top_skew: 8.0,
+						..Default::default()
+					};
+					scribe.set_baseline_relative(0.5);
+
+					cv.text_write(&font, &mut scribe, &mut pos, "Hello, \x1b[font_size=96.0]\x1b[font_width_scale=1.5]\x1b[top_skew=0.0]world!");
+
+					scribe.font_size = 32.0;
+					scribe.line_height = 32.0;
+					scribe.font_width_scale = 1.0;
+					scribe.color = cvmath::Vec4(255, 255, 0, 255);
+					cv.text_box(&font, &scribe, &cvmath::Rect::c(0.0, 0.0, size.width as f32, size.height as f32), shade::d2::BoxAlign::MiddleCenter, "These\nare\nmultiple\nlines.\n");
+
+					scribe.top_skew = 8.0;
+					let rainbow = "\x1b[color=#E81416]R\x1b[color=#FFA500]A\x1b[color=#FAEB36]I\x1b[color=#79C314]N\x1b[color=#487DE7]B\x1b[color=#4B369D]O\x1b[color=#70369D]W";
+					let rainbow_width = scribe.text_width(&mut {cvmath::Vec2::ZERO}, &font.font, rainbow);
+					let mut pos = cvmath::Vec2f((size.width as f32 - rainbow_width) * 0.5, size.height as f32 - scribe.font_size);
+					cv.text_write(&font, &mut scribe, &mut pos, rainbow);
+
+					cv.depend_on_display(&g);
+				}
 
 				cv.draw(&mut g, shade::Surface::BACK_BUFFER).unwrap();
 
@@ -13,6 +13,7 @@ unsafe impl shade::TVertex for TriangleVertex {
 	const VERTEX_LAYOUT: &'static shade::VertexLayout = &shade::VertexLayout {
 		size: std::mem::size_of::<TriangleVertex>() as u16,
 		alignment: std::mem::align_of::<TriangleVertex>() as u16,
+		instance_divisor: 0,
 		attributes: &[
 			shade::VertexAttribute {
 				format: shade::VertexAttributeFormat::F32,
@@ -32,16 +33,10 @@ unsafe impl shade::TVertex for TriangleVertex {
 // The triangle's uniforms and shaders
 // Here the shader has no uniforms, so we use an empty struct
 
-#[derive(Copy, Clone, dataview::Pod)]
+#[derive(Copy, Clone, Default, dataview::Pod)]
 #[repr(C)]
 struct TriangleUniforms {}
 
-impl Default for TriangleUniforms {
-	fn default() -> Self {
-		TriangleUniforms {}
-	}
-}
-
 unsafe impl shade::TUniform for TriangleUniforms {
 	const UNIFORM_LAYOUT: &'static shade::UniformLayout = &shade::UniformLayout {
 		size: std::mem::size_of::<TriangleUniforms>() as u16,
@@ -110,7 +105,7 @@ fn main() {
 
 	// Create the triangle shader
 	let shader = g.shader_create(None).unwrap();
-	if let Err(_) = g.shader_compile(shader, VERTEX_SHADER, FRAGMENT_SHADER) {
+	if g.shader_compile(shader, VERTEX_SHADER, FRAGMENT_SHADER).is_err() {
 		panic!("Failed to compile shader: {}", g.shader_compile_log(shader).unwrap());
 	}
 
@@ -143,6 +138,9 @@ fn main() {
 					blend_mode: shade::BlendMode::Solid,
 					depth_test: None,
 					cull_mode: None,
+					fill_mode: shade::FillMode::Solid,
+					alpha_to_coverage: false,
+					stencil: None,
 					prim_type: shade::PrimType::Triangles,
 					shader,
 					vertices: vb,
@@ -0,0 +1,134 @@
+use super::*;
+
+/// Range of a mesh sub-allocated in a [`BufferArena`].
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct ArenaRange {
+	/// Index of the first vertex.
+	pub vertex_start: u32,
+	/// Index of one past the last vertex.
+	pub vertex_end: u32,
+	/// Index of the first index.
+	pub index_start: u32,
+	/// Index of one past the last index.
+	pub index_end: u32,
+}
+
+impl ArenaRange {
+	/// Returns the base vertex to draw the mesh with.
+	///
+	/// The indices of the mesh are stored relative to its first vertex.
+	#[inline]
+	pub fn base_vertex(&self) -> i32 {
+		self.vertex_start as i32
+	}
+}
+
+/// Buffer arena.
+///
+/// Sub-allocates many small meshes inside one large vertex buffer and index buffer.
+/// Every allocation returns an [`ArenaRange`] which can be plugged into the draw arguments.
+///
+/// The indices of a mesh are stored relative to its own vertices,
+/// draw with the [`base_vertex`](ArenaRange::base_vertex) of the range to offset them into the shared vertex buffer.
+pub struct BufferArena<V> {
+	vertices: Vec<V>,
+	indices: Vec<u32>,
+	dirty: bool,
+
+	/// The shared vertex buffer, valid after the first upload.
+	pub vertex_buffer: VertexBuffer,
+	/// The shared index buffer, valid after the first upload.
+	pub index_buffer: IndexBuffer,
+}
+
+impl<V: TVertex> Default for BufferArena<V> {
+	#[inline]
+	fn default() -> Self {
+		BufferArena::new()
+	}
+}
+
+impl<V: TVertex> BufferArena<V> {
+	/// Creates a new buffer arena.
+	pub fn new() -> Self {
+		BufferArena {
+			vertices: Vec::new(),
+			indices: Vec::new(),
+			dirty: false,
+			vertex_buffer: VertexBuffer::INVALID,
+			index_buffer: IndexBuffer::INVALID,
+		}
+	}
+
+	/// Allocates a mesh in the arena.
+	///
+	/// The indices must be indices into the vertices slice, they are stored unchanged.
+	#[track_caller]
+	pub fn alloc(&mut self, vertices: &[V], indices: &[u32]) -> ArenaRange {
+		let vertex_start = self.vertices.len() as u32;
+		let index_start = self.indices.len() as u32;
+
+		self.vertices.extend_from_slice(vertices);
+		for &index in indices {
+			debug_assert!(index == u32::MAX || (index as usize) < vertices.len(), "vertex index ({}) out of bounds ({} vertices)", index, vertices.len());
+		}
+		self.indices.extend_from_slice(indices);
+		self.dirty = true;
+
+		ArenaRange {
+			vertex_start,
+			vertex_end: self.vertices.len() as u32,
+			index_start,
+			index_end: self.indices.len() as u32,
+		}
+	}
+
+	/// Returns the number of vertices and indices allocated.
+	#[inline]
+	pub fn counts(&self) -> (usize, usize) {
+		(self.vertices.len(), self.indices.len())
+	}
+
+	/// Clears all allocations for reuse.
+	///
+	/// Previously returned ranges are invalidated, the GPU buffers are kept.
+	pub fn clear(&mut self) {
+		self.vertices.clear();
+		self.indices.clear();
+		self.dirty = true;
+	}
+
+	/// Uploads the allocated meshes to the GPU.
+	///
+	/// Creates the shared buffers on first use, afterwards only uploads when new meshes were allocated.
+	pub fn upload(&mut self, g: &mut Graphics, usage: BufferUsage) -> Result<(), GfxError> {
+		if self.vertex_buffer == VertexBuffer::INVALID {
+			self.vertex_buffer = g.vertex_buffer_create::<V>(None, self.vertices.len())?;
+			self.dirty = true;
+		}
+		if self.index_buffer == IndexBuffer::INVALID {
+			self.index_buffer = g.index_buffer_create(None, self.indices.len())?;
+			self.dirty = true;
+		}
+		if self.dirty {
+			g.vertex_buffer_set_data(self.vertex_buffer, &self.vertices, usage)?;
+			g.index_buffer_set_data(self.index_buffer, &self.indices, usage)?;
+			self.dirty = false;
+		}
+		Ok(())
+	}
+
+	/// Releases the GPU buffers.
+	pub fn delete(&mut self, g: &mut Graphics) -> Result<(), GfxError> {
+		if self.index_buffer != IndexBuffer::INVALID {
+			g.index_buffer_delete(self.index_buffer, true)?;
+			self.index_buffer = IndexBuffer::INVALID;
+		}
+		if self.vertex_buffer != VertexBuffer::INVALID {
+			g.vertex_buffer_delete(self.vertex_buffer, true)?;
+			self.vertex_buffer = VertexBuffer::INVALID;
+		}
+		self.dirty = true;
+		Ok(())
+	}
+}
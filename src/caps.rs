@@ -0,0 +1,115 @@
+
+/// Graphics capabilities.
+///
+/// Reports limits and optional features of the graphics backend.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct Caps {
+	/// Maximum width and height of a 2D texture.
+	pub max_texture_size: i32,
+	/// Maximum number of vertex attributes.
+	pub max_vertex_attributes: i32,
+	/// Maximum number of texture units accessible from the fragment shader.
+	pub max_texture_units: i32,
+	/// Maximum number of color attachments of a surface.
+	pub max_color_attachments: i32,
+	/// Maximum anisotropy level, `0` if anisotropic filtering is not supported.
+	pub max_anisotropy: i32,
+	/// Instanced drawing is supported.
+	pub instancing: bool,
+	/// Floating point textures and surfaces are supported.
+	pub float_textures: bool,
+	/// Double precision uniforms and vertex attributes are supported.
+	///
+	/// Otherwise double precision uniforms and vertex attributes are converted to single precision with a warning,
+	/// the shader must declare them as `float` instead, see [`split_f64`](crate::split_f64) to emulate the precision.
+	pub f64: bool,
+	/// Compute shaders are supported.
+	pub compute: bool,
+	/// BC1, BC2 and BC3 (S3TC) compressed textures are supported.
+	pub s3tc: bool,
+	/// ETC2 compressed textures are supported.
+	pub etc2: bool,
+	/// ASTC compressed textures are supported.
+	pub astc: bool,
+}
+
+/// Device profile.
+///
+/// Selects a reduced pipeline on low-end devices based on the capabilities.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Profile {
+	Low,
+	Medium,
+	High,
+}
+
+impl Profile {
+	/// Selects the profile for the capabilities.
+	pub fn select(caps: &Caps) -> Profile {
+		if !caps.instancing || !caps.float_textures || caps.max_texture_size < 4096 {
+			Profile::Low
+		}
+		else if caps.max_texture_size < 8192 || caps.max_texture_units < 16 {
+			Profile::Medium
+		}
+		else {
+			Profile::High
+		}
+	}
+
+	/// Maximum width and height of textures loaded at this profile.
+	#[inline]
+	pub const fn max_texture_size(self) -> i32 {
+		match self {
+			Profile::Low => 1024,
+			Profile::Medium => 2048,
+			Profile::High => 4096,
+		}
+	}
+
+	/// Width and height of shadow maps, `0` disables shadows.
+	#[inline]
+	pub const fn shadow_map_size(self) -> i32 {
+		match self {
+			Profile::Low => 0,
+			Profile::Medium => 1024,
+			Profile::High => 2048,
+		}
+	}
+
+	/// Number of multisamples of the main surface.
+	#[inline]
+	pub const fn msaa_samples(self) -> i32 {
+		match self {
+			Profile::Low => 0,
+			Profile::Medium => 2,
+			Profile::High => 4,
+		}
+	}
+
+	/// Post processing effects are enabled.
+	#[inline]
+	pub const fn post_effects(self) -> bool {
+		!matches!(self, Profile::Low)
+	}
+}
+
+impl Caps {
+	/// Selects the device profile for the capabilities.
+	#[inline]
+	pub fn profile(&self) -> Profile {
+		Profile::select(self)
+	}
+
+	/// Returns if textures of the format are supported.
+	#[inline]
+	pub fn texture_format(&self, format: crate::TextureFormat) -> bool {
+		match format {
+			crate::TextureFormat::R8G8B8A8 => true,
+			crate::TextureFormat::R16G16B16A16F | crate::TextureFormat::R32G32B32A32F | crate::TextureFormat::R11G11B10F => self.float_textures,
+			crate::TextureFormat::BC1 | crate::TextureFormat::BC2 | crate::TextureFormat::BC3 => self.s3tc,
+			crate::TextureFormat::Etc2Rgb8 | crate::TextureFormat::Etc2Rgba8 => self.etc2,
+			crate::TextureFormat::Astc4x4 => self.astc,
+		}
+	}
+}
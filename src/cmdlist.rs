@@ -0,0 +1,179 @@
+use super::*;
+
+/// Reference to uniform data recorded in a [`CommandList`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct UniformRef(u32);
+
+struct UniformBlock {
+	layout: &'static UniformLayout,
+	count: usize,
+	data: ops::Range<usize>,
+}
+
+enum Command {
+	Clear(ClearArgs),
+	Draw(DrawArgs, Option<UniformRef>),
+	DrawIndexed(DrawIndexedArgs, Option<UniformRef>),
+}
+
+/// Command list.
+///
+/// Records draw calls without touching the graphics backend so it can be filled on worker threads.
+/// Uniform data is serialized into the list and uploaded when the list is submitted on the render thread.
+///
+/// # Example
+///
+/// ```rust
+/// use shade::{ClearArgs, CommandList};
+///
+/// let lists: Vec<CommandList> = std::thread::scope(|s| {
+///     let workers: Vec<_> = (0..4).map(|_| s.spawn(|| {
+///         let mut list = CommandList::new();
+///         list.clear_surface(ClearArgs { depth: Some(1.0), ..Default::default() });
+///         list
+///     })).collect();
+///     workers.into_iter().map(|w| w.join().unwrap()).collect()
+/// });
+///
+/// let mut frame = CommandList::new();
+/// for list in lists {
+///     frame.append(list);
+/// }
+/// assert_eq!(frame.len(), 4);
+/// ```
+pub struct CommandList {
+	commands: Vec<Command>,
+	blocks: Vec<UniformBlock>,
+	data: Vec<u8>,
+}
+
+impl Default for CommandList {
+	#[inline]
+	fn default() -> Self {
+		CommandList::new()
+	}
+}
+
+impl CommandList {
+	/// Creates a new empty command list.
+	pub fn new() -> CommandList {
+		CommandList {
+			commands: Vec::new(),
+			blocks: Vec::new(),
+			data: Vec::new(),
+		}
+	}
+
+	/// Returns the number of recorded commands.
+	#[inline]
+	pub fn len(&self) -> usize {
+		self.commands.len()
+	}
+
+	/// Returns true if there are no recorded commands.
+	#[inline]
+	pub fn is_empty(&self) -> bool {
+		self.commands.is_empty()
+	}
+
+	/// Records uniform data to be used by later draw calls.
+	pub fn uniforms<U: TUniform>(&mut self, data: &[U]) -> UniformRef {
+		let bytes = dataview::bytes(data);
+		let start = self.data.len();
+		self.data.extend_from_slice(bytes);
+		let index = self.blocks.len() as u32;
+		self.blocks.push(UniformBlock {
+			layout: U::UNIFORM_LAYOUT,
+			count: data.len(),
+			data: start..self.data.len(),
+		});
+		UniformRef(index)
+	}
+
+	/// Records clearing a surface.
+	#[inline]
+	pub fn clear_surface(&mut self, args: ClearArgs) {
+		self.commands.push(Command::Clear(args));
+	}
+
+	/// Records a draw call.
+	///
+	/// If uniforms are given they replace `args.uniforms` when the list is submitted.
+	#[inline]
+	pub fn draw(&mut self, args: DrawArgs, uniforms: Option<UniformRef>) {
+		self.commands.push(Command::Draw(args, uniforms));
+	}
+
+	/// Records an indexed draw call.
+	///
+	/// If uniforms are given they replace `args.uniforms` when the list is submitted.
+	#[inline]
+	pub fn draw_indexed(&mut self, args: DrawIndexedArgs, uniforms: Option<UniformRef>) {
+		self.commands.push(Command::DrawIndexed(args, uniforms));
+	}
+
+	/// Appends the commands of another list after the commands of this list.
+	pub fn append(&mut self, other: CommandList) {
+		let block_offset = self.blocks.len() as u32;
+		let data_offset = self.data.len();
+		let remap = |uniforms: Option<UniformRef>| uniforms.map(|UniformRef(index)| UniformRef(index + block_offset));
+
+		self.data.extend_from_slice(&other.data);
+		self.blocks.extend(other.blocks.into_iter().map(|block| UniformBlock {
+			data: block.data.start + data_offset..block.data.end + data_offset,
+			..block
+		}));
+		self.commands.extend(other.commands.into_iter().map(|cmd| match cmd {
+			Command::Clear(args) => Command::Clear(args),
+			Command::Draw(args, uniforms) => Command::Draw(args, remap(uniforms)),
+			Command::DrawIndexed(args, uniforms) => Command::DrawIndexed(args, remap(uniforms)),
+		}));
+	}
+
+	/// Submits all recorded commands in order, then clears the list.
+	///
+	/// Must be called on the thread owning the graphics context.
+	pub fn submit(&mut self, g: &mut Graphics) -> Result<(), GfxError> {
+		let mut buffers = Vec::with_capacity(self.blocks.len());
+		let mut result = self.upload(g, &mut buffers);
+		if result.is_ok() {
+			result = self.commands.iter_mut().try_for_each(|cmd| match cmd {
+				Command::Clear(args) => g.clear(args),
+				Command::Draw(args, uniforms) => {
+					if let Some(UniformRef(index)) = *uniforms {
+						args.uniforms = buffers[index as usize];
+					}
+					g.draw(args)
+				}
+				Command::DrawIndexed(args, uniforms) => {
+					if let Some(UniformRef(index)) = *uniforms {
+						args.uniforms = buffers[index as usize];
+					}
+					g.draw_indexed(args)
+				}
+			});
+		}
+		for ub in buffers {
+			g.uniform_buffer_delete(ub, true)?;
+		}
+		self.clear();
+		result
+	}
+
+	fn upload(&self, g: &mut Graphics, buffers: &mut Vec<UniformBuffer>) -> Result<(), GfxError> {
+		for block in &self.blocks {
+			let ub = IGraphics::uniform_buffer_create(&mut **g, None, block.layout, block.count)?;
+			buffers.push(ub);
+			IGraphics::uniform_buffer_set_data(&mut **g, ub, &self.data[block.data.clone()])?;
+		}
+		Ok(())
+	}
+
+	/// Clears the list without submitting.
+	#[inline]
+	pub fn clear(&mut self) {
+		self.commands.clear();
+		self.blocks.clear();
+		self.data.clear();
+	}
+}
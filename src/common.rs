@@ -1,12 +1,50 @@
 // use super::*;
 
 /// Primitive type.
+///
+/// For the strip, loop and fan primitive types an index of `u32::MAX` restarts the primitive in indexed draws.
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
 pub enum PrimType {
 	/// Triangles.
 	Triangles,
 	/// Lines.
 	Lines,
+	/// Points.
+	///
+	/// The point size is controlled by the vertex shader writing `gl_PointSize`.
+	Points,
+	/// Connected lines.
+	LineStrip,
+	/// Connected lines where the last vertex connects back to the first.
+	LineLoop,
+	/// Triangles sharing an edge with the previous triangle.
+	TriangleStrip,
+	/// Triangles sharing the first vertex.
+	TriangleFan,
+}
+
+impl PrimType {
+	/// Returns the number of vertices (or indices) required for a number of primitives.
+	#[inline]
+	pub const fn vertex_count(self, nprims: usize) -> usize {
+		if nprims == 0 {
+			return 0;
+		}
+		match self {
+			PrimType::Triangles => nprims * 3,
+			PrimType::Lines => nprims * 2,
+			PrimType::Points => nprims,
+			PrimType::LineStrip => nprims + 1,
+			PrimType::LineLoop => if nprims < 2 { 2 } else { nprims },
+			PrimType::TriangleStrip | PrimType::TriangleFan => nprims + 2,
+		}
+	}
+
+	/// Returns true if consecutive draws of this primitive type can be merged into a single draw.
+	#[inline]
+	pub const fn is_list(self) -> bool {
+		matches!(self, PrimType::Triangles | PrimType::Lines | PrimType::Points)
+	}
 }
 
 /// Blend mode.
@@ -29,6 +67,14 @@ pub enum BlendMode {
 	/// ```
 	Alpha,
 
+	/// Alpha blending with premultiplied alpha colors.
+	///
+	/// ```text
+	/// result[rgb] = src[rgb] + dest[rgb] * (1 - src[a])
+	/// result[a] = src[a] + dest[a] * (1 - src[a])
+	/// ```
+	PremultipliedAlpha,
+
 	/// Additive blending.
 	///
 	/// ```text
@@ -86,6 +132,23 @@ pub enum DepthTest {
 	Always,
 }
 
+/// Stencil mode.
+///
+/// Implements stencil-then-cover path filling: draw the path triangles with a fill rule mode, then cover them with [`Cover`](StencilMode::Cover).
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum StencilMode {
+	/// Count the winding of the triangles in the stencil buffer without writing color.
+	///
+	/// Front faces increment, back faces decrement. Used for the non-zero fill rule.
+	Winding,
+	/// Invert the stencil buffer without writing color.
+	///
+	/// Used for the even-odd fill rule.
+	Invert,
+	/// Draw where the stencil buffer is not zero and reset it to zero.
+	Cover,
+}
+
 /// Cull mode.
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
 pub enum CullMode {
@@ -95,6 +158,18 @@ pub enum CullMode {
 	CW,
 }
 
+/// Polygon fill mode.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub enum FillMode {
+	/// Fill the interior of polygons.
+	#[default]
+	Solid,
+	/// Draw only the edges of polygons.
+	Wireframe,
+	/// Draw only the vertices of polygons.
+	Points,
+}
+
 /// Buffer usage.
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
 pub enum BufferUsage {
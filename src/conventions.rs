@@ -0,0 +1,158 @@
+use std::sync::RwLock;
+
+/// World up axis.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub enum UpAxis {
+	/// Y is up, common for game engines and glTF.
+	#[default]
+	Y,
+	/// Z is up, common for CAD tools and STL models.
+	Z,
+}
+
+/// World coordinate conventions.
+///
+/// Set once with [`set_conventions`] and read by the camera helpers and when importing models,
+/// assets authored with different conventions are converted to the world conventions.
+///
+/// # Example
+///
+/// ```rust
+/// use shade::cvmath::Vec3;
+/// use shade::{Conventions, UpAxis};
+///
+/// let world = Conventions::default();
+/// let stl = Conventions { up_axis: UpAxis::Z, ..Conventions::default() };
+///
+/// // The up vector of the model is the up vector of the world
+/// assert_eq!(world.convert(&stl, Vec3(0.0, 0.0, 1.0)), Vec3(0.0, 1.0, 0.0));
+/// assert!(!world.flips_winding(&stl));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Conventions {
+	pub up_axis: UpAxis,
+	pub handedness: cvmath::Hand,
+	/// Depth range of the clip space.
+	pub clip: cvmath::Clip,
+}
+
+impl Default for Conventions {
+	#[inline]
+	fn default() -> Self {
+		Conventions::DEFAULT
+	}
+}
+
+static CONVENTIONS: RwLock<Conventions> = RwLock::new(Conventions::DEFAULT);
+
+/// Sets the global world conventions.
+pub fn set_conventions(conventions: Conventions) {
+	let mut guard = match CONVENTIONS.write() {
+		Ok(guard) => guard,
+		Err(err) => err.into_inner(),
+	};
+	*guard = conventions;
+}
+
+/// Returns the global world conventions.
+pub fn conventions() -> Conventions {
+	match CONVENTIONS.read() {
+		Ok(guard) => *guard,
+		Err(err) => *err.into_inner(),
+	}
+}
+
+impl Conventions {
+	/// Y-up right-handed world with the OpenGL `-1..1` depth range.
+	pub const DEFAULT: Conventions = Conventions {
+		up_axis: UpAxis::Y,
+		handedness: cvmath::RH,
+		clip: cvmath::NO,
+	};
+
+	/// Returns the up vector.
+	#[inline]
+	pub fn up(&self) -> cvmath::Vec3<f32> {
+		match self.up_axis {
+			UpAxis::Y => cvmath::Vec3::Y,
+			UpAxis::Z => cvmath::Vec3::Z,
+		}
+	}
+
+	/// Creates a perspective projection matrix with the field of view and the size of the viewport.
+	#[inline]
+	pub fn perspective_fov(&self, fov: cvmath::Deg<f32>, width: f32, height: f32, near: f32, far: f32) -> cvmath::Mat4<f32> {
+		cvmath::Mat4::perspective_fov(fov, width, height, near, far, (self.handedness, self.clip))
+	}
+
+	/// Creates a view matrix looking from the eye at the target, oriented by the up axis.
+	#[inline]
+	pub fn look_at(&self, eye: cvmath::Vec3<f32>, target: cvmath::Vec3<f32>) -> cvmath::Mat4<f32> {
+		cvmath::Mat4::look_at(eye, target, self.up(), self.handedness)
+	}
+
+	/// Creates a view matrix for relative-to-eye rendering, looking from the eye at the target.
+	///
+	/// The eye and target are in double precision world coordinates, the view matrix has the eye at the origin.
+	/// Place the models with [`relative_to_eye`] so the large world coordinates cancel out in double precision,
+	/// distant objects in planet-scale scenes do not jitter when the camera moves.
+	#[inline]
+	pub fn look_at_relative(&self, eye: cvmath::Vec3<f64>, target: cvmath::Vec3<f64>) -> cvmath::Mat4<f32> {
+		self.look_at(cvmath::Vec3::ZERO, relative_to_eye(target, eye))
+	}
+
+	/// Converts a position or direction authored with other conventions to these conventions.
+	///
+	/// The right axis is kept, the up axes are aligned and the forward axis is mirrored when the handedness differs.
+	pub fn convert(&self, from: &Conventions, v: cvmath::Vec3<f32>) -> cvmath::Vec3<f32> {
+		// Go through Y-up right-handed coordinates
+		let v = mirror(from, v);
+		let v = match from.up_axis {
+			UpAxis::Y => v,
+			UpAxis::Z => cvmath::Vec3(v.x, v.z, -v.y),
+		};
+		let v = match self.up_axis {
+			UpAxis::Y => v,
+			UpAxis::Z => cvmath::Vec3(v.x, -v.z, v.y),
+		};
+		mirror(self, v)
+	}
+
+	/// Returns if [`convert`](Self::convert) mirrors the geometry, the triangle winding must be reversed to keep the front faces.
+	#[inline]
+	pub fn flips_winding(&self, from: &Conventions) -> bool {
+		self.handedness != from.handedness
+	}
+}
+
+/// Returns the position relative to the eye in single precision.
+///
+/// Subtracts in double precision before converting, translate the model by the result and draw with [`Conventions::look_at_relative`].
+/// The precision of the result is highest close to the eye where it matters.
+///
+/// # Example
+///
+/// ```rust
+/// use shade::cvmath::Vec3;
+///
+/// let eye = Vec3(6378137.0, 0.0, 0.0);
+/// let position = Vec3(6378137.25, 1.5, 0.0);
+/// assert_eq!(shade::relative_to_eye(position, eye), Vec3(0.25, 1.5, 0.0));
+/// ```
+#[inline]
+pub fn relative_to_eye(position: cvmath::Vec3<f64>, eye: cvmath::Vec3<f64>) -> cvmath::Vec3<f32> {
+	let v = position - eye;
+	cvmath::Vec3(v.x as f32, v.y as f32, v.z as f32)
+}
+
+/// Mirrors the forward axis of left-handed coordinates.
+#[inline]
+fn mirror(conventions: &Conventions, v: cvmath::Vec3<f32>) -> cvmath::Vec3<f32> {
+	if conventions.handedness == cvmath::RH {
+		return v;
+	}
+	match conventions.up_axis {
+		UpAxis::Y => cvmath::Vec3(v.x, v.y, -v.z),
+		UpAxis::Z => cvmath::Vec3(v.x, -v.y, v.z),
+	}
+}
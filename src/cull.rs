@@ -0,0 +1,95 @@
+use cvmath::{Mat4, Vec3, Vec4};
+
+/// Returns if the box may be visible in the view frustum.
+///
+/// The corners of the box are transformed to clip space by the matrix, eg. `view_proj * model`.
+/// The test is conservative, the box is only culled when all its corners are outside the same clip plane.
+///
+/// # Examples
+///
+/// ```rust
+/// use shade::cvmath::{Mat4, Vec3};
+///
+/// assert!(shade::box_visible(&Mat4::IDENTITY, Vec3(-0.5, -0.5, -0.5), Vec3(0.5, 0.5, 0.5)));
+/// assert!(shade::box_visible(&Mat4::IDENTITY, Vec3(0.5, 0.5, 0.5), Vec3(2.0, 2.0, 2.0)));
+/// assert!(!shade::box_visible(&Mat4::IDENTITY, Vec3(2.0, -0.5, -0.5), Vec3(3.0, 0.5, 0.5)));
+/// ```
+pub fn box_visible(clip: &Mat4<f32>, mins: Vec3<f32>, maxs: Vec3<f32>) -> bool {
+	let mut outside = [true; 6];
+	for i in 0..8 {
+		let corner = Vec4(
+			if i & 1 != 0 { maxs.x } else { mins.x },
+			if i & 2 != 0 { maxs.y } else { mins.y },
+			if i & 4 != 0 { maxs.z } else { mins.z },
+			1.0,
+		);
+		let p = *clip * corner;
+		let planes = [p.x < -p.w, p.x > p.w, p.y < -p.w, p.y > p.w, p.z < -p.w, p.z > p.w];
+		for (outside, plane) in outside.iter_mut().zip(planes) {
+			*outside &= plane;
+		}
+	}
+	!outside.contains(&true)
+}
+
+/// Culls instances against the view frustum.
+///
+/// Compacts the visible instances of a mesh into an array every frame,
+/// upload it to the instance vertex buffer and draw as many instances as are visible.
+/// The array is reused across frames to avoid reallocating.
+pub struct InstanceCuller<T> {
+	visible: Vec<T>,
+	culled: usize,
+}
+
+impl<T> Default for InstanceCuller<T> {
+	#[inline]
+	fn default() -> Self {
+		InstanceCuller::new()
+	}
+}
+
+impl<T> InstanceCuller<T> {
+	/// Creates a new instance culler.
+	#[inline]
+	pub fn new() -> InstanceCuller<T> {
+		InstanceCuller { visible: Vec::new(), culled: 0 }
+	}
+
+	/// Returns the visible instances of the last cull.
+	#[inline]
+	pub fn visible(&self) -> &[T] {
+		&self.visible
+	}
+
+	/// Returns the number of visible instances of the last cull.
+	///
+	/// Pass as the number of instances to draw.
+	#[inline]
+	pub fn instances(&self) -> i32 {
+		self.visible.len() as i32
+	}
+
+	/// Returns the number of instances culled by the last cull.
+	#[inline]
+	pub fn culled(&self) -> usize {
+		self.culled
+	}
+}
+
+impl<T: Copy> InstanceCuller<T> {
+	/// Culls the instances and returns the visible ones.
+	///
+	/// The bounds of the mesh are shared by all instances, the model matrix of each instance places the bounds in the world.
+	/// The visible instances keep their order.
+	pub fn cull<F: Fn(&T) -> Mat4<f32>>(&mut self, view_proj: &Mat4<f32>, mins: Vec3<f32>, maxs: Vec3<f32>, instances: &[T], model: F) -> &[T] {
+		self.visible.clear();
+		for instance in instances {
+			if box_visible(&(*view_proj * model(instance)), mins, maxs) {
+				self.visible.push(*instance);
+			}
+		}
+		self.culled = instances.len() - self.visible.len();
+		&self.visible
+	}
+}
@@ -0,0 +1,103 @@
+use super::*;
+
+/// 3D point projected to the viewport.
+///
+/// Positions 2D elements such as nameplates, health bars and off-screen indicators relative to 3D objects.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ScreenAnchor {
+	/// Position in the viewport, clamped to the edge of the viewport when the point is off-screen.
+	pub position: Point2<f32>,
+	/// Unit direction from the center of the viewport towards the point.
+	///
+	/// Points an edge indicator at off-screen points, also for points behind the camera.
+	pub direction: Vec2<f32>,
+	/// The point is in front of the camera and inside the viewport.
+	pub on_screen: bool,
+	/// Normalized device depth of the point.
+	pub depth: f32,
+}
+
+impl ScreenAnchor {
+	/// Returns the rectangle of the given size placed with its pivot at the anchor.
+	///
+	/// The pivot is relative to the size of the rectangle, eg. `Vec2(0.5, 1.0)` places the bottom center at the anchor.
+	#[inline]
+	pub fn rect(&self, size: Vec2<f32>, pivot: Vec2<f32>) -> Rect<f32> {
+		let mins = self.position - size * pivot;
+		Rect::new(mins, mins + size)
+	}
+}
+
+/// Projects a 3D position to viewport coordinates.
+///
+/// The view projection matrix transforms the position to clip space, the viewport coordinates have their origin at the top left of the viewport with the Y axis pointing down.
+/// Returns `None` if the position is behind the camera.
+pub fn d3_to_d2(view_proj: &Mat4<f32>, viewport: &Rect<f32>, position: Vec3<f32>) -> Option<Point2<f32>> {
+	let clip = *view_proj * Vec4(position.x, position.y, position.z, 1.0);
+	if clip.w <= 0.0 {
+		return None;
+	}
+	Some(ndc_to_viewport(viewport, Vec2(clip.x / clip.w, clip.y / clip.w)))
+}
+
+/// Projects a 3D position to viewport coordinates, clamping off-screen positions to the edge of the viewport.
+///
+/// Off-screen positions are moved towards the center of the viewport until they are inside the viewport inset by the margin.
+///
+/// # Examples
+///
+/// ```rust
+/// use shade::cvmath::*;
+/// use shade::d2::d3_to_d2_clamped;
+///
+/// let viewport = Rect::c(0.0, 0.0, 200.0, 100.0);
+///
+/// let anchor = d3_to_d2_clamped(&Mat4::IDENTITY, &viewport, Vec3(0.5, 0.5, 0.0), 10.0);
+/// assert!(anchor.on_screen);
+/// assert_eq!(anchor.position, Point2(150.0, 25.0));
+///
+/// let anchor = d3_to_d2_clamped(&Mat4::IDENTITY, &viewport, Vec3(4.0, 0.0, 0.0), 10.0);
+/// assert!(!anchor.on_screen);
+/// assert_eq!(anchor.position, Point2(190.0, 50.0));
+/// assert_eq!(anchor.direction, Vec2(1.0, 0.0));
+/// ```
+pub fn d3_to_d2_clamped(view_proj: &Mat4<f32>, viewport: &Rect<f32>, position: Vec3<f32>, margin: f32) -> ScreenAnchor {
+	let clip = *view_proj * Vec4(position.x, position.y, position.z, 1.0);
+	let in_front = clip.w > 0.0;
+
+	// Divide by the absolute W to keep the side of points behind the camera
+	let w = f32::max(clip.w.abs(), f32::MIN_POSITIVE);
+	let ndc = Vec2(clip.x / w, clip.y / w);
+	let on_screen = in_front && ndc.x.abs() <= 1.0 && ndc.y.abs() <= 1.0;
+
+	let center = (viewport.mins + viewport.maxs) * 0.5;
+	let point = ndc_to_viewport(viewport, ndc);
+	let offset = point - center;
+	let len = offset.len();
+	// Points right behind the camera are indicated at the bottom
+	let direction = if len > 0.0 { offset / len } else { Vec2(0.0, 1.0) };
+
+	let position = if on_screen {
+		point
+	}
+	else {
+		let half = Vec2(
+			f32::max((viewport.maxs.x - viewport.mins.x) * 0.5 - margin, 0.0),
+			f32::max((viewport.maxs.y - viewport.mins.y) * 0.5 - margin, 0.0),
+		);
+		// Scale the direction to hit the nearest edge of the inset viewport
+		let tx = if direction.x != 0.0 { half.x / direction.x.abs() } else { f32::INFINITY };
+		let ty = if direction.y != 0.0 { half.y / direction.y.abs() } else { f32::INFINITY };
+		center + direction * f32::min(tx, ty)
+	};
+
+	ScreenAnchor { position, direction, on_screen, depth: clip.z / w }
+}
+
+#[inline]
+fn ndc_to_viewport(viewport: &Rect<f32>, ndc: Vec2<f32>) -> Point2<f32> {
+	Point2(
+		viewport.mins.x + (ndc.x + 1.0) * 0.5 * (viewport.maxs.x - viewport.mins.x),
+		viewport.mins.y + (1.0 - ndc.y) * 0.5 * (viewport.maxs.y - viewport.mins.y),
+	)
+}
@@ -0,0 +1,104 @@
+use super::*;
+
+impl<V: TVertex + Lerp, U: TUniform> CommandBuffer<V, U> {
+	/// Stamps a rectangle clipped to a convex shape.
+	///
+	/// The vertex attributes are interpolated across the rectangle so the sprite is cut out rather than squashed.
+	/// The clip shape may have either winding order.
+	#[inline(never)]
+	pub fn stamp_rect_clip_convex<T: ToVertex<V>>(&mut self, stamp: &Stamp<T>, rc: &Rect<f32>, clip: &[Point2<f32>]) {
+		let mut pts = Vec::new();
+		clip_convex(&rect_contour(rc), clip, &mut pts);
+		self.stamp_clipped(stamp, rc, &pts);
+	}
+
+	/// Stamps a rectangle clipped to a polygon shape.
+	///
+	/// The polygon is given as a triangulation of its points, allowing concave shapes and holes.
+	#[inline(never)]
+	pub fn stamp_rect_clip_polygon<T: ToVertex<V>>(&mut self, stamp: &Stamp<T>, rc: &Rect<f32>, clip: &[Point2<f32>], triangles: &[(u32, u32, u32)]) {
+		let subject = rect_contour(rc);
+		let mut pts = Vec::new();
+		for &(p1, p2, p3) in triangles {
+			// Must be indices into the points slice
+			let triangle = [clip[p1 as usize], clip[p2 as usize], clip[p3 as usize]];
+			clip_convex(&subject, &triangle, &mut pts);
+			self.stamp_clipped(stamp, rc, &pts);
+		}
+	}
+
+	fn stamp_clipped<T: ToVertex<V>>(&mut self, stamp: &Stamp<T>, rc: &Rect<f32>, pts: &[Point2<f32>]) {
+		if pts.len() < 3 {
+			return;
+		}
+
+		let bottom_left = stamp.bottom_left.to_vertex(rc.bottom_left(), 0);
+		let top_left = stamp.top_left.to_vertex(rc.top_left(), 1);
+		let top_right = stamp.top_right.to_vertex(rc.top_right(), 2);
+		let bottom_right = stamp.bottom_right.to_vertex(rc.bottom_right(), 3);
+
+		// Bilinear coordinates relative to the top left corner
+		let origin = rc.top_left();
+		let extent = rc.bottom_right() - origin;
+
+		// n vertices, n - 2 primitives
+		let n = pts.len();
+		let mut cv = self.begin(PrimType::Triangles, n, n - 2);
+		for i in 1..n - 1 {
+			cv.add_index3(0, i as u32, i as u32 + 1);
+		}
+		for &pt in pts {
+			let u = if extent.x != 0.0 { (pt.x - origin.x) / extent.x } else { 0.0 };
+			let v = if extent.y != 0.0 { (pt.y - origin.y) / extent.y } else { 0.0 };
+			let top = top_left.lerp(&top_right, u);
+			let bottom = bottom_left.lerp(&bottom_right, u);
+			cv.add_vertex(top.lerp(&bottom, v));
+		}
+	}
+}
+
+fn rect_contour(rc: &Rect<f32>) -> [Point2<f32>; 4] {
+	[rc.bottom_left(), rc.top_left(), rc.top_right(), rc.bottom_right()]
+}
+
+/// Clips the subject polygon against a convex clip polygon.
+///
+/// Sutherland-Hodgman, the result is empty if the polygons do not overlap.
+fn clip_convex(subject: &[Point2<f32>], clip: &[Point2<f32>], out: &mut Vec<Point2<f32>>) {
+	out.clear();
+	if clip.len() < 3 {
+		return;
+	}
+	out.extend_from_slice(subject);
+
+	// Orient the edge tests by the winding order of the clip shape
+	let mut area = 0.0;
+	for i in 0..clip.len() {
+		let (a, b) = (clip[i], clip[(i + 1) % clip.len()]);
+		area += a.x * b.y - b.x * a.y;
+	}
+	let sign = if area < 0.0 { -1.0 } else { 1.0 };
+
+	let mut input = Vec::new();
+	for i in 0..clip.len() {
+		let (a, b) = (clip[i], clip[(i + 1) % clip.len()]);
+		let dist = |p: Point2<f32>| sign * ((b.x - a.x) * (p.y - a.y) - (b.y - a.y) * (p.x - a.x));
+
+		std::mem::swap(out, &mut input);
+		out.clear();
+		for j in 0..input.len() {
+			let (p, q) = (input[j], input[(j + 1) % input.len()]);
+			let (dp, dq) = (dist(p), dist(q));
+			if dp >= 0.0 {
+				out.push(p);
+			}
+			// Points exactly on the edge are kept above, avoid emitting them twice
+			if dp > 0.0 && dq < 0.0 || dp < 0.0 && dq > 0.0 {
+				out.push(p + (q - p) * (dp / (dp - dq)));
+			}
+		}
+		if out.is_empty() {
+			return;
+		}
+	}
+}
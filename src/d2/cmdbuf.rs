@@ -2,16 +2,19 @@ use std::mem;
 
 use super::*;
 
+#[derive(Copy, Clone)]
 pub(super) struct Command {
 	prim_type: PrimType,
 	blend_mode: BlendMode,
+	stencil: Option<StencilMode>,
 	scissor_test: Option<cvmath::Rect<i32>>,
-	shader: Shader,
-	vertex_start: u32,
-	vertex_end: u32,
+	pub(super) shader: Shader,
+	pub(super) vertex_start: u32,
+	pub(super) vertex_end: u32,
 	index_start: u32,
 	index_end: u32,
-	uniform_index: u32,
+	pub(super) uniform_index: u32,
+	user_data: u32,
 }
 
 /// Command buffer.
@@ -22,11 +25,34 @@ pub struct CommandBuffer<V, U> {
 	pub(super) commands: Vec<Command>,
 
 	pub blend_mode: BlendMode,
+	pub stencil: Option<StencilMode>,
 	pub shader: Shader,
 	pub viewport: Rect<i32>,
 	pub scissor_test: Option<cvmath::Rect<i32>>,
 	pub depth_test: Option<DepthTest>,
 	pub cull_mode: Option<CullMode>,
+	pub fill_mode: FillMode,
+	pub alpha_to_coverage: bool,
+	/// User data of the following commands, passed to the draw hook.
+	///
+	/// See [`draw_with_hook`](CommandBuffer::draw_with_hook).
+	pub user_data: u32,
+	/// Display generation the contents were laid out for.
+	///
+	/// See [`depend_on_display`](CommandBuffer::depend_on_display).
+	pub display_generation: Option<u32>,
+	/// Maximum distance between a curve and its line segments.
+	///
+	/// Curve methods compute the number of segments from the tolerance when given zero or fewer segments.
+	/// The tolerance is in vertex coordinates, divide the pixel error by the scale of the transform.
+	pub tolerance: f32,
+}
+
+impl<V: TVertex, U: TUniform> Default for CommandBuffer<V, U> {
+	#[inline]
+	fn default() -> Self {
+		CommandBuffer::new()
+	}
 }
 
 impl<V: TVertex, U: TUniform> CommandBuffer<V, U> {
@@ -39,11 +65,17 @@ impl<V: TVertex, U: TUniform> CommandBuffer<V, U> {
 			commands: Vec::new(),
 
 			blend_mode: BlendMode::Solid,
+			stencil: None,
 			shader: Shader::INVALID,
 			viewport: Rect::ZERO,
 			scissor_test: None,
 			depth_test: None,
 			cull_mode: None,
+			fill_mode: FillMode::Solid,
+			alpha_to_coverage: false,
+			user_data: 0,
+			display_generation: None,
+			tolerance: 0.25,
 		}
 	}
 
@@ -54,45 +86,110 @@ impl<V: TVertex, U: TUniform> CommandBuffer<V, U> {
 		self.uniforms.clear();
 		self.commands.clear();
 		self.blend_mode = BlendMode::Solid;
+		self.stencil = None;
 		self.shader = Shader::INVALID;
 		self.viewport = Rect::ZERO;
 		self.scissor_test = None;
 		self.depth_test = None;
 		self.cull_mode = None;
+		self.fill_mode = FillMode::Solid;
+		self.alpha_to_coverage = false;
+		self.user_data = 0;
+		self.display_generation = None;
+		self.tolerance = 0.25;
+	}
+
+	/// Makes the contents depend on the size and scale of the display.
+	///
+	/// Retained text and UI laid out for the display become stale when the window is resized or its DPI changes.
+	#[inline]
+	pub fn depend_on_display(&mut self, g: &Graphics) {
+		self.display_generation = Some(g.display_info().generation);
+	}
+
+	/// Returns if the display changed since the contents were laid out.
+	///
+	/// Clear and rebuild stale buffers before drawing them.
+	#[inline]
+	pub fn is_stale(&self, g: &Graphics) -> bool {
+		self.display_generation.is_some_and(|generation| generation != g.display_info().generation)
+	}
+
+	/// Returns the number of segments for an elliptic arc.
+	///
+	/// If the segments are zero or fewer they are computed from the radii, the sweep in radians and the tolerance.
+	pub fn arc_segments(&self, segments: i32, radius: Vec2<f32>, sweep: f32) -> i32 {
+		if segments > 0 {
+			return segments;
+		}
+		let radius = f32::max(radius.x.abs(), radius.y.abs());
+		let tolerance = f32::max(self.tolerance, 1e-3);
+		if radius <= tolerance {
+			return 1;
+		}
+		// Angle subtended by a chord with the tolerance as its sagitta
+		let step = 2.0 * (1.0 - tolerance / radius).acos();
+		f32::min(1024.0, (sweep.abs() / step).ceil()) as i32
+	}
+
+	/// Returns the number of segments for a quadratic or cubic bezier curve.
+	///
+	/// If the segments are zero or fewer they are computed from the control points and the tolerance.
+	pub fn bezier_segments(&self, segments: i32, pts: &[Point2<f32>]) -> i32 {
+		if segments > 0 {
+			return segments;
+		}
+		// Wang's formula: bound the second differences of the control points
+		let degree = pts.len() as f32 - 1.0;
+		let mut dd = 0.0f32;
+		for w in pts.windows(3) {
+			let d = w[0] - w[1] * 2.0 + w[2];
+			dd = f32::max(dd, (d.x * d.x + d.y * d.y).sqrt());
+		}
+		let tolerance = f32::max(self.tolerance, 1e-3);
+		let n = (degree * (degree - 1.0) * 0.125 * dd / tolerance).sqrt().ceil();
+		n.clamp(1.0, 1024.0) as i32
 	}
 
 	/// Draws the command buffer.
 	pub fn draw(&self, g: &mut Graphics, surface: Surface) -> Result<(), GfxError> {
-		let vb = g.vertex_buffer(None, &self.vertices, BufferUsage::Static)?;
-		let ib = g.index_buffer(None, &self.indices, BufferUsage::Static)?;
-		let ub = g.uniform_buffer(None, &self.uniforms)?;
+		self.draw_commands(g, surface, |_| true, |_, _| Ok(()))
+	}
 
-		for cmd in &self.commands {
-			g.draw_indexed(&DrawIndexedArgs {
-				surface,
-				viewport: self.viewport,
-				scissor: self.scissor_test,
-				blend_mode: cmd.blend_mode,
-				depth_test: self.depth_test,
-				cull_mode: self.cull_mode,
-				prim_type: cmd.prim_type,
-				shader: cmd.shader,
-				vertices: vb,
-				indices: ib,
-				uniforms: ub,
-				vertex_start: cmd.vertex_start,
-				vertex_end: cmd.vertex_end,
-				index_start: cmd.index_start,
-				index_end: cmd.index_end,
-				uniform_index: cmd.uniform_index,
-				instances: -1,
-			})?;
-		}
+	/// Draws the command buffer calling the hook with the user data before every draw call.
+	///
+	/// The hook injects dynamic uniforms without rebuilding the command buffer, eg. by binding a uniform block.
+	pub fn draw_with_hook<H: FnMut(&mut Graphics, u32) -> Result<(), GfxError>>(&self, g: &mut Graphics, surface: Surface, hook: H) -> Result<(), GfxError> {
+		self.draw_commands(g, surface, |_| true, hook)
+	}
 
-		g.uniform_buffer_delete(ub, true)?;
-		g.index_buffer_delete(ib, true)?;
-		g.vertex_buffer_delete(vb, true)?;
-		Ok(())
+	fn draw_commands<F: FnMut(&Command) -> bool, H: FnMut(&mut Graphics, u32) -> Result<(), GfxError>>(&self, g: &mut Graphics, surface: Surface, visible: F, hook: H) -> Result<(), GfxError> {
+		let prepared = self.upload(g)?;
+		let result = prepared.draw_commands(g, surface, visible, hook);
+		prepared.delete(g)?;
+		result
+	}
+
+	/// Uploads the vertices, indices and uniforms to the GPU without drawing.
+	///
+	/// Upload many command buffers together early in the frame and draw the prepared buffers later,
+	/// this avoids interleaving buffer creation with draw calls. The command buffer can be cleared and reused after uploading.
+	pub fn upload(&self, g: &mut Graphics) -> Result<PreparedDraw, GfxError> {
+		let vertices = g.vertex_buffer(None, &self.vertices, BufferUsage::Static)?;
+		let indices = g.index_buffer(None, &self.indices, BufferUsage::Static)?;
+		let uniforms = g.uniform_buffer(None, &self.uniforms)?;
+		Ok(PreparedDraw {
+			vertices,
+			indices,
+			uniforms,
+			commands: self.commands.clone(),
+			viewport: self.viewport,
+			scissor: self.scissor_test,
+			depth_test: self.depth_test,
+			cull_mode: self.cull_mode,
+			fill_mode: self.fill_mode,
+			alpha_to_coverage: self.alpha_to_coverage,
+		})
 	}
 
 	/// Gets the current uniform.
@@ -124,16 +221,19 @@ impl<V: TVertex, U: TUniform> CommandBuffer<V, U> {
 			self.uniforms.push(U::default());
 		}
 
-		let nindices = nprims * match prim_type { PrimType::Triangles => 3, PrimType::Lines => 2, };
+		let nindices = prim_type.vertex_count(nprims);
 
 		// Check if the new command can be merged with the last command.
 		let mut new_cmd = true;
 		if let Some(last) = self.commands.last_mut() {
 			let compatible =
+				prim_type.is_list() &&
 				last.shader == self.shader &&
 				last.prim_type == prim_type &&
 				last.blend_mode == self.blend_mode &&
+				last.stencil == self.stencil &&
 				last.scissor_test == self.scissor_test &&
+				last.user_data == self.user_data &&
 				last.uniform_index + 1 == self.uniforms.len() as u32;
 			if compatible {
 				last.vertex_end += nverts as u32;
@@ -145,6 +245,7 @@ impl<V: TVertex, U: TUniform> CommandBuffer<V, U> {
 		// Otherwise add a new command.
 		if new_cmd {
 			let blend_mode = self.blend_mode;
+			let stencil = self.stencil;
 			let scissor_test = self.scissor_test;
 			let shader = self.shader;
 			let vertex_start = self.vertices.len() as u32;
@@ -152,7 +253,8 @@ impl<V: TVertex, U: TUniform> CommandBuffer<V, U> {
 			let index_start = self.indices.len() as u32;
 			let index_end = index_start + nindices as u32;
 			let uniform_index = self.uniforms.len() as u32 - 1;
-			self.commands.push(Command { prim_type, blend_mode, scissor_test, shader, vertex_start, vertex_end, index_start, index_end, uniform_index });
+			let user_data = self.user_data;
+			self.commands.push(Command { prim_type, blend_mode, stencil, scissor_test, shader, vertex_start, vertex_end, index_start, index_end, uniform_index, user_data });
 		}
 
 		let vertex_start = self.vertices.len();
@@ -171,6 +273,36 @@ impl<V: TVertex, U: TUniform> CommandBuffer<V, U> {
 	}
 }
 
+impl<V: TVertex + Position, U: TUniform> CommandBuffer<V, U> {
+	/// Returns the bounding box of the vertices of every command.
+	///
+	/// Commands without vertices have an empty bounding box with `mins` greater than `maxs`.
+	pub fn commands_bounds(&self) -> Vec<Rect<f32>> {
+		self.commands.iter().map(|cmd| self.command_bounds(cmd)).collect()
+	}
+
+	fn command_bounds(&self, cmd: &Command) -> Rect<f32> {
+		let empty = Rect::new(Vec2::dup(f32::INFINITY), Vec2::dup(f32::NEG_INFINITY));
+		self.vertices[cmd.vertex_start as usize..cmd.vertex_end as usize].iter().fold(empty, |rc, v| {
+			let pos = v.position();
+			Rect::new(
+				Vec2(f32::min(rc.mins.x, pos.x), f32::min(rc.mins.y, pos.y)),
+				Vec2(f32::max(rc.maxs.x, pos.x), f32::max(rc.maxs.y, pos.y)))
+		})
+	}
+
+	/// Draws the command buffer skipping commands entirely outside the visible rectangle.
+	///
+	/// The visible rectangle is in the same coordinate space as the vertex positions.
+	pub fn draw_culled(&self, g: &mut Graphics, surface: Surface, visible: &Rect<f32>) -> Result<(), GfxError> {
+		self.draw_commands(g, surface, |cmd| {
+			let rc = self.command_bounds(cmd);
+			rc.mins.x <= visible.maxs.x && rc.maxs.x >= visible.mins.x &&
+			rc.mins.y <= visible.maxs.y && rc.maxs.y >= visible.mins.y
+		}, |_, _| Ok(()))
+	}
+}
+
 /// Builder for adding vertices and indices to a command buffer.
 pub struct PrimBuilder<'a, V: TVertex> {
 	vertices: &'a mut [V],
@@ -185,12 +317,12 @@ impl<'a, V: TVertex> PrimBuilder<'a, V> {
 	pub fn add_index(&mut self, vertex: u32) {
 		#[cfg(debug_assertions)]
 		{
-			assert!(self.indices.len() >= 1, "too many indices");
-			assert!((vertex as usize) < self.nvertices, "vertex index ({}) out of bounds ({} vertices)", vertex, self.nvertices);
+			assert!(!self.indices.is_empty(), "too many indices");
+			assert!(vertex == u32::MAX || (vertex as usize) < self.nvertices, "vertex index ({}) out of bounds ({} vertices)", vertex, self.nvertices);
 		}
 
-		let (head, tail) = mem::replace(&mut self.indices, &mut []).split_at_mut(1);
-		head[0] += vertex;
+		let (head, tail) = mem::take(&mut self.indices).split_at_mut(1);
+		rebase(&mut head[0], vertex);
 		self.indices = tail;
 	}
 
@@ -200,13 +332,13 @@ impl<'a, V: TVertex> PrimBuilder<'a, V> {
 		#[cfg(debug_assertions)]
 		{
 			assert!(self.indices.len() >= 2, "too many indices");
-			assert!((vertex1 as usize) < self.nvertices, "vertex index ({}) out of bounds ({} vertices)", vertex1, self.nvertices);
-			assert!((vertex2 as usize) < self.nvertices, "vertex index ({}) out of bounds ({} vertices)", vertex2, self.nvertices);
+			assert!(vertex1 == u32::MAX || (vertex1 as usize) < self.nvertices, "vertex index ({}) out of bounds ({} vertices)", vertex1, self.nvertices);
+			assert!(vertex2 == u32::MAX || (vertex2 as usize) < self.nvertices, "vertex index ({}) out of bounds ({} vertices)", vertex2, self.nvertices);
 		}
 
-		let (head, tail) = mem::replace(&mut self.indices, &mut []).split_at_mut(2);
-		head[0] += vertex1;
-		head[1] += vertex2;
+		let (head, tail) = mem::take(&mut self.indices).split_at_mut(2);
+		rebase(&mut head[0], vertex1);
+		rebase(&mut head[1], vertex2);
 		self.indices = tail;
 	}
 
@@ -216,32 +348,34 @@ impl<'a, V: TVertex> PrimBuilder<'a, V> {
 		#[cfg(debug_assertions)]
 		{
 			assert!(self.indices.len() >= 3, "too many indices");
-			assert!((vertex1 as usize) < self.nvertices, "vertex index ({}) out of bounds ({} vertices)", vertex1, self.nvertices);
-			assert!((vertex2 as usize) < self.nvertices, "vertex index ({}) out of bounds ({} vertices)", vertex2, self.nvertices);
-			assert!((vertex3 as usize) < self.nvertices, "vertex index ({}) out of bounds ({} vertices)", vertex3, self.nvertices);
+			assert!(vertex1 == u32::MAX || (vertex1 as usize) < self.nvertices, "vertex index ({}) out of bounds ({} vertices)", vertex1, self.nvertices);
+			assert!(vertex2 == u32::MAX || (vertex2 as usize) < self.nvertices, "vertex index ({}) out of bounds ({} vertices)", vertex2, self.nvertices);
+			assert!(vertex3 == u32::MAX || (vertex3 as usize) < self.nvertices, "vertex index ({}) out of bounds ({} vertices)", vertex3, self.nvertices);
 		}
 
-		let (head, tail) = mem::replace(&mut self.indices, &mut []).split_at_mut(3);
-		head[0] += vertex1;
-		head[1] += vertex2;
-		head[2] += vertex3;
+		let (head, tail) = mem::take(&mut self.indices).split_at_mut(3);
+		rebase(&mut head[0], vertex1);
+		rebase(&mut head[1], vertex2);
+		rebase(&mut head[2], vertex3);
 		self.indices = tail;
 	}
 
 	/// Adds vertex indices to the command buffer.
+	///
+	/// An index of `u32::MAX` restarts strip, loop and fan primitives, reserve an index for every restart.
 	#[track_caller]
 	pub fn add_indices(&mut self, indices: &[u32]) {
 		#[cfg(debug_assertions)]
 		{
 			assert!(self.indices.len() >= indices.len(), "too many indices");
 			for &index in indices {
-				assert!((index as usize) < self.nvertices, "vertex index ({}) out of bounds ({} vertices)", index, self.nvertices);
+				assert!(index == u32::MAX || (index as usize) < self.nvertices, "vertex index ({}) out of bounds ({} vertices)", index, self.nvertices);
 			}
 		}
 
-		let (head, tail) = mem::replace(&mut self.indices, &mut []).split_at_mut(indices.len());
-		for i in 0..indices.len() {
-			head[i] += indices[i];
+		let (head, tail) = mem::take(&mut self.indices).split_at_mut(indices.len());
+		for (head, &index) in head.iter_mut().zip(indices) {
+			rebase(head, index);
 		}
 		self.indices = tail;
 	}
@@ -263,10 +397,10 @@ impl<'a, V: TVertex> PrimBuilder<'a, V> {
 	pub fn add_vertex(&mut self, vertex: V) {
 		#[cfg(debug_assertions)]
 		{
-			assert!(self.vertices.len() >= 1, "too many vertices");
+			assert!(!self.vertices.is_empty(), "too many vertices");
 		}
 
-		let (head, tail) = mem::replace(&mut self.vertices, &mut []).split_at_mut(1);
+		let (head, tail) = mem::take(&mut self.vertices).split_at_mut(1);
 		head[0] = vertex;
 		self.vertices = tail;
 	}
@@ -279,12 +413,18 @@ impl<'a, V: TVertex> PrimBuilder<'a, V> {
 			assert!(self.vertices.len() >= vertices.len(), "too many vertices");
 		}
 
-		let (head, tail) = mem::replace(&mut self.vertices, &mut []).split_at_mut(vertices.len());
+		let (head, tail) = mem::take(&mut self.vertices).split_at_mut(vertices.len());
 		head.copy_from_slice(vertices);
 		self.vertices = tail;
 	}
 }
 
+/// Offsets the vertex index by the base vertex, the primitive restart index `u32::MAX` is passed through unchanged.
+#[inline]
+fn rebase(base: &mut u32, index: u32) {
+	*base = if index == u32::MAX { u32::MAX } else { *base + index };
+}
+
 #[cfg(debug_assertions)]
 impl<'a, V: TVertex> Drop for PrimBuilder<'a, V> {
 	#[track_caller]
@@ -293,3 +433,83 @@ impl<'a, V: TVertex> Drop for PrimBuilder<'a, V> {
 		assert!(self.vertices.is_empty(), "expected more vertices, {} left", self.vertices.len());
 	}
 }
+
+/// Command buffer uploaded to the GPU.
+///
+/// Created with [`CommandBuffer::upload`], draw it any number of times and delete it when done.
+pub struct PreparedDraw {
+	vertices: VertexBuffer,
+	indices: IndexBuffer,
+	uniforms: UniformBuffer,
+	commands: Vec<Command>,
+	pub viewport: Rect<i32>,
+	pub scissor: Option<cvmath::Rect<i32>>,
+	pub depth_test: Option<DepthTest>,
+	pub cull_mode: Option<CullMode>,
+	pub fill_mode: FillMode,
+	pub alpha_to_coverage: bool,
+}
+
+impl PreparedDraw {
+	/// Returns the number of draw calls.
+	#[inline]
+	pub fn len(&self) -> usize {
+		self.commands.len()
+	}
+
+	/// Returns true if there is nothing to draw.
+	#[inline]
+	pub fn is_empty(&self) -> bool {
+		self.commands.is_empty()
+	}
+
+	/// Draws the prepared buffers.
+	pub fn draw(&self, g: &mut Graphics, surface: Surface) -> Result<(), GfxError> {
+		self.draw_commands(g, surface, |_| true, |_, _| Ok(()))
+	}
+
+	/// Draws the prepared buffers calling the hook with the user data before every draw call.
+	///
+	/// See [`CommandBuffer::draw_with_hook`].
+	pub fn draw_with_hook<H: FnMut(&mut Graphics, u32) -> Result<(), GfxError>>(&self, g: &mut Graphics, surface: Surface, hook: H) -> Result<(), GfxError> {
+		self.draw_commands(g, surface, |_| true, hook)
+	}
+
+	fn draw_commands<F: FnMut(&Command) -> bool, H: FnMut(&mut Graphics, u32) -> Result<(), GfxError>>(&self, g: &mut Graphics, surface: Surface, mut visible: F, mut hook: H) -> Result<(), GfxError> {
+		for cmd in self.commands.iter().filter(|cmd| visible(cmd)) {
+			hook(g, cmd.user_data)?;
+			g.draw_indexed(&DrawIndexedArgs {
+				surface,
+				viewport: self.viewport,
+				scissor: self.scissor,
+				blend_mode: cmd.blend_mode,
+				depth_test: self.depth_test,
+				cull_mode: self.cull_mode,
+				fill_mode: self.fill_mode,
+				alpha_to_coverage: self.alpha_to_coverage,
+				stencil: cmd.stencil,
+				prim_type: cmd.prim_type,
+				shader: cmd.shader,
+				vertices: self.vertices,
+				indices: self.indices,
+				uniforms: self.uniforms,
+				vertex_start: cmd.vertex_start,
+				vertex_end: cmd.vertex_end,
+				index_start: cmd.index_start,
+				index_end: cmd.index_end,
+				base_vertex: 0,
+				uniform_index: cmd.uniform_index,
+				instances: -1,
+			})?;
+		}
+		Ok(())
+	}
+
+	/// Releases the GPU buffers.
+	pub fn delete(self, g: &mut Graphics) -> Result<(), GfxError> {
+		g.uniform_buffer_delete(self.uniforms, true)?;
+		g.index_buffer_delete(self.indices, true)?;
+		g.vertex_buffer_delete(self.vertices, true)?;
+		Ok(())
+	}
+}
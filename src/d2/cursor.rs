@@ -0,0 +1,62 @@
+use super::*;
+
+/// Image of a software cursor state.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CursorImage<T> {
+	/// Stamp with the texture coordinates of the image.
+	pub stamp: Stamp<T>,
+	/// Size of the image.
+	pub size: Vec2<f32>,
+	/// Point of the image placed at the cursor position, relative to its top left corner.
+	pub hotspot: Vec2<f32>,
+}
+
+/// Software cursor.
+///
+/// Hide the OS cursor and draw this cursor as the last draw of the frame.
+/// Every state (e.g. arrow, hand, text beam) has its own image.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cursor<T> {
+	/// Images per cursor state.
+	pub images: Vec<CursorImage<T>>,
+	/// Index of the current state.
+	pub state: usize,
+	/// Position of the cursor.
+	pub position: Point2<f32>,
+	/// Scale of the images.
+	pub scale: f32,
+	/// Draw the cursor.
+	pub visible: bool,
+}
+
+impl<T> Cursor<T> {
+	/// Creates a new cursor with the images per state.
+	pub fn new(images: Vec<CursorImage<T>>) -> Cursor<T> {
+		Cursor {
+			images,
+			state: 0,
+			position: Point2::ZERO,
+			scale: 1.0,
+			visible: true,
+		}
+	}
+
+	/// Returns the rectangle covered by the cursor image.
+	pub fn rect(&self) -> Option<Rect<f32>> {
+		let image = self.images.get(self.state)?;
+		let mins = self.position - image.hotspot * self.scale;
+		Some(Rect::new(mins, mins + image.size * self.scale))
+	}
+}
+
+impl<V: TVertex, U: TUniform> CommandBuffer<V, U> {
+	/// Draws a software cursor.
+	pub fn draw_cursor<T: ToVertex<V>>(&mut self, cursor: &Cursor<T>) {
+		if !cursor.visible {
+			return;
+		}
+		if let (Some(image), Some(rc)) = (cursor.images.get(cursor.state), cursor.rect()) {
+			self.stamp_rect(&image.stamp, &rc);
+		}
+	}
+}
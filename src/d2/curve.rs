@@ -1,7 +1,14 @@
+/*!
+Curve evaluation and editing.
+*/
+
 use super::*;
 
+/// Evaluates a quadratic bezier curve, note that `x` runs from `1.0` (at p1) to `0.0` (at p3).
+///
+/// Use [`Bezier2::eval`] for the conventional parameterization.
 #[inline]
-pub fn bezier2(x: f32, p1: Point2<f32>, p2: Point2<f32>, p3: Point2<f32>) -> Point2<f32> {
+pub(crate) fn bezier2(x: f32, p1: Point2<f32>, p2: Point2<f32>, p3: Point2<f32>) -> Point2<f32> {
 	let s = x;
 	let t = 1.0 - s;
 	let term1 = p1 * (s * s);
@@ -10,8 +17,11 @@ pub fn bezier2(x: f32, p1: Point2<f32>, p2: Point2<f32>, p3: Point2<f32>) -> Poi
 	term1 + term2 + term3
 }
 
+/// Evaluates a cubic bezier curve, note that `x` runs from `1.0` (at p1) to `0.0` (at p4).
+///
+/// Use [`Bezier3::eval`] for the conventional parameterization.
 #[inline]
-pub fn bezier3(x: f32, p1: Point2<f32>, p2: Point2<f32>, p3: Point2<f32>, p4: Point2<f32>) -> Point2<f32> {
+pub(crate) fn bezier3(x: f32, p1: Point2<f32>, p2: Point2<f32>, p3: Point2<f32>, p4: Point2<f32>) -> Point2<f32> {
 	let s = x;
 	let t = 1.0 - s;
 	let term1 = p1 * (s * s * s);
@@ -20,3 +30,218 @@ pub fn bezier3(x: f32, p1: Point2<f32>, p2: Point2<f32>, p3: Point2<f32>, p4: Po
 	let term4 = p4 * (t * t * t);
 	term1 + term2 + term3 + term4
 }
+
+/// Quadratic bezier curve.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Bezier2 {
+	pub p0: Point2<f32>,
+	pub p1: Point2<f32>,
+	pub p2: Point2<f32>,
+}
+
+impl Bezier2 {
+	/// Evaluates the curve at `t` between `0.0` (at p0) and `1.0` (at p2).
+	#[inline]
+	pub fn eval(&self, t: f32) -> Point2<f32> {
+		let s = 1.0 - t;
+		self.p0 * (s * s) + self.p1 * (2.0 * s * t) + self.p2 * (t * t)
+	}
+
+	/// Converts to the equivalent cubic bezier curve.
+	#[inline]
+	pub fn to_cubic(&self) -> Bezier3 {
+		Bezier3 {
+			p0: self.p0,
+			p1: self.p0 + (self.p1 - self.p0) * (2.0 / 3.0),
+			p2: self.p2 + (self.p1 - self.p2) * (2.0 / 3.0),
+			p3: self.p2,
+		}
+	}
+}
+
+/// Cubic bezier curve.
+///
+/// The control points p1 and p2 are the handles of the end points p0 and p3.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Bezier3 {
+	pub p0: Point2<f32>,
+	pub p1: Point2<f32>,
+	pub p2: Point2<f32>,
+	pub p3: Point2<f32>,
+}
+
+/// Limits the recursion when flattening degenerate curves.
+const MAX_FLATTEN_DEPTH: u32 = 16;
+
+impl Bezier3 {
+	/// Evaluates the curve at `t` between `0.0` (at p0) and `1.0` (at p3).
+	#[inline]
+	pub fn eval(&self, t: f32) -> Point2<f32> {
+		let s = 1.0 - t;
+		self.p0 * (s * s * s) + self.p1 * (3.0 * s * s * t) + self.p2 * (3.0 * s * t * t) + self.p3 * (t * t * t)
+	}
+
+	/// Evaluates the tangent of the curve at `t`.
+	#[inline]
+	pub fn derivative(&self, t: f32) -> Vec2<f32> {
+		let s = 1.0 - t;
+		(self.p1 - self.p0) * (3.0 * s * s) + (self.p2 - self.p1) * (6.0 * s * t) + (self.p3 - self.p2) * (3.0 * t * t)
+	}
+
+	/// Splits the curve at `t` in two curves.
+	pub fn split(&self, t: f32) -> (Bezier3, Bezier3) {
+		let lerp = |a: Point2<f32>, b: Point2<f32>| a + (b - a) * t;
+		let p01 = lerp(self.p0, self.p1);
+		let p12 = lerp(self.p1, self.p2);
+		let p23 = lerp(self.p2, self.p3);
+		let p012 = lerp(p01, p12);
+		let p123 = lerp(p12, p23);
+		let mid = lerp(p012, p123);
+		(
+			Bezier3 { p0: self.p0, p1: p01, p2: p012, p3: mid },
+			Bezier3 { p0: mid, p1: p123, p2: p23, p3: self.p3 },
+		)
+	}
+
+	/// Subdivides the curve in `n` curves of equal parameter length.
+	pub fn subdivide(&self, n: i32, out: &mut Vec<Bezier3>) {
+		let n = cmp::max(1, n);
+		let mut rest = *self;
+		for i in 0..n - 1 {
+			// Rescale the split parameter to the remaining part of the curve
+			let (head, tail) = rest.split(1.0 / (n - i) as f32);
+			out.push(head);
+			rest = tail;
+		}
+		out.push(rest);
+	}
+
+	/// Returns the tight bounding box of the curve.
+	pub fn bounds(&self) -> Rect<f32> {
+		let mut bounds = Rect::new(self.p0, self.p0);
+		let mut add = |pt: Point2<f32>| {
+			bounds.mins.x = f32::min(bounds.mins.x, pt.x);
+			bounds.mins.y = f32::min(bounds.mins.y, pt.y);
+			bounds.maxs.x = f32::max(bounds.maxs.x, pt.x);
+			bounds.maxs.y = f32::max(bounds.maxs.y, pt.y);
+		};
+		add(self.p3);
+
+		// The extrema are at the roots of the derivative
+		let mut roots = [0.0f32; 4];
+		let mut nroots = 0;
+		for (p0, p1, p2, p3) in [
+			(self.p0.x, self.p1.x, self.p2.x, self.p3.x),
+			(self.p0.y, self.p1.y, self.p2.y, self.p3.y),
+		] {
+			let a = 3.0 * (-p0 + 3.0 * p1 - 3.0 * p2 + p3);
+			let b = 6.0 * (p0 - 2.0 * p1 + p2);
+			let c = 3.0 * (p1 - p0);
+			nroots += solve_quadratic(a, b, c, &mut roots[nroots..nroots + 2]);
+		}
+		for &t in &roots[..nroots] {
+			if t > 0.0 && t < 1.0 {
+				add(self.eval(t));
+			}
+		}
+		bounds
+	}
+
+	/// Finds the parameter of the point on the curve nearest to the point.
+	pub fn nearest(&self, pt: Point2<f32>) -> f32 {
+		let dist2 = |a: Point2<f32>| {
+			let d = a - pt;
+			d.x * d.x + d.y * d.y
+		};
+
+		// Coarse search followed by Newton refinement
+		const SAMPLES: i32 = 16;
+		let mut best = 0.0;
+		let mut best_dist = f32::INFINITY;
+		for i in 0..=SAMPLES {
+			let t = i as f32 / SAMPLES as f32;
+			let d = dist2(self.eval(t));
+			if d < best_dist {
+				best = t;
+				best_dist = d;
+			}
+		}
+
+		let mut t = best;
+		for _ in 0..4 {
+			let d = self.eval(t) - pt;
+			let d1 = self.derivative(t);
+			let d2 = (self.p2 - self.p1 * 2.0 + self.p0) * (6.0 * (1.0 - t)) + (self.p3 - self.p2 * 2.0 + self.p1) * (6.0 * t);
+			let num = d.x * d1.x + d.y * d1.y;
+			let den = d1.x * d1.x + d1.y * d1.y + d.x * d2.x + d.y * d2.y;
+			if den == 0.0 {
+				break;
+			}
+			t = (t - num / den).clamp(0.0, 1.0);
+		}
+
+		if dist2(self.eval(t)) < best_dist { t } else { best }
+	}
+
+	/// Flattens the curve into line segments.
+	///
+	/// The points deviate at most the tolerance from the curve, the first point is not added.
+	pub fn flatten(&self, tolerance: f32, out: &mut Vec<Point2<f32>>) {
+		self.flatten_rec(f32::max(tolerance, 0.001), MAX_FLATTEN_DEPTH, out);
+	}
+
+	fn flatten_rec(&self, tolerance: f32, depth: u32, out: &mut Vec<Point2<f32>>) {
+		if depth == 0 || self.flatness() <= tolerance {
+			out.push(self.p3);
+			return;
+		}
+		let (head, tail) = self.split(0.5);
+		head.flatten_rec(tolerance, depth - 1, out);
+		tail.flatten_rec(tolerance, depth - 1, out);
+	}
+
+	/// Returns the maximum distance of the control points to the chord.
+	fn flatness(&self) -> f32 {
+		let chord = self.p3 - self.p0;
+		let len = (chord.x * chord.x + chord.y * chord.y).sqrt();
+		let dist = |p: Point2<f32>| {
+			let d = p - self.p0;
+			if len > 0.0 {
+				(chord.x * d.y - chord.y * d.x).abs() / len
+			}
+			else {
+				(d.x * d.x + d.y * d.y).sqrt()
+			}
+		};
+		f32::max(dist(self.p1), dist(self.p2))
+	}
+}
+
+/// Solves `a t² + b t + c = 0`, returns the number of roots written.
+fn solve_quadratic(a: f32, b: f32, c: f32, roots: &mut [f32]) -> usize {
+	if a.abs() < 1e-6 {
+		if b.abs() < 1e-6 {
+			return 0;
+		}
+		roots[0] = -c / b;
+		return 1;
+	}
+	let disc = b * b - 4.0 * a * c;
+	if disc < 0.0 {
+		return 0;
+	}
+	let sqrt = disc.sqrt();
+	roots[0] = (-b + sqrt) / (2.0 * a);
+	roots[1] = (-b - sqrt) / (2.0 * a);
+	2
+}
+
+impl<V: TVertex, U: TUniform> CommandBuffer<V, U> {
+	/// Draws a cubic bezier curve flattened to the tolerance.
+	#[inline(never)]
+	pub fn draw_curve<T: ToVertex<V>>(&mut self, pen: &Pen<T>, curve: &Bezier3, tolerance: f32) {
+		let mut pts = vec![curve.p0];
+		curve.flatten(tolerance, &mut pts);
+		self.draw_poly_line(pen, &pts, false);
+	}
+}
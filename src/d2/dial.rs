@@ -0,0 +1,99 @@
+use super::*;
+
+/// Dial draws arc progress rings with a gradient along the arc.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Dial<T> {
+	/// Vertex template at the start of the arc.
+	pub start: T,
+	/// Vertex template at the end of the arc.
+	pub end: T,
+}
+
+/// Shape of a dial arc.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct DialArc {
+	/// Angle where the arc starts in radians.
+	pub start: f32,
+	/// Angle of the full arc in radians, negative to go the other way around.
+	pub sweep: f32,
+	/// Thickness of the ring, measured inward from the rectangle.
+	pub thickness: f32,
+	/// Round off the ends of the arc.
+	pub round_caps: bool,
+	/// Number of segments of the full arc, zero or fewer to compute them from the tolerance.
+	pub segments: i32,
+}
+
+impl<V: TVertex + Lerp, U: TUniform> CommandBuffer<V, U> {
+	/// Fills the full arc of a dial.
+	#[inline]
+	pub fn fill_dial<T: ToVertex<V>>(&mut self, dial: &Dial<T>, rc: &Rect<f32>, arc: &DialArc) {
+		self.fill_gauge(dial, rc, arc, 1.0)
+	}
+
+	/// Fills the arc of a dial up to the value between `0.0` and `1.0`.
+	///
+	/// The gradient is laid out along the full arc so the color indicates the value.
+	#[inline(never)]
+	pub fn fill_gauge<T: ToVertex<V>>(&mut self, dial: &Dial<T>, rc: &Rect<f32>, arc: &DialArc, value: f32) {
+		let value = value.clamp(0.0, 1.0);
+		if value <= 0.0 {
+			return;
+		}
+
+		let radius = rc.size() * 0.5;
+		let segments = self.arc_segments(arc.segments, radius, arc.sweep);
+		let n = cmp::max(1, (segments as f32 * value).ceil() as i32) as usize;
+		let m = if arc.round_caps { cmp::max(4, segments / 8) as usize } else { 0 };
+
+		let center = rc.top_left() + radius;
+		let inner = radius - Vec2::dup(arc.thickness);
+		let mid = radius - Vec2::dup(arc.thickness * 0.5);
+		let half = arc.thickness * 0.5;
+
+		let vertex = |pos: Point2<f32>, index: usize, t: f32| {
+			dial.start.to_vertex(pos, index).lerp(&dial.end.to_vertex(pos, index), t)
+		};
+
+		// Arc: (n + 1) * 2 vertices, n * 2 primitives
+		// Caps: (m + 2) vertices and m primitives each
+		let nverts = (n + 1) * 2 + if m > 0 { (m + 2) * 2 } else { 0 };
+		let nprims = n * 2 + m * 2;
+		let mut cv = self.begin(PrimType::Triangles, nverts, nprims);
+
+		for i in 0..n as u32 {
+			let v = i * 2;
+			cv.add_index3(v, v + 1, v + 2);
+			cv.add_index3(v + 1, v + 3, v + 2);
+		}
+		for i in 0..=n {
+			let t = value * (i as f32 / n as f32);
+			let (s, c) = (arc.start + arc.sweep * t).sin_cos();
+			let dir = Vec2(c, s);
+			cv.add_vertex(vertex(center + dir * radius, i * 2, t));
+			cv.add_vertex(vertex(center + dir * inner, i * 2 + 1, t));
+		}
+
+		if m > 0 {
+			// Sweep the caps away from the arc
+			let forward = if arc.sweep < 0.0 { -1.0 } else { 1.0 };
+			for (cap, t) in [(0usize, 0.0), (1, value)] {
+				let (s, c) = (arc.start + arc.sweep * t).sin_cos();
+				let radial = Vec2(c, s);
+				let tangent = Vec2(-s, c) * if cap == 0 { -forward } else { forward };
+				let pivot = center + radial * mid;
+
+				let base = ((n + 1) * 2 + cap * (m + 2)) as u32;
+				for i in 1..=m as u32 {
+					cv.add_index3(base, base + i, base + i + 1);
+				}
+				cv.add_vertex(vertex(pivot, base as usize, t));
+				for i in 0..=m {
+					let (ps, pc) = (std::f32::consts::PI * (i as f32 / m as f32)).sin_cos();
+					let pos = pivot + (radial * pc + tangent * ps) * half;
+					cv.add_vertex(vertex(pos, base as usize + 1 + i, t));
+				}
+			}
+		}
+	}
+}
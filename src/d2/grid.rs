@@ -0,0 +1,203 @@
+use super::*;
+use super::layout::Orientation;
+
+/// Limit the number of grid lines to avoid runaway geometry when zoomed out.
+const MAX_LINES: i32 = 4096;
+
+/// Grid for editor overlays.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Grid {
+	/// Position of the grid origin.
+	pub origin: Point2<f32>,
+	/// Spacing between minor grid lines.
+	pub spacing: Vec2<f32>,
+	/// Number of minor cells per major cell.
+	pub major: i32,
+}
+
+impl Default for Grid {
+	#[inline]
+	fn default() -> Self {
+		Grid {
+			origin: Point2::ZERO,
+			spacing: Vec2(10.0, 10.0),
+			major: 10,
+		}
+	}
+}
+
+impl Grid {
+	/// Snaps a position to the nearest minor grid intersection.
+	#[inline]
+	pub fn snap(&self, pt: Point2<f32>) -> Point2<f32> {
+		Point2(
+			snap1d(pt.x, self.origin.x, self.spacing.x),
+			snap1d(pt.y, self.origin.y, self.spacing.y),
+		)
+	}
+
+	/// Snaps a position to the nearest major grid intersection.
+	#[inline]
+	pub fn snap_major(&self, pt: Point2<f32>) -> Point2<f32> {
+		let major = cmp::max(1, self.major) as f32;
+		Point2(
+			snap1d(pt.x, self.origin.x, self.spacing.x * major),
+			snap1d(pt.y, self.origin.y, self.spacing.y * major),
+		)
+	}
+
+	/// Returns the minor cell containing the position.
+	#[inline]
+	pub fn cell(&self, pt: Point2<f32>) -> Point2<i32> {
+		Point2(
+			((pt.x - self.origin.x) / self.spacing.x).floor() as i32,
+			((pt.y - self.origin.y) / self.spacing.y).floor() as i32,
+		)
+	}
+
+	/// Returns the range of minor grid line indices visible within `min..=max` and the step between the visible lines.
+	///
+	/// When more than `MAX_LINES` lines are visible the step goes up to the major spacing, and multiples thereof, until they fit.
+	fn lines(&self, min: f32, max: f32, origin: f32, spacing: f32) -> Option<(i32, i32, usize)> {
+		if spacing.is_nan() || spacing <= 0.0 || min > max {
+			return None;
+		}
+		let factor = cmp::max(2, self.major);
+		let mut step = 1i32;
+		loop {
+			let stride = spacing * step as f32;
+			let first = ((min - origin) / stride).ceil();
+			let last = ((max - origin) / stride).floor();
+			if last < first {
+				return None;
+			}
+			if last - first <= MAX_LINES as f32 {
+				return Some(((first as i32).checked_mul(step)?, (last as i32).checked_mul(step)?, step as usize));
+			}
+			step = step.checked_mul(factor)?;
+		}
+	}
+
+	#[inline]
+	fn is_major(&self, index: i32) -> bool {
+		self.major > 0 && index.rem_euclid(self.major) == 0
+	}
+}
+
+/// Snaps a value to the nearest multiple of the spacing relative to the origin.
+#[inline]
+pub fn snap1d(value: f32, origin: f32, spacing: f32) -> f32 {
+	if spacing.is_nan() || spacing <= 0.0 {
+		return value;
+	}
+	((value - origin) / spacing).round() * spacing + origin
+}
+
+impl<V: TVertex, U: TUniform> CommandBuffer<V, U> {
+	/// Draws the grid lines within the rectangle.
+	///
+	/// Major grid lines are drawn with the major pen, all others with the minor pen.
+	#[inline(never)]
+	pub fn draw_grid<T: ToVertex<V>>(&mut self, minor: &Pen<T>, major: &Pen<T>, grid: &Grid, rc: &Rect<f32>) {
+		let mut minor_pts = Vec::new();
+		let mut major_pts = Vec::new();
+
+		if let Some((first, last, step)) = grid.lines(rc.mins.x, rc.maxs.x, grid.origin.x, grid.spacing.x) {
+			for i in (first..=last).step_by(step) {
+				let x = grid.origin.x + i as f32 * grid.spacing.x;
+				let pts = if grid.is_major(i) { &mut major_pts } else { &mut minor_pts };
+				pts.push(Point2(x, rc.mins.y));
+				pts.push(Point2(x, rc.maxs.y));
+			}
+		}
+		if let Some((first, last, step)) = grid.lines(rc.mins.y, rc.maxs.y, grid.origin.y, grid.spacing.y) {
+			for i in (first..=last).step_by(step) {
+				let y = grid.origin.y + i as f32 * grid.spacing.y;
+				let pts = if grid.is_major(i) { &mut major_pts } else { &mut minor_pts };
+				pts.push(Point2(rc.mins.x, y));
+				pts.push(Point2(rc.maxs.x, y));
+			}
+		}
+
+		for (pen, pts) in [(minor, &minor_pts), (major, &major_pts)] {
+			if pts.is_empty() {
+				continue;
+			}
+			let mut cv = self.begin(PrimType::Lines, pts.len(), pts.len() / 2);
+			for i in 0..pts.len() / 2 {
+				cv.add_index2((i * 2) as u32, (i * 2 + 1) as u32);
+			}
+			for (v, &pt) in pts.iter().enumerate() {
+				cv.add_vertex(pen.template.to_vertex(pt, v));
+			}
+		}
+	}
+
+	/// Draws a ruler strip along the edge of the rectangle.
+	///
+	/// The strip is `size` thick along the top edge (horizontal) or the left edge (vertical) of the rectangle.
+	/// Minor ticks are drawn at a quarter and major ticks at half the size of the strip.
+	#[inline(never)]
+	pub fn draw_ruler<T: ToVertex<V>, P: ToVertex<V>>(&mut self, paint: &Paint<P>, pen: &Pen<T>, grid: &Grid, rc: &Rect<f32>, orientation: Orientation, size: f32) {
+		let strip = ruler_strip(rc, orientation, size);
+		self.fill_rect(paint, &strip);
+
+		let (min, max, origin, spacing) = match orientation {
+			Orientation::Horizontal => (strip.mins.x, strip.maxs.x, grid.origin.x, grid.spacing.x),
+			Orientation::Vertical => (strip.mins.y, strip.maxs.y, grid.origin.y, grid.spacing.y),
+		};
+		let Some((first, last, step)) = grid.lines(min, max, origin, spacing) else { return };
+
+		let n = (last - first) as usize / step + 1;
+		let mut cv = self.begin(PrimType::Lines, n * 2, n);
+		for i in 0..n {
+			cv.add_index2((i * 2) as u32, (i * 2 + 1) as u32);
+		}
+		for (j, i) in (first..=last).step_by(step).enumerate() {
+			let pos = origin + i as f32 * spacing;
+			let tick = if grid.is_major(i) { size * 0.5 } else { size * 0.25 };
+			let (a, b) = match orientation {
+				Orientation::Horizontal => (Point2(pos, strip.maxs.y), Point2(pos, strip.maxs.y - tick)),
+				Orientation::Vertical => (Point2(strip.maxs.x, pos), Point2(strip.maxs.x - tick, pos)),
+			};
+			let v = j * 2;
+			cv.add_vertex(pen.template.to_vertex(a, v));
+			cv.add_vertex(pen.template.to_vertex(b, v + 1));
+		}
+	}
+}
+
+impl TextBuffer {
+	/// Writes the labels of the major ticks of a ruler strip.
+	///
+	/// Each label shows the distance from the grid origin.
+	pub fn ruler_labels(&mut self, font: &FontResource<impl IFont>, scribe: &Scribe, grid: &Grid, rc: &Rect<f32>, orientation: Orientation, size: f32) {
+		let strip = ruler_strip(rc, orientation, size);
+		let (min, max, origin, spacing) = match orientation {
+			Orientation::Horizontal => (strip.mins.x, strip.maxs.x, grid.origin.x, grid.spacing.x),
+			Orientation::Vertical => (strip.mins.y, strip.maxs.y, grid.origin.y, grid.spacing.y),
+		};
+		let Some((first, last, step)) = grid.lines(min, max, origin, spacing) else { return };
+
+		let mut scribe = scribe.clone();
+		for i in (first..=last).step_by(step) {
+			if !grid.is_major(i) {
+				continue;
+			}
+			let offset = i as f32 * spacing;
+			let mut cursor = match orientation {
+				Orientation::Horizontal => Vec2(origin + offset + 2.0, strip.mins.y),
+				Orientation::Vertical => Vec2(strip.mins.x + 2.0, origin + offset),
+			};
+			scribe.x_pos = cursor.x;
+			self.text_write(font, &mut scribe, &mut cursor, offset);
+		}
+	}
+}
+
+fn ruler_strip(rc: &Rect<f32>, orientation: Orientation, size: f32) -> Rect<f32> {
+	match orientation {
+		Orientation::Horizontal => Rect::c(rc.mins.x, rc.mins.y, rc.maxs.x, rc.mins.y + size),
+		Orientation::Vertical => Rect::c(rc.mins.x, rc.mins.y, rc.mins.x + size, rc.maxs.y),
+	}
+}
@@ -43,5 +43,7 @@ pub enum Justify {
 	SpaceEvenly,
 }
 
+mod anchor;
 mod flex;
+pub use anchor::*;
 pub use flex::*;
@@ -0,0 +1,122 @@
+use cvmath::{Rect, Vec2};
+
+/// Insets from the edges of a rectangle.
+///
+/// Used for the safe area of displays with notches or rounded corners.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Insets {
+	pub left: f32,
+	pub top: f32,
+	pub right: f32,
+	pub bottom: f32,
+}
+
+impl Insets {
+	/// No insets.
+	pub const ZERO: Insets = Insets { left: 0.0, top: 0.0, right: 0.0, bottom: 0.0 };
+
+	/// Same inset on all edges.
+	#[inline]
+	pub const fn uniform(value: f32) -> Insets {
+		Insets { left: value, top: value, right: value, bottom: value }
+	}
+
+	/// Shrinks the rectangle by the insets.
+	///
+	/// The result is collapsed to its center when the insets exceed its size.
+	#[inline]
+	pub fn apply(&self, rc: &Rect<f32>) -> Rect<f32> {
+		let mut mins = Vec2(rc.mins.x + self.left, rc.mins.y + self.top);
+		let mut maxs = Vec2(rc.maxs.x - self.right, rc.maxs.y - self.bottom);
+		if mins.x > maxs.x {
+			mins.x = (mins.x + maxs.x) * 0.5;
+			maxs.x = mins.x;
+		}
+		if mins.y > maxs.y {
+			mins.y = (mins.y + maxs.y) * 0.5;
+			maxs.y = mins.y;
+		}
+		Rect::new(mins, maxs)
+	}
+}
+
+/// Anchor region within a parent rectangle.
+///
+/// Given as fractions of the parent size from its `mins` corner, equal min and max anchor to a point.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Anchor {
+	pub min: Vec2<f32>,
+	pub max: Vec2<f32>,
+}
+
+impl Anchor {
+	pub const TOP_LEFT: Anchor = Anchor::point(0.0, 0.0);
+	pub const TOP_CENTER: Anchor = Anchor::point(0.5, 0.0);
+	pub const TOP_RIGHT: Anchor = Anchor::point(1.0, 0.0);
+	pub const MIDDLE_LEFT: Anchor = Anchor::point(0.0, 0.5);
+	pub const CENTER: Anchor = Anchor::point(0.5, 0.5);
+	pub const MIDDLE_RIGHT: Anchor = Anchor::point(1.0, 0.5);
+	pub const BOTTOM_LEFT: Anchor = Anchor::point(0.0, 1.0);
+	pub const BOTTOM_CENTER: Anchor = Anchor::point(0.5, 1.0);
+	pub const BOTTOM_RIGHT: Anchor = Anchor::point(1.0, 1.0);
+	/// Stretch along the top edge.
+	pub const TOP_STRETCH: Anchor = Anchor { min: Vec2 { x: 0.0, y: 0.0 }, max: Vec2 { x: 1.0, y: 0.0 } };
+	/// Stretch along the bottom edge.
+	pub const BOTTOM_STRETCH: Anchor = Anchor { min: Vec2 { x: 0.0, y: 1.0 }, max: Vec2 { x: 1.0, y: 1.0 } };
+	/// Stretch over the whole parent.
+	pub const STRETCH: Anchor = Anchor { min: Vec2 { x: 0.0, y: 0.0 }, max: Vec2 { x: 1.0, y: 1.0 } };
+
+	/// Anchors to a point.
+	#[inline]
+	pub const fn point(x: f32, y: f32) -> Anchor {
+		Anchor { min: Vec2 { x, y }, max: Vec2 { x, y } }
+	}
+}
+
+/// Anchored placement of an element.
+///
+/// The size is added to the size of the anchor region:
+/// for point anchors this is the size of the element, for stretched anchors a negative size shrinks the element.
+/// The pivot is the point of the element, as a fraction of its size, which is placed at the anchor.
+///
+/// # Example
+///
+/// ```rust
+/// use shade::cvmath::{Rect, Vec2};
+/// use shade::d2::layout::*;
+///
+/// let screen = Rect::new(Vec2(0.0, 0.0), Vec2(800.0, 600.0));
+/// let safe = Insets { left: 0.0, top: 40.0, right: 0.0, bottom: 20.0 }.apply(&screen);
+///
+/// // Health bar in the bottom right corner, 10 pixels from the edges
+/// let health = Anchored {
+///     anchor: Anchor::BOTTOM_RIGHT,
+///     pivot: Vec2(1.0, 1.0),
+///     offset: Vec2(-10.0, -10.0),
+///     size: Vec2(200.0, 20.0),
+/// };
+/// let rc = health.place(&safe);
+///
+/// assert_eq!(rc, Rect::new(Vec2(590.0, 550.0), Vec2(790.0, 570.0)));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Anchored {
+	pub anchor: Anchor,
+	pub pivot: Vec2<f32>,
+	pub offset: Vec2<f32>,
+	pub size: Vec2<f32>,
+}
+
+impl Anchored {
+	/// Places the element within the parent rectangle.
+	pub fn place(&self, parent: &Rect<f32>) -> Rect<f32> {
+		let parent_size = parent.maxs - parent.mins;
+		let region_min = parent.mins + parent_size * self.anchor.min;
+		let region_max = parent.mins + parent_size * self.anchor.max;
+		let region_size = region_max - region_min;
+
+		let size = region_size + self.size;
+		let mins = region_min + region_size * self.pivot + self.offset - size * self.pivot;
+		Rect::new(mins, mins + size)
+	}
+}
@@ -46,7 +46,7 @@ pub fn flex1d_slice(
 ) {
 	// Sanity check the parameters
 	debug_assert_eq!(template.len(), spans.len());
-	if template.len() == 0 || template.len() != spans.len() {
+	if template.is_empty() || template.len() != spans.len() {
 		return;
 	}
 	debug_assert!(min <= max);
@@ -66,8 +66,8 @@ pub fn flex1d_slice(
 	};
 
 	// Analyze the template to extract total used space and fraction units
-	for i in 0..template.len() {
-		match template[i] {
+	for unit in template {
+		match *unit {
 			Unit::Abs(v) => total_used += v,
 			Unit::Pct(v) => total_used += v * (0.01 * size),
 			Unit::Fr(v) => total_fr += v,
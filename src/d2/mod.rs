@@ -10,20 +10,50 @@ mod cmdbuf;
 mod paint;
 mod pen;
 mod stamp;
-mod curve;
+pub mod curve;
 mod scribe;
+mod grid;
+mod dial;
+mod clip;
+mod palette;
+mod shadow;
+mod sprite;
+mod cursor;
+mod anchor;
 pub mod layout;
 
-pub use self::cmdbuf::{CommandBuffer, PrimBuilder};
-pub use self::paint::Paint;
+pub use self::cmdbuf::{CommandBuffer, PrimBuilder, PreparedDraw};
+pub use self::paint::{Paint, FillRule};
 pub use self::pen::Pen;
 pub use self::stamp::Stamp;
 pub use self::scribe::*;
+pub use self::grid::{Grid, snap1d};
+pub use self::curve::{Bezier2, Bezier3};
+pub use self::dial::{Dial, DialArc};
+pub use self::palette::{Palette, PaletteBuffer, PaletteUniform, PaletteVertex};
+pub use self::shadow::Shadow;
+pub use self::sprite::{SpriteBatch, SpriteInstance, SpriteUniform};
+pub use self::cursor::{Cursor, CursorImage};
+pub use self::anchor::{ScreenAnchor, d3_to_d2, d3_to_d2_clamped};
 
 /// Generate vertex data from a template.
 pub trait ToVertex<V> {
 	fn to_vertex(&self, pos: Point2<f32>, index: usize) -> V;
 }
 
+/// Linearly interpolate vertices.
+///
+/// Required to re-tessellate shapes while preserving the vertex attributes.
+pub trait Lerp {
+	fn lerp(&self, rhs: &Self, t: f32) -> Self;
+}
+
+/// Position of a vertex.
+///
+/// Required to compute the bounds of the geometry.
+pub trait Position {
+	fn position(&self) -> Point2<f32>;
+}
+
 #[cfg(test)]
 mod tests;
@@ -8,6 +8,16 @@ pub struct Paint<T> {
 	pub template: T,
 }
 
+/// Fill rule for stencil filled paths.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub enum FillRule {
+	/// Fill where the winding number is not zero.
+	#[default]
+	NonZero,
+	/// Fill where the winding number is odd.
+	EvenOdd,
+}
+
 impl<V: TVertex, U: TUniform> CommandBuffer<V, U> {
 	/// Fills a rectangle.
 	#[inline(never)]
@@ -77,8 +87,8 @@ impl<V: TVertex, U: TUniform> CommandBuffer<V, U> {
 			cv.add_index3(i, i + 1, n as u32 + 1);
 		}
 		// Add vertices
-		for v in 0..pts.len() {
-			cv.add_vertex(paint.template.to_vertex(pts[v], v));
+		for (v, &pt) in pts.iter().enumerate() {
+			cv.add_vertex(paint.template.to_vertex(pt, v));
 		}
 	}
 
@@ -87,17 +97,16 @@ impl<V: TVertex, U: TUniform> CommandBuffer<V, U> {
 	pub fn fill_polygon<T: ToVertex<V>>(&mut self, paint: &Paint<T>, pts: &[Point2<f32>], triangles: &[(u32, u32, u32)]) {
 		let mut cv = self.begin(PrimType::Triangles, pts.len(), triangles.len());
 		// Add indices
-		for i in 0..triangles.len() {
+		for &(p1, p2, p3) in triangles {
 			// Must be indices into the points slice
-			let (p1, p2, p3) = triangles[i];
 			let _ = pts[p1 as usize];
 			let _ = pts[p2 as usize];
 			let _ = pts[p3 as usize];
 			cv.add_index3(p1, p2, p3);
 		}
 		// Add vertices
-		for v in 0..pts.len() {
-			cv.add_vertex(paint.template.to_vertex(pts[v], v));
+		for (v, &pt) in pts.iter().enumerate() {
+			cv.add_vertex(paint.template.to_vertex(pt, v));
 		}
 	}
 
@@ -119,6 +128,7 @@ impl<V: TVertex, U: TUniform> CommandBuffer<V, U> {
 	#[inline(never)]
 	pub fn fill_ellipse<T: ToVertex<V>>(&mut self, paint: &Paint<T>, rc: &Rect<f32>, segments: i32) {
 		// n + 1 vertices, n primitives, n * 3 indices
+		let segments = self.arc_segments(segments, rc.size() * 0.5, std::f32::consts::TAU);
 		let n = cmp::max(3, segments) as usize;
 		let mut cv = self.begin(PrimType::Triangles, n + 1, n);
 
@@ -151,6 +161,7 @@ impl<V: TVertex, U: TUniform> CommandBuffer<V, U> {
 	#[inline(never)]
 	pub fn fill_pie<T: ToVertex<V>>(&mut self, paint: &Paint<T>, rc: &Rect<f32>, start: Rad<f32>, sweep: Rad<f32>, segments: i32) {
 		// n + 2 vertices, n primitives, n * 3 indices
+		let segments = self.arc_segments(segments, rc.size() * 0.5, sweep_radians(sweep));
 		let n = cmp::max(2, segments) as usize;
 		let mut cv = self.begin(PrimType::Triangles, n + 2, n);
 
@@ -185,6 +196,7 @@ impl<V: TVertex, U: TUniform> CommandBuffer<V, U> {
 	#[inline(never)]
 	pub fn fill_ring<T: ToVertex<V>>(&mut self, paint: &Paint<T>, rc: &Rect<f32>, thickness: f32, segments: i32) {
 		// n * 2 vertices, n * 2 primitives, n * 6 indices
+		let segments = self.arc_segments(segments, rc.size() * 0.5, std::f32::consts::TAU);
 		let n = cmp::max(3, segments) as usize;
 		let mut cv = self.begin(PrimType::Triangles, n * 2, n * 2);
 
@@ -221,8 +233,48 @@ impl<V: TVertex, U: TUniform> CommandBuffer<V, U> {
 		}
 	}
 
+	/// Fills a path using the stencil buffer.
+	///
+	/// Correctly fills self-intersecting and concave paths without triangulation.
+	/// The path triangles are fanned into the stencil buffer, then its bounding box is covered with the paint.
+	/// Requires a surface with a stencil buffer cleared to zero and no triangle culling.
+	#[inline(never)]
+	pub fn fill_path<T: ToVertex<V>>(&mut self, paint: &Paint<T>, pts: &[Point2<f32>], rule: FillRule) {
+		if pts.len() < 3 {
+			return;
+		}
+
+		let stencil = self.stencil;
+		self.stencil = Some(match rule {
+			FillRule::NonZero => StencilMode::Winding,
+			FillRule::EvenOdd => StencilMode::Invert,
+		});
+		{
+			// n vertices, n - 2 primitives, (n - 2) * 3 indices
+			let n = pts.len();
+			let mut cv = self.begin(PrimType::Triangles, n, n - 2);
+			for i in 1..n - 1 {
+				cv.add_index3(0, i as u32, i as u32 + 1);
+			}
+			for (i, &pt) in pts.iter().enumerate() {
+				cv.add_vertex(paint.template.to_vertex(pt, i));
+			}
+		}
+
+		let mut mins = pts[0];
+		let mut maxs = pts[0];
+		for pt in &pts[1..] {
+			mins = Point2(f32::min(mins.x, pt.x), f32::min(mins.y, pt.y));
+			maxs = Point2(f32::max(maxs.x, pt.x), f32::max(maxs.y, pt.y));
+		}
+		self.stencil = Some(StencilMode::Cover);
+		self.fill_rect(paint, &Rect::new(mins, maxs));
+		self.stencil = stencil;
+	}
+
 	pub fn fill_bezier2<T: ToVertex<V>>(&mut self, paint: &Paint<T>, pivot: &Point2<f32>, pts: &[Point2<f32>; 3], segments: i32) {
 		// n + 2 vertices, n primitives, n * 3 indices
+		let segments = self.bezier_segments(segments, pts);
 		let n = cmp::max(2, segments) as usize;
 		let mut cv = self.begin(PrimType::Triangles, n + 2, n);
 
@@ -239,3 +291,12 @@ impl<V: TVertex, U: TUniform> CommandBuffer<V, U> {
 		}
 	}
 }
+
+/// Returns the sweep angle in radians.
+///
+/// Recovered from the quarter angle which stays unambiguous for sweeps up to two full turns.
+#[inline]
+pub(super) fn sweep_radians(sweep: Rad<f32>) -> f32 {
+	let (s, c) = (sweep / 4.0).sin_cos();
+	4.0 * s.atan2(c)
+}
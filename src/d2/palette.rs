@@ -0,0 +1,186 @@
+use super::*;
+
+/// Maximum number of colors in a palette.
+const PALETTE_SIZE: usize = 256;
+
+/// Palette vertex.
+///
+/// Stores an index into the palette instead of a color.
+/// Changing a palette entry retints every vertex using it without regenerating the vertices.
+#[derive(Copy, Clone, Debug, Default, dataview::Pod)]
+#[repr(C)]
+pub struct PaletteVertex {
+	pub pos: Vec2<f32>,
+	pub index: u8,
+	pub _pad: [u8; 3],
+}
+
+unsafe impl TVertex for PaletteVertex {
+	const VERTEX_LAYOUT: &'static VertexLayout = &VertexLayout {
+		size: std::mem::size_of::<PaletteVertex>() as u16,
+		alignment: std::mem::align_of::<PaletteVertex>() as u16,
+		instance_divisor: 0,
+		attributes: &[
+			VertexAttribute {
+				format: VertexAttributeFormat::F32,
+				len: 2,
+				offset: dataview::offset_of!(PaletteVertex.pos) as u16,
+			},
+			VertexAttribute {
+				format: VertexAttributeFormat::U8,
+				len: 1,
+				offset: dataview::offset_of!(PaletteVertex.index) as u16,
+			},
+		],
+	};
+}
+
+impl PaletteVertex {
+	/// Creates a palette vertex template for the palette index.
+	#[inline]
+	pub const fn index(index: u8) -> PaletteVertex {
+		PaletteVertex { pos: Vec2 { x: 0.0, y: 0.0 }, index, _pad: [0; 3] }
+	}
+}
+
+impl ToVertex<PaletteVertex> for PaletteVertex {
+	#[inline]
+	fn to_vertex(&self, pos: Point2<f32>, _index: usize) -> PaletteVertex {
+		PaletteVertex { pos, ..*self }
+	}
+}
+
+impl Position for PaletteVertex {
+	#[inline]
+	fn position(&self) -> Point2<f32> {
+		self.pos
+	}
+}
+
+/// Palette uniform.
+#[derive(Copy, Clone, Debug, dataview::Pod)]
+#[repr(C)]
+pub struct PaletteUniform {
+	pub transform: Transform2<f32>,
+	pub palette: Texture2D,
+}
+
+impl Default for PaletteUniform {
+	fn default() -> Self {
+		PaletteUniform {
+			transform: Transform2::IDENTITY,
+			palette: Texture2D::INVALID,
+		}
+	}
+}
+
+unsafe impl TUniform for PaletteUniform {
+	const UNIFORM_LAYOUT: &'static UniformLayout = &UniformLayout {
+		size: std::mem::size_of::<PaletteUniform>() as u16,
+		alignment: std::mem::align_of::<PaletteUniform>() as u16,
+		attributes: &[
+			UniformAttribute {
+				name: "u_transform",
+				ty: UniformType::Mat3x2 { order: UniformMatOrder::RowMajor },
+				offset: dataview::offset_of!(PaletteUniform.transform) as u16,
+				len: 1,
+			},
+			UniformAttribute {
+				name: "u_palette",
+				ty: UniformType::Sampler2D(0),
+				offset: dataview::offset_of!(PaletteUniform.palette) as u16,
+				len: 1,
+			},
+		],
+	};
+}
+
+/// Command buffer for palette indexed shapes.
+///
+/// Draw with the shader compiled from `gl::PALETTE_VS` and `gl::PALETTE_FS`.
+pub type PaletteBuffer = CommandBuffer<PaletteVertex, PaletteUniform>;
+
+/// Color palette.
+///
+/// The colors are stored in a 256 by 1 texture.
+pub struct Palette {
+	colors: [Vec4<u8>; PALETTE_SIZE],
+	dirty: bool,
+	/// The palette texture, valid after the first upload.
+	pub texture: Texture2D,
+}
+
+impl Default for Palette {
+	#[inline]
+	fn default() -> Self {
+		Palette::new()
+	}
+}
+
+impl Palette {
+	/// Creates a new palette with all entries transparent.
+	pub fn new() -> Palette {
+		Palette {
+			colors: [Vec4(0, 0, 0, 0); PALETTE_SIZE],
+			dirty: true,
+			texture: Texture2D::INVALID,
+		}
+	}
+
+	/// Gets a palette entry.
+	#[inline]
+	pub fn get(&self, index: u8) -> Vec4<u8> {
+		self.colors[index as usize]
+	}
+
+	/// Sets a palette entry.
+	#[inline]
+	pub fn set(&mut self, index: u8, color: Vec4<u8>) {
+		if self.colors[index as usize] != color {
+			self.colors[index as usize] = color;
+			self.dirty = true;
+		}
+	}
+
+	/// Sets consecutive palette entries starting at the index.
+	pub fn set_range(&mut self, start: u8, colors: &[Vec4<u8>]) {
+		for (i, &color) in colors.iter().take(PALETTE_SIZE - start as usize).enumerate() {
+			self.set(start + i as u8, color);
+		}
+	}
+
+	/// Uploads the palette to the GPU.
+	///
+	/// Creates the texture on first use, afterwards only uploads when entries were changed.
+	pub fn upload(&mut self, g: &mut Graphics) -> Result<Texture2D, GfxError> {
+		if self.texture == Texture2D::INVALID {
+			self.texture = g.texture2d_create(None, &Texture2DInfo {
+				width: PALETTE_SIZE as i32,
+				height: 1,
+				filter_min: TextureFilter::Nearest,
+				filter_mag: TextureFilter::Nearest,
+				..Default::default()
+			})?;
+			self.dirty = true;
+		}
+		if self.dirty {
+			let mut data = [0u8; PALETTE_SIZE * 4];
+			for (i, color) in self.colors.iter().enumerate() {
+				data[i * 4..i * 4 + 4].copy_from_slice(&[color.x, color.y, color.z, color.w]);
+			}
+			g.texture2d_set_data(self.texture, &data)?;
+			self.dirty = false;
+		}
+		Ok(self.texture)
+	}
+
+	/// Releases the palette texture.
+	pub fn delete(&mut self, g: &mut Graphics) -> Result<(), GfxError> {
+		if self.texture != Texture2D::INVALID {
+			g.texture2d_delete(self.texture, true)?;
+			self.texture = Texture2D::INVALID;
+		}
+		self.dirty = true;
+		Ok(())
+	}
+}
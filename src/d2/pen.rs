@@ -24,11 +24,11 @@ impl<V: TVertex, U: TUniform> CommandBuffer<V, U> {
 	#[inline(never)]
 	pub fn draw_lines<T: ToVertex<V>>(&mut self, pen: &Pen<T>, pts: &[Point2<f32>], lines: &[(u32, u32)]) {
 		let mut cv = self.begin(PrimType::Lines, pts.len(), lines.len());
-		for i in 0..lines.len() {
-			cv.add_index2(lines[i].0, lines[i].1);
+		for &(a, b) in lines {
+			cv.add_index2(a, b);
 		}
-		for i in 0..pts.len() {
-			cv.add_vertex(pen.template.to_vertex(pts[i], i));
+		for (i, &pt) in pts.iter().enumerate() {
+			cv.add_vertex(pen.template.to_vertex(pt, i));
 		}
 	}
 
@@ -100,8 +100,8 @@ impl<V: TVertex, U: TUniform> CommandBuffer<V, U> {
 			cv.add_index2((pts.len() - 1) as u32, 0);
 		}
 		// Add vertices
-		for v in 0..pts.len() {
-			cv.add_vertex(pen.template.to_vertex(pts[v], v));
+		for (v, &pt) in pts.iter().enumerate() {
+			cv.add_vertex(pen.template.to_vertex(pt, v));
 		}
 	}
 
@@ -109,6 +109,7 @@ impl<V: TVertex, U: TUniform> CommandBuffer<V, U> {
 	#[inline(never)]
 	pub fn draw_ellipse<T: ToVertex<V>>(&mut self, pen: &Pen<T>, rc: &Rect<f32>, segments: i32) {
 		// n vertices, n primitives, n * 2 indices
+		let segments = self.arc_segments(segments, rc.size() * 0.5, std::f32::consts::TAU);
 		let n = cmp::max(3, segments) as usize;
 		let mut cv = self.begin(PrimType::Lines, n, n);
 
@@ -143,6 +144,7 @@ impl<V: TVertex, U: TUniform> CommandBuffer<V, U> {
 		}
 
 		// n + 1 vertices, n primitives, n * 2 indices
+		let segments = self.arc_segments(segments, rc.size() * 0.5, paint::sweep_radians(sweep));
 		let n = cmp::max(2, segments) as usize;
 		let mut cv = self.begin(PrimType::Lines, n + 1, n);
 
@@ -174,6 +176,7 @@ impl<V: TVertex, U: TUniform> CommandBuffer<V, U> {
 	#[inline(never)]
 	pub fn draw_bezier2<T: ToVertex<V>>(&mut self, pen: &Pen<T>, pts: &[Point2<f32>; 3], segments: i32) {
 		// n + 1 vertices, n primitives, n * 2 indices
+		let segments = self.bezier_segments(segments, pts);
 		let n = cmp::max(2, segments) as usize;
 		let mut cv = self.begin(PrimType::Lines, n + 1, n);
 
@@ -192,6 +195,7 @@ impl<V: TVertex, U: TUniform> CommandBuffer<V, U> {
 	#[inline(never)]
 	pub fn draw_bezier3<T: ToVertex<V>>(&mut self, pen: &Pen<T>, pts: &[Point2<f32>; 4], segments: i32) {
 		// n + 1 vertices, n primitives, n * 2 indices
+		let segments = self.bezier_segments(segments, pts);
 		let n = cmp::max(2, segments) as usize;
 		let mut cv = self.begin(PrimType::Lines, n + 1, n);
 
@@ -1,14 +1,22 @@
 use super::*;
 
+mod bitmap;
 pub(crate) mod escape;
+mod fallback;
 mod font;
+mod locale;
+mod prepared;
 mod resource;
 mod u;
 mod v;
 
+pub use self::bitmap::{BitmapFont, BitmapGlyph};
+pub use self::fallback::FontChain;
 pub use self::font::IFont;
+pub use self::locale::{ILocale, Plural, TextMetricsCache};
+pub use self::prepared::PreparedText;
 pub use self::resource::FontResource;
-pub use self::u::TextUniform;
+pub use self::u::{TextLayer, TextUniform};
 pub use self::v::TextVertex;
 
 pub type TextBuffer = CommandBuffer<TextVertex, TextUniform>;
@@ -91,7 +99,7 @@ impl Scribe {
 			cursor.x = scribe_st.x_pos;
 			cursor.y += scribe_st.line_height;
 		}
-		return width;
+		width
 	}
 
 	/// Measures the height of a text string.
@@ -111,6 +119,30 @@ impl TextBuffer {
 		text_write(self, font.as_dyn().font, scribe, cursor, &text);
 	}
 
+	/// Writes a text string with the outline in a separate pass behind the fill.
+	///
+	/// Avoids the outline of a glyph overlapping the fill of its neighbours.
+	pub fn text_write_layered<T: fmt::Display>(&mut self, font: &FontResource<impl IFont>, scribe: &mut Scribe, cursor: &mut Vec2<f32>, text: T) {
+		self.shader = font.shader;
+		let layer = self.get_uniform().layer;
+
+		let mut outline_scribe = scribe.clone();
+		let mut outline_cursor = *cursor;
+		self.push_uniform_f(|u| TextUniform { layer: TextLayer::Outline as i32, ..*u });
+		text_write(self, font.as_dyn().font, &mut outline_scribe, &mut outline_cursor, &text);
+
+		self.push_uniform_f(|u| TextUniform { layer: TextLayer::Fill as i32, ..*u });
+		text_write(self, font.as_dyn().font, scribe, cursor, &text);
+
+		self.push_uniform_f(|u| TextUniform { layer, ..*u });
+	}
+
+	/// Sets the layer of the glyphs rendered by the following text.
+	#[inline]
+	pub fn text_layer(&mut self, layer: TextLayer) {
+		self.push_uniform_f(|u| TextUniform { layer: layer as i32, ..*u });
+	}
+
 	/// Writes individual lines of text strings using the box model.
 	///
 	/// The text will be aligned within the rect according to the alignment.
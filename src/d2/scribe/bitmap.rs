@@ -0,0 +1,108 @@
+use std::collections::HashMap;
+
+use super::*;
+
+/// Bitmap glyph.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct BitmapGlyph {
+	/// Bounds of the glyph in the atlas in pixels, y down from the top of the atlas.
+	pub atlas: Rect<f32>,
+	/// Bounds of the glyph quad in em units, y down from the top of the line.
+	pub plane: Rect<f32>,
+	/// Horizontal advance in em units.
+	pub advance: f32,
+}
+
+/// Color bitmap font.
+///
+/// Renders glyphs from an RGBA atlas, such as emoji, without signed distance field processing.
+/// Draw with the `gl::COLOR_GLYPH_FS` shader, the text color only modulates the alpha of the glyphs.
+///
+/// Mix with MSDF text by adding the font to a [`FontChain`] as a fallback.
+#[derive(Clone, Debug, Default)]
+pub struct BitmapFont {
+	/// Size of the atlas in pixels.
+	pub atlas_size: Vec2<f32>,
+	/// Glyphs by character.
+	pub glyphs: HashMap<char, BitmapGlyph>,
+}
+
+impl BitmapFont {
+	/// Creates a new empty bitmap font for an atlas of the given size.
+	#[inline]
+	pub fn new(atlas_width: i32, atlas_height: i32) -> BitmapFont {
+		BitmapFont {
+			atlas_size: Vec2(atlas_width as f32, atlas_height as f32),
+			glyphs: HashMap::new(),
+		}
+	}
+
+	/// Adds a glyph to the font.
+	#[inline]
+	pub fn insert(&mut self, chr: char, glyph: BitmapGlyph) {
+		self.glyphs.insert(chr, glyph);
+	}
+}
+
+impl IFont for BitmapFont {
+	fn write_span(&self, mut cv: Option<&mut TextBuffer>, scribe: &mut Scribe, cursor: &mut Vec2<f32>, text: &str) {
+		let mut chars = text.chars();
+		while let Some(chr) = chars.next() {
+			if chr == '\n' {
+				cursor.x = scribe.x_pos;
+				cursor.y += scribe.line_height;
+				continue;
+			}
+
+			// Process escape sequences
+			if chr == '\x1b' {
+				if chars.next() == Some('[') {
+					if let Some((sequence, tail)) = chars.as_str().split_once("]") {
+						escape::process(sequence, scribe, cv.as_deref_mut());
+						chars = tail.chars();
+					}
+					else {
+						// No terminal bracket found
+						break;
+					}
+				}
+				continue;
+			}
+
+			let Some(glyph) = self.glyphs.get(&chr) else { continue };
+			let pos = *cursor + Vec2(0.0, scribe.line_height - scribe.font_size - scribe.baseline);
+
+			let scale = Vec2(scribe.font_size * scribe.font_width_scale, scribe.font_size);
+			cursor.x += glyph.advance * scale.x + scribe.letter_spacing;
+
+			if let Some(cv) = &mut cv {
+				let pleft = glyph.plane.mins.x * scale.x;
+				let pright = glyph.plane.maxs.x * scale.x;
+				let ptop = glyph.plane.mins.y * scale.y;
+				let pbottom = glyph.plane.maxs.y * scale.y;
+
+				let aleft = glyph.atlas.mins.x / self.atlas_size.x;
+				let aright = glyph.atlas.maxs.x / self.atlas_size.x;
+				let atop = glyph.atlas.mins.y / self.atlas_size.y;
+				let abottom = glyph.atlas.maxs.y / self.atlas_size.y;
+
+				let template = TextVertex { pos: Vec2::ZERO, uv: Vec2::ZERO, color: scribe.color, outline: scribe.outline };
+				let vertices = [
+					TextVertex { pos: pos + Vec2(pleft, pbottom), uv: Vec2(aleft, abottom), ..template },
+					TextVertex { pos: pos + Vec2(pleft + scribe.top_skew, ptop), uv: Vec2(aleft, atop), ..template },
+					TextVertex { pos: pos + Vec2(pright + scribe.top_skew, ptop), uv: Vec2(aright, atop), ..template },
+					TextVertex { pos: pos + Vec2(pright, pbottom), uv: Vec2(aright, abottom), ..template },
+				];
+
+				let mut p = cv.begin(PrimType::Triangles, 4, 2);
+				p.add_indices_quad();
+				p.add_vertices(&vertices);
+			}
+		}
+	}
+
+	#[inline]
+	fn has_glyph(&self, chr: char) -> bool {
+		self.glyphs.contains_key(&chr)
+	}
+}
@@ -176,11 +176,9 @@ pub(crate) fn process(sequence: &str, scribe: &mut Scribe, _cv: Option<&mut Text
 	let mut success = false;
 	if let Some(&first) = sequence.as_bytes().first() {
 		for i in 0..handlers.len() {
-			if first == key_chars[i] {
-				if handlers[i](sequence) {
-					success = true;
-					break;
-				}
+			if first == key_chars[i] && handlers[i](sequence) {
+				success = true;
+				break;
 			}
 		}
 	}
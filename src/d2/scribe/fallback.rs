@@ -0,0 +1,111 @@
+use super::*;
+
+/// Font fallback chain.
+///
+/// Glyphs missing from the primary font (the first font in the chain) are taken from the first fallback font which has them.
+/// Characters missing from every font are handed to the primary font.
+///
+/// When writing to a text buffer the shader and atlas texture are switched per run of glyphs from the same font.
+/// Fallback atlases should be generated with the same distance range and size as the primary atlas.
+#[derive(Clone, Debug, Default)]
+pub struct FontChain<F> {
+	/// The fonts in order of preference.
+	pub fonts: Vec<FontResource<F>>,
+}
+
+impl<F: IFont> FontChain<F> {
+	/// Creates a new font chain with the primary font.
+	#[inline]
+	pub fn new(primary: FontResource<F>) -> FontChain<F> {
+		FontChain { fonts: vec![primary] }
+	}
+
+	/// Adds a fallback font to the end of the chain.
+	#[inline]
+	pub fn push(&mut self, fallback: FontResource<F>) {
+		self.fonts.push(fallback);
+	}
+
+	/// Returns the index of the font used to render the character.
+	#[inline]
+	pub fn select(&self, chr: char) -> usize {
+		self.fonts.iter().position(|res| res.font.has_glyph(chr)).unwrap_or(0)
+	}
+
+	/// Returns a font resource for the chain using the primary font's texture and shader.
+	#[inline]
+	pub fn as_resource(&self) -> FontResource<&FontChain<F>> {
+		let (texture, shader) = match self.fonts.first() {
+			Some(res) => (res.texture, res.shader),
+			None => (Texture2D::INVALID, Shader::INVALID),
+		};
+		FontResource { font: self, texture, shader }
+	}
+
+	fn write_run(&self, cv: Option<&mut TextBuffer>, scribe: &mut Scribe, cursor: &mut Vec2<f32>, index: usize, text: &str) {
+		let res = &self.fonts[index];
+		let mut cv = cv;
+		if let Some(cv) = &mut cv {
+			cv.shader = res.shader;
+			set_texture(cv, res.texture);
+		}
+		res.font.write_span(cv, scribe, cursor, text);
+	}
+}
+
+impl<F: IFont> IFont for FontChain<F> {
+	fn write_span(&self, cv: Option<&mut TextBuffer>, scribe: &mut Scribe, cursor: &mut Vec2<f32>, text: &str) {
+		if self.fonts.is_empty() {
+			return;
+		}
+
+		let mut cv = cv;
+		let state = cv.as_mut().map(|cv| (cv.shader, cv.get_uniform().texture));
+
+		// Split the text in runs of glyphs from the same font
+		// Escape sequences and newlines stay with the run they appear in
+		let mut current = None;
+		let mut start = 0;
+		let mut pos = 0;
+		while let Some(chr) = text[pos..].chars().next() {
+			if chr == '\x1b' {
+				pos = match text[pos..].find(']') {
+					Some(end) => pos + end + 1,
+					None => text.len(),
+				};
+				continue;
+			}
+			if chr != '\n' {
+				let index = self.select(chr);
+				if current != Some(index) {
+					if let Some(current) = current {
+						self.write_run(cv.as_deref_mut(), scribe, cursor, current, &text[start..pos]);
+						start = pos;
+					}
+					current = Some(index);
+				}
+			}
+			pos += chr.len_utf8();
+		}
+		if start < text.len() {
+			self.write_run(cv.as_deref_mut(), scribe, cursor, current.unwrap_or(0), &text[start..]);
+		}
+
+		// Restore the shader and texture for subsequent text
+		if let (Some(cv), Some((shader, texture))) = (cv, state) {
+			cv.shader = shader;
+			set_texture(cv, texture);
+		}
+	}
+
+	#[inline]
+	fn has_glyph(&self, chr: char) -> bool {
+		self.fonts.iter().any(|res| res.font.has_glyph(chr))
+	}
+}
+
+pub(super) fn set_texture(cv: &mut TextBuffer, texture: Texture2D) {
+	if cv.get_uniform().texture != texture {
+		cv.push_uniform_f(|u| TextUniform { texture, ..*u });
+	}
+}
@@ -4,20 +4,50 @@ use super::*;
 pub trait IFont {
 	/// Write a span of text to the text buffer.
 	fn write_span(&self, cv: Option<&mut TextBuffer>, scribe_st: &mut Scribe, cursor: &mut Vec2<f32>, text: &str);
+
+	/// Returns true if the font has a glyph for the character.
+	#[inline]
+	fn has_glyph(&self, _chr: char) -> bool {
+		true
+	}
+
+	/// Returns a value identifying the font, used to key cached text.
+	///
+	/// Defaults to the address of the font.
+	#[inline]
+	fn font_id(&self) -> usize {
+		self as *const Self as *const () as usize
+	}
 }
 
-impl<'a, T: ?Sized + IFont> IFont for &'a T {
+impl<T: ?Sized + IFont> IFont for &T {
 	#[inline]
 	fn write_span(&self, cv: Option<&mut TextBuffer>, scribe_st: &mut Scribe, cursor: &mut Vec2<f32>, text: &str) {
 		(**self).write_span(cv, scribe_st, cursor, text)
 	}
+	#[inline]
+	fn has_glyph(&self, chr: char) -> bool {
+		(**self).has_glyph(chr)
+	}
+	#[inline]
+	fn font_id(&self) -> usize {
+		(**self).font_id()
+	}
 }
 
-impl<'a, T: ?Sized + IFont> IFont for &'a mut T {
+impl<T: ?Sized + IFont> IFont for &mut T {
 	#[inline]
 	fn write_span(&self, cv: Option<&mut TextBuffer>, scribe_st: &mut Scribe, cursor: &mut Vec2<f32>, text: &str) {
 		(**self).write_span(cv, scribe_st, cursor, text)
 	}
+	#[inline]
+	fn has_glyph(&self, chr: char) -> bool {
+		(**self).has_glyph(chr)
+	}
+	#[inline]
+	fn font_id(&self) -> usize {
+		(**self).font_id()
+	}
 }
 
 impl<T: IFont> IFont for Option<T> {
@@ -26,6 +56,17 @@ impl<T: IFont> IFont for Option<T> {
 		let Some(font) = self else { return };
 		font.write_span(cv, scribe_st, cursor, text)
 	}
+	#[inline]
+	fn has_glyph(&self, chr: char) -> bool {
+		matches!(self, Some(font) if font.has_glyph(chr))
+	}
+	#[inline]
+	fn font_id(&self) -> usize {
+		match self {
+			Some(font) => font.font_id(),
+			_ => self as *const Self as usize,
+		}
+	}
 }
 
 impl<T: IFont, E> IFont for Result<T, E> {
@@ -34,4 +75,15 @@ impl<T: IFont, E> IFont for Result<T, E> {
 		let Ok(font) = self else { return };
 		font.write_span(cv, scribe_st, cursor, text)
 	}
+	#[inline]
+	fn has_glyph(&self, chr: char) -> bool {
+		matches!(self, Ok(font) if font.has_glyph(chr))
+	}
+	#[inline]
+	fn font_id(&self) -> usize {
+		match self {
+			Ok(font) => font.font_id(),
+			_ => self as *const Self as usize,
+		}
+	}
 }
@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+use super::*;
+
+/// Plural category of a count.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Plural {
+	Zero,
+	One,
+	Two,
+	Few,
+	Many,
+	Other,
+}
+
+/// Locale hooks for the text layer.
+///
+/// The default implementations format like the `en` locale.
+pub trait ILocale {
+	/// Locale identifier, eg. `en-US`.
+	fn id(&self) -> &str;
+
+	/// Formats a number with the number of decimals.
+	fn format_number(&self, value: f64, decimals: usize, out: &mut String) {
+		let _ = write!(out, "{:.*}", decimals, value);
+	}
+
+	/// Formats a calendar date.
+	fn format_date(&self, year: i32, month: u32, day: u32, out: &mut String) {
+		let _ = write!(out, "{:04}-{:02}-{:02}", year, month, day);
+	}
+
+	/// Returns the plural category of the count.
+	fn plural(&self, count: i64) -> Plural {
+		if count == 1 { Plural::One } else { Plural::Other }
+	}
+
+	/// Looks up the localized string for the key and plural category.
+	fn lookup(&self, key: &str, plural: Plural) -> Option<&str>;
+}
+
+impl dyn ILocale + '_ {
+	/// Looks up the localized string for the count and replaces `{n}` with the formatted count.
+	///
+	/// Falls back to the `Other` plural category and then to the key itself.
+	pub fn plural_text(&self, key: &str, count: i64) -> String {
+		let plural = self.plural(count);
+		let template = self.lookup(key, plural).or_else(|| self.lookup(key, Plural::Other)).unwrap_or(key);
+		let mut number = String::new();
+		self.format_number(count as f64, 0, &mut number);
+		template.replace("{n}", &number)
+	}
+}
+
+/// Caches text widths per locale, font and scribe metrics.
+///
+/// Localized strings are measured often by layout code, the cache is cleared when the locale changes.
+#[derive(Clone, Debug, Default)]
+pub struct TextMetricsCache {
+	locale: String,
+	widths: HashMap<(String, usize, [u32; 4]), f32>,
+}
+
+impl TextMetricsCache {
+	/// Creates a new empty cache.
+	pub fn new() -> TextMetricsCache {
+		TextMetricsCache::default()
+	}
+
+	/// Returns the width of the text, measuring it if it is not cached.
+	pub fn text_width(&mut self, locale: &dyn ILocale, font: &dyn IFont, scribe: &Scribe, text: &str) -> f32 {
+		if self.locale != locale.id() {
+			self.locale.clear();
+			self.locale.push_str(locale.id());
+			self.widths.clear();
+		}
+		let metrics = [scribe.font_size, scribe.font_width_scale, scribe.x_pos, scribe.letter_spacing].map(f32::to_bits);
+		let key = (text.to_string(), font.font_id(), metrics);
+		if let Some(&width) = self.widths.get(&key) {
+			return width;
+		}
+		let width = scribe.text_width(&mut {Vec2::ZERO}, font, text);
+		self.widths.insert(key, width);
+		width
+	}
+
+	/// Clears the cached widths.
+	#[inline]
+	pub fn clear(&mut self) {
+		self.widths.clear();
+	}
+}
+
+impl TextBuffer {
+	/// Writes the localized string for the count.
+	pub fn text_plural(&mut self, font: &FontResource<impl IFont>, scribe: &mut Scribe, cursor: &mut Vec2<f32>, locale: &dyn ILocale, key: &str, count: i64) {
+		let text = locale.plural_text(key, count);
+		self.text_write(font, scribe, cursor, text);
+	}
+}
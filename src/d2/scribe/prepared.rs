@@ -0,0 +1,147 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use super::*;
+use super::fallback::set_texture;
+
+/// Run of glyph quads drawn with the same shader and texture.
+#[derive(Copy, Clone, Debug)]
+struct PreparedRun {
+	shader: Shader,
+	/// The texture selected by the font, invalid if the run is drawn with the current texture.
+	texture: Texture2D,
+	/// Number of glyph quads in the run.
+	quads: usize,
+}
+
+/// Prepared text.
+///
+/// Caches the glyph quads of a text string so it can be drawn repeatedly without re-tessellating every glyph.
+/// The quads are regenerated only when the font, scribe, cursor or text changes.
+///
+/// The shader and texture of every run of glyphs are cached with the quads, text prepared with a [`FontChain`] keeps its fallback fonts.
+/// The font is identified by its [`font_id`](IFont::font_id), [`invalidate`](PreparedText::invalidate) the prepared text when the font is modified in place.
+#[derive(Clone, Debug, Default)]
+pub struct PreparedText {
+	key: u64,
+	shader: Shader,
+	runs: Vec<PreparedRun>,
+	vertices: Vec<TextVertex>,
+	/// The cursor position after writing the text.
+	pub cursor: Vec2<f32>,
+}
+
+impl PreparedText {
+	/// Creates a new empty prepared text.
+	#[inline]
+	pub fn new() -> PreparedText {
+		PreparedText::default()
+	}
+
+	/// Prepares the text string.
+	///
+	/// Returns true if the glyph quads were regenerated.
+	pub fn prepare<T: fmt::Display>(&mut self, font: &FontResource<impl IFont>, scribe: &Scribe, cursor: Vec2<f32>, text: T) -> bool {
+		let text = text.to_string();
+		let key = prepare_key(font.font.font_id(), scribe, cursor, &text);
+		if key == self.key {
+			return false;
+		}
+
+		let mut buf = TextBuffer::new();
+		let mut scribe = scribe.clone();
+		let mut end = cursor;
+		buf.text_write(font, &mut scribe, &mut end, &text);
+
+		// Every command is a run of glyphs, merge the consecutive runs sharing the shader and texture
+		self.runs.clear();
+		for cmd in &buf.commands {
+			let texture = buf.uniforms[cmd.uniform_index as usize].texture;
+			let quads = (cmd.vertex_end - cmd.vertex_start) as usize / 4;
+			match self.runs.last_mut() {
+				Some(run) if run.shader == cmd.shader && run.texture == texture => run.quads += quads,
+				_ => self.runs.push(PreparedRun { shader: cmd.shader, texture, quads }),
+			}
+		}
+
+		self.key = key;
+		self.shader = font.shader;
+		self.vertices = buf.vertices;
+		self.cursor = end;
+		true
+	}
+
+	/// Returns true if there are no glyphs to draw.
+	#[inline]
+	pub fn is_empty(&self) -> bool {
+		self.vertices.is_empty()
+	}
+
+	/// Returns the bounding box of the glyph quads.
+	pub fn bounds(&self) -> Rect<f32> {
+		let Some(first) = self.vertices.first() else { return Rect::ZERO };
+		let mut bounds = Rect::new(first.pos, first.pos);
+		for v in &self.vertices {
+			bounds.mins.x = f32::min(bounds.mins.x, v.pos.x);
+			bounds.mins.y = f32::min(bounds.mins.y, v.pos.y);
+			bounds.maxs.x = f32::max(bounds.maxs.x, v.pos.x);
+			bounds.maxs.y = f32::max(bounds.maxs.y, v.pos.y);
+		}
+		bounds
+	}
+
+	/// Forgets the cached glyph quads.
+	#[inline]
+	pub fn invalidate(&mut self) {
+		self.key = 0;
+		self.runs.clear();
+		self.vertices.clear();
+	}
+}
+
+impl TextBuffer {
+	/// Draws a prepared text string translated by the offset.
+	///
+	/// Like writing the text, the shader is left set to the font's shader and the texture is restored after the runs of fallback fonts.
+	#[inline(never)]
+	pub fn text_prepared(&mut self, text: &PreparedText, offset: Vec2<f32>) {
+		if text.vertices.is_empty() {
+			return;
+		}
+		let texture = self.get_uniform().texture;
+		let mut start = 0;
+		for run in &text.runs {
+			self.shader = run.shader;
+			set_texture(self, if run.texture != Texture2D::INVALID { run.texture } else { texture });
+
+			let vertices = &text.vertices[start * 4..(start + run.quads) * 4];
+			start += run.quads;
+			let mut cv = self.begin(PrimType::Triangles, run.quads * 4, run.quads * 2);
+			for i in 0..run.quads as u32 {
+				let v = i * 4;
+				cv.add_indices(&[v, v + 1, v + 2, v, v + 2, v + 3]);
+			}
+			for v in vertices {
+				cv.add_vertex(TextVertex { pos: v.pos + offset, ..*v });
+			}
+		}
+		self.shader = text.shader;
+		set_texture(self, texture);
+	}
+}
+
+fn prepare_key(font_id: usize, scribe: &Scribe, cursor: Vec2<f32>, text: &str) -> u64 {
+	let mut hasher = DefaultHasher::new();
+	font_id.hash(&mut hasher);
+	for value in [
+		scribe.font_size, scribe.font_width_scale, scribe.line_height, scribe.baseline,
+		scribe.x_pos, scribe.letter_spacing, scribe.top_skew, cursor.x, cursor.y,
+	] {
+		value.to_bits().hash(&mut hasher);
+	}
+	[scribe.color.x, scribe.color.y, scribe.color.z, scribe.color.w].hash(&mut hasher);
+	[scribe.outline.x, scribe.outline.y, scribe.outline.z, scribe.outline.w].hash(&mut hasher);
+	text.hash(&mut hasher);
+	// Reserve zero for the invalidated state
+	hasher.finish() | 1
+}
@@ -1,5 +1,22 @@
 use super::*;
 
+/// Which parts of the glyphs are rendered.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+#[repr(i32)]
+pub enum TextLayer {
+	/// Fill and outline in a single pass.
+	#[default]
+	Combined = 0,
+	/// Only the fill.
+	Fill = 1,
+	/// The outline including the area covered by the fill.
+	///
+	/// Render in a separate pass behind the fill to avoid the outline of a glyph overlapping its neighbours.
+	Outline = 2,
+	/// Only the outline for hollow text.
+	Stroke = 3,
+}
+
 /// Text uniform.
 #[derive(Copy, Clone, Debug, dataview::Pod)]
 #[repr(C)]
@@ -12,6 +29,8 @@ pub struct TextUniform {
 	pub outline_width_absolute: f32,
 	pub outline_width_relative: f32,
 	pub gamma: f32,
+	/// The [`TextLayer`] to render.
+	pub layer: i32,
 }
 
 impl Default for TextUniform {
@@ -25,6 +44,7 @@ impl Default for TextUniform {
 			outline_width_absolute: 1.0,
 			outline_width_relative: 0.125,
 			gamma: 1.0,
+			layer: TextLayer::Combined as i32,
 		}
 	}
 }
@@ -82,6 +102,12 @@ unsafe impl TUniform for TextUniform {
 				offset: dataview::offset_of!(TextUniform.gamma) as u16,
 				len: 1,
 			},
+			UniformAttribute {
+				name: "u_layer",
+				ty: UniformType::I1,
+				offset: dataview::offset_of!(TextUniform.layer) as u16,
+				len: 1,
+			},
 		],
 	};
 }
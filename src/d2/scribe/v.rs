@@ -14,6 +14,7 @@ unsafe impl TVertex for TextVertex {
 	const VERTEX_LAYOUT: &'static crate::VertexLayout = &crate::VertexLayout {
 		size: std::mem::size_of::<TextVertex>() as u16,
 		alignment: std::mem::align_of::<TextVertex>() as u16,
+		instance_divisor: 0,
 		attributes: &[
 			VertexAttribute {
 				format: VertexAttributeFormat::F32,
@@ -45,3 +46,26 @@ impl ToVertex<TextVertex> for TextVertex {
 		TextVertex { pos, ..*self }
 	}
 }
+
+impl Position for TextVertex {
+	#[inline]
+	fn position(&self) -> Point2<f32> {
+		self.pos
+	}
+}
+
+impl Lerp for TextVertex {
+	#[inline]
+	fn lerp(&self, rhs: &TextVertex, t: f32) -> TextVertex {
+		let color = |a: Vec4<u8>, b: Vec4<u8>| {
+			let f = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+			Vec4(f(a.x, b.x), f(a.y, b.y), f(a.z, b.z), f(a.w, b.w))
+		};
+		TextVertex {
+			pos: self.pos + (rhs.pos - self.pos) * t,
+			uv: self.uv + (rhs.uv - self.uv) * t,
+			color: color(self.color, rhs.color),
+			outline: color(self.outline, rhs.outline),
+		}
+	}
+}
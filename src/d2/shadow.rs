@@ -0,0 +1,96 @@
+use super::*;
+
+/// Shadow casts soft drop shadows behind shapes.
+///
+/// The shadow is solid inside the shape and fades out linearly across the blur radius.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Shadow<T> {
+	/// Offset of the shadow from the shape.
+	pub offset: Vec2<f32>,
+	/// Width of the blurred edge, centered on the outline of the shape.
+	pub radius: f32,
+	/// Vertex template for the solid part of the shadow.
+	pub color: T,
+	/// Vertex template for the outer edge of the shadow, typically the same color with zero alpha.
+	pub fade: T,
+}
+
+impl<T: Clone> Paint<T> {
+	/// Creates a shadow with the color of the paint.
+	///
+	/// The fade template is used at the outer edge of the blur radius.
+	#[inline]
+	pub fn shadow(&self, offset: Vec2<f32>, radius: f32, fade: T) -> Shadow<T> {
+		Shadow { offset, radius, color: self.template.clone(), fade }
+	}
+}
+
+impl<V: TVertex, U: TUniform> CommandBuffer<V, U> {
+	/// Draws the shadow of a rectangle.
+	#[inline]
+	pub fn shadow_rect<T: ToVertex<V>>(&mut self, shadow: &Shadow<T>, rc: &Rect<f32>, segments: i32) {
+		self.shadow_round_rect(shadow, rc, 0.0, segments)
+	}
+
+	/// Draws the shadow of a rounded rectangle.
+	///
+	/// The segments are the number of segments per corner.
+	#[inline(never)]
+	pub fn shadow_round_rect<T: ToVertex<V>>(&mut self, shadow: &Shadow<T>, rc: &Rect<f32>, corner: f32, segments: i32) {
+		let rc = Rect::new(rc.mins + shadow.offset, rc.maxs + shadow.offset);
+		let half = f32::max(shadow.radius, 0.0) * 0.5;
+		let corner = f32::max(0.0, f32::min(corner, f32::min(rc.width(), rc.height()) * 0.5));
+
+		// Shrink the inner contour toward the center without flipping it inside out
+		let shrink = Vec2(f32::min(half, rc.width() * 0.5), f32::min(half, rc.height() * 0.5));
+		let inner = Rect::new(rc.mins + shrink, rc.maxs - shrink);
+		let outer = Rect::new(rc.mins - Vec2::dup(half), rc.maxs + Vec2::dup(half));
+
+		let n = cmp::max(1, segments) as usize;
+		let mut inner_pts = Vec::with_capacity(4 * (n + 1));
+		let mut outer_pts = Vec::with_capacity(4 * (n + 1));
+		round_rect_contour(&inner, f32::max(0.0, corner - half), n, &mut inner_pts);
+		round_rect_contour(&outer, corner + half, n, &mut outer_pts);
+
+		// m * 2 vertices, (m - 2) + m * 2 primitives
+		let m = inner_pts.len();
+		let mut cv = self.begin(PrimType::Triangles, m * 2, (m - 2) + m * 2);
+
+		// Solid interior as a fan of the inner contour
+		for i in 1..m - 1 {
+			cv.add_index3(0, i as u32, i as u32 + 1);
+		}
+		// Fading edge between the inner and outer contours
+		for i in 0..m {
+			let j = (i + 1) % m;
+			let (a, b) = (i as u32, j as u32);
+			let (c, d) = ((m + i) as u32, (m + j) as u32);
+			cv.add_index3(a, c, d);
+			cv.add_index3(a, d, b);
+		}
+
+		for (v, &pt) in inner_pts.iter().enumerate() {
+			cv.add_vertex(shadow.color.to_vertex(pt, v));
+		}
+		for (v, &pt) in outer_pts.iter().enumerate() {
+			cv.add_vertex(shadow.fade.to_vertex(pt, m + v));
+		}
+	}
+}
+
+/// Generates the contour of a rounded rectangle with `n + 1` points per corner.
+fn round_rect_contour(rc: &Rect<f32>, corner: f32, n: usize, pts: &mut Vec<Point2<f32>>) {
+	let centers = [
+		Point2(rc.maxs.x - corner, rc.maxs.y - corner),
+		Point2(rc.mins.x + corner, rc.maxs.y - corner),
+		Point2(rc.mins.x + corner, rc.mins.y + corner),
+		Point2(rc.maxs.x - corner, rc.mins.y + corner),
+	];
+	for (k, &center) in centers.iter().enumerate() {
+		for i in 0..=n {
+			let angle = (k as f32 + i as f32 / n as f32) * std::f32::consts::FRAC_PI_2;
+			let (s, c) = angle.sin_cos();
+			pts.push(center + Vec2(c, s) * corner);
+		}
+	}
+}
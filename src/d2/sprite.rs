@@ -0,0 +1,172 @@
+use super::*;
+
+/// Sprite instance.
+///
+/// Per-instance attributes of a sprite quad drawn by a [`SpriteBatch`].
+#[derive(Copy, Clone, Debug, Default, dataview::Pod)]
+#[repr(C)]
+pub struct SpriteInstance {
+	/// Position of the first corner of the quad.
+	pub pos: Vec2<f32>,
+	/// Size of the quad, the opposite corner is at `pos + size`.
+	pub size: Vec2<f32>,
+	/// Texture coordinates of the first and opposite corners.
+	pub uv: Vec4<f32>,
+	/// Color multiplied with the texture.
+	pub color: Vec4<u8>,
+}
+
+unsafe impl TVertex for SpriteInstance {
+	const VERTEX_LAYOUT: &'static VertexLayout = &VertexLayout {
+		size: std::mem::size_of::<SpriteInstance>() as u16,
+		alignment: std::mem::align_of::<SpriteInstance>() as u16,
+		instance_divisor: 1,
+		attributes: &[
+			VertexAttribute {
+				format: VertexAttributeFormat::F32,
+				len: 2,
+				offset: dataview::offset_of!(SpriteInstance.pos) as u16,
+			},
+			VertexAttribute {
+				format: VertexAttributeFormat::F32,
+				len: 2,
+				offset: dataview::offset_of!(SpriteInstance.size) as u16,
+			},
+			VertexAttribute {
+				format: VertexAttributeFormat::F32,
+				len: 4,
+				offset: dataview::offset_of!(SpriteInstance.uv) as u16,
+			},
+			VertexAttribute {
+				format: VertexAttributeFormat::U8Norm,
+				len: 4,
+				offset: dataview::offset_of!(SpriteInstance.color) as u16,
+			},
+		],
+	};
+}
+
+/// Sprite uniform.
+#[derive(Copy, Clone, Debug, dataview::Pod)]
+#[repr(C)]
+pub struct SpriteUniform {
+	pub transform: Transform2<f32>,
+	pub texture: Texture2D,
+}
+
+impl Default for SpriteUniform {
+	fn default() -> Self {
+		SpriteUniform {
+			transform: Transform2::IDENTITY,
+			texture: Texture2D::INVALID,
+		}
+	}
+}
+
+unsafe impl TUniform for SpriteUniform {
+	const UNIFORM_LAYOUT: &'static UniformLayout = &UniformLayout {
+		size: std::mem::size_of::<SpriteUniform>() as u16,
+		alignment: std::mem::align_of::<SpriteUniform>() as u16,
+		attributes: &[
+			UniformAttribute {
+				name: "u_transform",
+				ty: UniformType::Mat3x2 { order: UniformMatOrder::RowMajor },
+				offset: dataview::offset_of!(SpriteUniform.transform) as u16,
+				len: 1,
+			},
+			UniformAttribute {
+				name: "u_texture",
+				ty: UniformType::Sampler2D(0),
+				offset: dataview::offset_of!(SpriteUniform.texture) as u16,
+				len: 1,
+			},
+		],
+	};
+}
+
+/// Sprite batch.
+///
+/// Draws many sprites sharing a texture with a single instanced draw call.
+/// Every sprite is one [`SpriteInstance`], the quad corners are generated in the vertex shader.
+/// Compile the shader from `gl::SPRITE_VS` and `gl::SPRITE_FS`.
+pub struct SpriteBatch {
+	pub instances: Vec<SpriteInstance>,
+	pub uniform: SpriteUniform,
+	pub blend_mode: BlendMode,
+	pub shader: Shader,
+	pub viewport: Rect<i32>,
+	pub scissor_test: Option<Rect<i32>>,
+}
+
+impl Default for SpriteBatch {
+	#[inline]
+	fn default() -> Self {
+		SpriteBatch::new()
+	}
+}
+
+impl SpriteBatch {
+	/// Creates a new sprite batch.
+	pub fn new() -> Self {
+		SpriteBatch {
+			instances: Vec::new(),
+			uniform: SpriteUniform::default(),
+			blend_mode: BlendMode::Alpha,
+			shader: Shader::INVALID,
+			viewport: Rect::ZERO,
+			scissor_test: None,
+		}
+	}
+
+	/// Clears the sprites for reuse.
+	pub fn clear(&mut self) {
+		self.instances.clear();
+	}
+
+	/// Adds a sprite covering the rectangle.
+	#[inline]
+	pub fn push(&mut self, rc: &Rect<f32>, uv: &Rect<f32>, color: Vec4<u8>) {
+		self.instances.push(SpriteInstance {
+			pos: rc.mins,
+			size: rc.maxs - rc.mins,
+			uv: Vec4(uv.mins.x, uv.mins.y, uv.maxs.x, uv.maxs.y),
+			color,
+		});
+	}
+
+	/// Draws all sprites with a single instanced draw call.
+	///
+	/// Requires instancing support from the graphics backend.
+	pub fn draw_instanced(&self, g: &mut Graphics, surface: Surface) -> Result<(), GfxError> {
+		if self.instances.is_empty() {
+			return Ok(());
+		}
+
+		let vb = g.vertex_buffer(None, &self.instances, BufferUsage::Stream)?;
+		let ub = g.uniform_buffer(None, &[self.uniform])?;
+
+		let result = g.draw(&DrawArgs {
+			surface,
+			viewport: self.viewport,
+			scissor: self.scissor_test,
+			blend_mode: self.blend_mode,
+			depth_test: None,
+			cull_mode: None,
+			fill_mode: FillMode::Solid,
+			alpha_to_coverage: false,
+			stencil: None,
+			prim_type: PrimType::TriangleStrip,
+			shader: self.shader,
+			vertices: vb,
+			uniforms: ub,
+			vertex_start: 0,
+			vertex_end: 4,
+			uniform_index: 0,
+			instances: self.instances.len() as i32,
+		});
+
+		g.uniform_buffer_delete(ub, true)?;
+		g.vertex_buffer_delete(vb, true)?;
+		result
+	}
+}
@@ -0,0 +1,20 @@
+use super::*;
+
+#[test]
+fn behind_camera() {
+	// Right-handed camera looking down the negative Z axis
+	let view_proj = Conventions::DEFAULT.perspective_fov(Deg(90.0), 100.0, 100.0, 0.1, 100.0);
+	let viewport = Rect::c(0.0, 0.0, 100.0, 100.0);
+	assert_eq!(d3_to_d2(&view_proj, &viewport, Vec3(0.5, 0.0, 1.0)), None);
+
+	let anchor = d3_to_d2_clamped(&view_proj, &viewport, Vec3(0.5, 0.0, 1.0), 0.0);
+	assert!(!anchor.on_screen);
+	assert_eq!(anchor.direction, Vec2(1.0, 0.0));
+	assert_eq!(anchor.position, Point2(100.0, 50.0));
+}
+
+#[test]
+fn anchor_rect() {
+	let anchor = d3_to_d2_clamped(&Mat4::IDENTITY, &Rect::c(0.0, 0.0, 100.0, 100.0), Vec3(0.0, 0.0, 0.0), 0.0);
+	assert_eq!(anchor.rect(Vec2(20.0, 10.0), Vec2(0.5, 1.0)), Rect::c(40.0, 40.0, 60.0, 50.0));
+}
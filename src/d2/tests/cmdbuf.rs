@@ -0,0 +1,75 @@
+use super::*;
+
+#[test]
+fn merge_lists() {
+	let mut cbuf = CommandBuffer::<MockVertex, MockUniform>::new();
+	{
+		let mut cv = cbuf.begin(PrimType::Triangles, 3, 1);
+		cv.add_index3(0, 1, 2);
+		cv.add_vertices(&[MockVertex::default(); 3]);
+	}
+	{
+		let mut cv = cbuf.begin(PrimType::Triangles, 3, 1);
+		cv.add_index3(0, 1, 2);
+		cv.add_vertices(&[MockVertex::default(); 3]);
+	}
+	assert_eq!(cbuf.commands.len(), 1);
+	assert_eq!(cbuf.indices, &[0, 1, 2, 3, 4, 5]);
+}
+
+#[test]
+fn no_merge_strips() {
+	let mut cbuf = CommandBuffer::<MockVertex, MockUniform>::new();
+	for _ in 0..2 {
+		let mut cv = cbuf.begin(PrimType::TriangleStrip, 4, 2);
+		cv.add_indices(&[0, 1, 2, 3]);
+		cv.add_vertices(&[MockVertex::default(); 4]);
+	}
+	assert_eq!(cbuf.commands.len(), 2);
+	assert_eq!(cbuf.indices, &[0, 1, 2, 3, 4, 5, 6, 7]);
+}
+
+#[test]
+fn commands_bounds() {
+	let mut cbuf = CommandBuffer::<MockVertex, MockUniform>::new();
+	{
+		let mut cv = cbuf.begin(PrimType::Triangles, 3, 1);
+		cv.add_index3(0, 1, 2);
+		cv.add_vertices(&[MockVertex { pos: Point2::new(1.0, 2.0) }, MockVertex { pos: Point2::new(4.0, -1.0) }, MockVertex { pos: Point2::new(2.0, 5.0) }]);
+	}
+	cbuf.blend_mode = BlendMode::Alpha;
+	{
+		let mut cv = cbuf.begin(PrimType::Lines, 2, 1);
+		cv.add_index2(0, 1);
+		cv.add_vertices(&[MockVertex { pos: Point2::new(-3.0, 0.0) }, MockVertex { pos: Point2::new(-3.0, 8.0) }]);
+	}
+	let bounds = cbuf.commands_bounds();
+	assert_eq!(bounds.len(), 2);
+	assert_eq!((bounds[0].mins, bounds[0].maxs), (Point2::new(1.0, -1.0), Point2::new(4.0, 5.0)));
+	assert_eq!((bounds[1].mins, bounds[1].maxs), (Point2::new(-3.0, 0.0), Point2::new(-3.0, 8.0)));
+}
+
+#[test]
+fn no_merge_user_data() {
+	let mut cbuf = CommandBuffer::<MockVertex, MockUniform>::new();
+	for user_data in [1, 1, 2] {
+		cbuf.user_data = user_data;
+		let mut cv = cbuf.begin(PrimType::Triangles, 3, 1);
+		cv.add_index3(0, 1, 2);
+		cv.add_vertices(&[MockVertex::default(); 3]);
+	}
+	assert_eq!(cbuf.commands.len(), 2);
+}
+
+#[test]
+fn restart_index() {
+	let mut cbuf = CommandBuffer::<MockVertex, MockUniform>::new();
+	for _ in 0..2 {
+		// Two strips of two triangles separated by a restart index
+		let mut cv = cbuf.begin(PrimType::TriangleStrip, 6, 5);
+		cv.add_indices(&[0, 1, 2, u32::MAX, 3, 4, 5]);
+		cv.add_vertices(&[MockVertex::default(); 6]);
+	}
+	assert_eq!(cbuf.commands.len(), 2);
+	assert_eq!(cbuf.indices, &[0, 1, 2, u32::MAX, 3, 4, 5, 6, 7, 8, u32::MAX, 9, 10, 11]);
+}
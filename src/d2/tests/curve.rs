@@ -0,0 +1,62 @@
+use super::*;
+
+fn curve() -> Bezier3 {
+	Bezier3 {
+		p0: Point2::new(0.0, 0.0),
+		p1: Point2::new(0.0, 100.0),
+		p2: Point2::new(100.0, 100.0),
+		p3: Point2::new(100.0, 0.0),
+	}
+}
+
+#[test]
+fn split() {
+	let curve = curve();
+	let (head, tail) = curve.split(0.25);
+	assert_eq!(head.p0, curve.p0);
+	assert_eq!(tail.p3, curve.p3);
+	assert_eq!(head.p3, tail.p0);
+	assert_eq!(head.p3, curve.eval(0.25));
+
+	let mut parts = Vec::new();
+	curve.subdivide(4, &mut parts);
+	assert_eq!(parts.len(), 4);
+	let mid = curve.eval(0.5);
+	assert!((parts[1].p3.x - mid.x).abs() < 0.001 && (parts[1].p3.y - mid.y).abs() < 0.001);
+}
+
+#[test]
+fn bounds() {
+	let bounds = curve().bounds();
+	assert_eq!(bounds.mins, Point2::new(0.0, 0.0));
+	assert_eq!(bounds.maxs.x, 100.0);
+	// The peak of the symmetric curve is at t = 0.5
+	assert!((bounds.maxs.y - 75.0).abs() < 0.001);
+}
+
+#[test]
+fn nearest() {
+	let curve = curve();
+	let t = curve.nearest(Point2::new(50.0, 200.0));
+	assert!((t - 0.5).abs() < 0.001);
+	assert_eq!(curve.nearest(Point2::new(-10.0, -10.0)), 0.0);
+}
+
+#[test]
+fn flatten() {
+	let curve = curve();
+	let mut coarse = Vec::new();
+	curve.flatten(10.0, &mut coarse);
+	let mut fine = Vec::new();
+	curve.flatten(0.1, &mut fine);
+	assert!(coarse.len() < fine.len());
+	assert_eq!(fine.last(), Some(&curve.p3));
+
+	let mut cbuf = CommandBuffer::<MockVertex, MockUniform>::new();
+	let pen = Pen {
+		template: (),
+	};
+	cbuf.draw_curve(&pen, &curve, 1.0);
+	assert_eq!(cbuf.commands.len(), 1);
+	assert!(cbuf.vertices.len() > 2);
+}
@@ -0,0 +1,52 @@
+use super::*;
+
+#[test]
+fn snap() {
+	let grid = Grid {
+		origin: Point2(5.0, 0.0),
+		spacing: Vec2(10.0, 4.0),
+		major: 5,
+	};
+	assert_eq!(grid.snap(Point2(13.0, 5.0)), Point2(15.0, 4.0));
+	assert_eq!(grid.snap(Point2(-6.0, -1.0)), Point2(-5.0, 0.0));
+	assert_eq!(grid.snap_major(Point2(33.0, 11.0)), Point2(55.0, 20.0));
+	assert_eq!(grid.cell(Point2(-6.0, 9.0)), Point2(-2, 2));
+}
+
+#[test]
+fn draw_grid() {
+	let mut cbuf = CommandBuffer::<MockVertex, MockUniform>::new();
+	let pen = Pen {
+		template: (),
+	};
+	let grid = Grid {
+		origin: Point2(0.0, 0.0),
+		spacing: Vec2(10.0, 10.0),
+		major: 2,
+	};
+	cbuf.draw_grid(&pen, &pen, &grid, &Rect::c(0.0, 0.0, 30.0, 20.0));
+	// 4 vertical and 3 horizontal lines, the minor and major batches merge as both pens share the same state
+	assert_eq!(cbuf.commands.len(), 1);
+	assert_eq!(cbuf.vertices.len(), 7 * 2);
+	assert_eq!(cbuf.indices.len(), 7 * 2);
+	// The 3 minor lines come before the 4 major lines
+	assert_eq!(cbuf.vertices[0].pos, Point2(10.0, 0.0));
+	assert_eq!(cbuf.vertices[6].pos, Point2(0.0, 0.0));
+}
+
+#[test]
+fn draw_grid_zoomed_out() {
+	let mut cbuf = CommandBuffer::<MockVertex, MockUniform>::new();
+	let pen = Pen {
+		template: (),
+	};
+	let grid = Grid {
+		origin: Point2(0.0, 0.0),
+		spacing: Vec2(1.0, 1.0),
+		major: 10,
+	};
+	cbuf.draw_grid(&pen, &pen, &grid, &Rect::c(0.0, 0.0, 100000.0, 0.0));
+	// Too many minor and major lines, steps up to every tenth major line
+	assert_eq!(cbuf.vertices.len(), (1001 + 1) * 2);
+	assert_eq!(cbuf.vertices[2].pos, Point2(100.0, 0.0));
+}
@@ -10,6 +10,7 @@ unsafe impl TVertex for MockVertex {
 	const VERTEX_LAYOUT: &'static VertexLayout = &VertexLayout {
 		size: std::mem::size_of::<MockVertex>() as u16,
 		alignment: std::mem::align_of::<MockVertex>() as u16,
+		instance_divisor: 0,
 		attributes: &[
 			VertexAttribute {
 				format: VertexAttributeFormat::F32,
@@ -20,6 +21,20 @@ unsafe impl TVertex for MockVertex {
 	};
 }
 
+impl Lerp for MockVertex {
+	#[inline]
+	fn lerp(&self, rhs: &MockVertex, t: f32) -> MockVertex {
+		MockVertex { pos: self.pos + (rhs.pos - self.pos) * t }
+	}
+}
+
+impl Position for MockVertex {
+	#[inline]
+	fn position(&self) -> Point2<f32> {
+		self.pos
+	}
+}
+
 impl ToVertex<MockVertex> for () {
 	#[inline]
 	fn to_vertex(&self, pos: Point2<f32>, _index: usize) -> MockVertex {
@@ -39,6 +54,11 @@ unsafe impl TUniform for MockUniform {
 	};
 }
 
+mod anchor;
+mod cmdbuf;
+mod curve;
+mod grid;
 mod pen;
 mod paint;
+mod scribe;
 mod stamp;
@@ -138,3 +138,103 @@ fn fill_bezier2() {
 	assert_eq!(cbuf.vertices.len(), 34);
 	assert_eq!(cbuf.indices.len(), 32 * 3);
 }
+
+#[test]
+fn shadow_round_rect() {
+	let mut cbuf = CommandBuffer::<MockVertex, MockUniform>::new();
+	let paint = Paint {
+		template: (),
+	};
+	let shadow = paint.shadow(Vec2::new(2.0, 2.0), 8.0, ());
+	let rc = Rect::new(Point2::new(0.0, 0.0), Point2::new(100.0, 50.0));
+	cbuf.shadow_round_rect(&shadow, &rc, 10.0, 4);
+	// 4 corners with 5 points each for both contours
+	let m = 20;
+	assert_eq!(cbuf.commands.len(), 1);
+	assert_eq!(cbuf.vertices.len(), m * 2);
+	assert_eq!(cbuf.indices.len(), ((m - 2) + m * 2) * 3);
+	// The outer contour extends half the radius beyond the offset shape
+	let max_x = cbuf.vertices.iter().map(|v| v.pos.x).fold(f32::MIN, f32::max);
+	assert_eq!(max_x, 106.0);
+}
+
+#[test]
+fn palette() {
+	let mut palette = Palette::new();
+	palette.set_range(254, &[Vec4(1, 2, 3, 4), Vec4(5, 6, 7, 8), Vec4(9, 9, 9, 9)]);
+	assert_eq!(palette.get(254), Vec4(1, 2, 3, 4));
+	assert_eq!(palette.get(255), Vec4(5, 6, 7, 8));
+	assert_eq!(palette.get(0), Vec4(0, 0, 0, 0));
+
+	let mut cbuf = PaletteBuffer::new();
+	let paint = Paint {
+		template: PaletteVertex::index(7),
+	};
+	cbuf.fill_rect(&paint, &Rect::new(Point2::new(0.0, 0.0), Point2::new(10.0, 10.0)));
+	assert!(cbuf.vertices.iter().all(|v| v.index == 7));
+}
+
+#[test]
+fn fill_gauge() {
+	let mut cbuf = CommandBuffer::<MockVertex, MockUniform>::new();
+	let dial = Dial { start: (), end: () };
+	let rc = Rect::new(Point2::new(0.0, 0.0), Point2::new(100.0, 100.0));
+	let mut arc = DialArc {
+		start: 0.0,
+		sweep: std::f32::consts::PI,
+		thickness: 10.0,
+		round_caps: false,
+		segments: 16,
+	};
+	cbuf.fill_gauge(&dial, &rc, &arc, 0.5);
+	assert_eq!(cbuf.commands.len(), 1);
+	assert_eq!(cbuf.vertices.len(), (8 + 1) * 2);
+	assert_eq!(cbuf.indices.len(), 8 * 2 * 3);
+
+	// Round caps add a fan at both ends
+	cbuf.clear();
+	arc.round_caps = true;
+	cbuf.fill_dial(&dial, &rc, &arc);
+	assert_eq!(cbuf.vertices.len(), (16 + 1) * 2 + (4 + 2) * 2);
+	assert_eq!(cbuf.indices.len(), (16 * 2 + 4 * 2) * 3);
+
+	// Nothing to draw for an empty gauge
+	cbuf.clear();
+	cbuf.fill_gauge(&dial, &rc, &arc, 0.0);
+	assert!(cbuf.vertices.is_empty());
+}
+
+#[test]
+fn auto_segments() {
+	let mut cbuf = CommandBuffer::<MockVertex, MockUniform>::new();
+	assert_eq!(cbuf.arc_segments(19, Vec2(50.0, 50.0), std::f32::consts::TAU), 19);
+	assert_eq!(cbuf.arc_segments(0, Vec2(50.0, 50.0), std::f32::consts::TAU), 32);
+	assert_eq!(cbuf.arc_segments(0, Vec2(5.0, 5.0), std::f32::consts::TAU), 10);
+
+	// Straight curves need a single segment
+	let line = [Point2::new(0.0, 0.0), Point2::new(50.0, 0.0), Point2::new(100.0, 0.0)];
+	assert_eq!(cbuf.bezier_segments(0, &line), 1);
+
+	let paint = Paint { template: () };
+	let rc = Rect::new(Point2::new(0.0, 0.0), Point2::new(100.0, 100.0));
+	cbuf.fill_ellipse(&paint, &rc, 0);
+	assert_eq!(cbuf.vertices.len(), 33);
+}
+
+#[test]
+fn fill_path() {
+	let mut cbuf = CommandBuffer::<MockVertex, MockUniform>::new();
+	let paint = Paint { template: () };
+	let star = [
+		Point2::new(50.0, 0.0),
+		Point2::new(80.0, 100.0),
+		Point2::new(0.0, 35.0),
+		Point2::new(100.0, 35.0),
+		Point2::new(20.0, 100.0),
+	];
+	cbuf.fill_path(&paint, &star, FillRule::EvenOdd);
+	assert_eq!(cbuf.commands.len(), 2);
+	assert_eq!(&cbuf.indices[..9], &[0, 1, 2, 0, 2, 3, 0, 3, 4]);
+	assert_eq!(cbuf.vertices.len(), 9);
+	assert_eq!(cbuf.stencil, None);
+}
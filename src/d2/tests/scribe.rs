@@ -0,0 +1,201 @@
+use super::*;
+
+/// Writes a unit quad for every character.
+struct MockFont;
+
+impl IFont for MockFont {
+	fn write_span(&self, cv: Option<&mut TextBuffer>, scribe: &mut Scribe, cursor: &mut Vec2<f32>, text: &str) {
+		let mut cv = cv;
+		for _ in text.chars() {
+			let pos = *cursor;
+			cursor.x += scribe.font_size;
+			if let Some(cv) = &mut cv {
+				let template = TextVertex::default();
+				cv.stamp_rect(&Stamp {
+					bottom_left: template,
+					top_left: template,
+					top_right: template,
+					bottom_right: template,
+				}, &Rect::new(pos, pos + Vec2(scribe.font_size, scribe.font_size)));
+			}
+		}
+	}
+}
+
+/// Only has glyphs for the given characters.
+struct SubsetFont(&'static str);
+
+impl IFont for SubsetFont {
+	fn write_span(&self, cv: Option<&mut TextBuffer>, scribe: &mut Scribe, cursor: &mut Vec2<f32>, text: &str) {
+		MockFont.write_span(cv, scribe, cursor, text)
+	}
+	fn has_glyph(&self, chr: char) -> bool {
+		self.0.contains(chr)
+	}
+}
+
+/// Advances by a multiple of the font size.
+struct WideFont(f32);
+
+impl IFont for WideFont {
+	fn write_span(&self, _cv: Option<&mut TextBuffer>, scribe: &mut Scribe, cursor: &mut Vec2<f32>, text: &str) {
+		cursor.x += scribe.font_size * self.0 * text.chars().count() as f32;
+	}
+}
+
+#[test]
+fn prepared_text() {
+	let font = FontResource { font: MockFont, texture: Texture2D::INVALID, shader: Shader::INVALID };
+	let scribe = Scribe::default();
+
+	let mut text = PreparedText::new();
+	assert!(text.prepare(&font, &scribe, Vec2::ZERO, "abc"));
+	assert!(!text.prepare(&font, &scribe, Vec2::ZERO, "abc"));
+	assert_eq!(text.cursor, Vec2(scribe.font_size * 3.0, 0.0));
+	assert_eq!(text.bounds(), Rect::c(0.0, 0.0, scribe.font_size * 3.0, scribe.font_size));
+
+	let mut cbuf = TextBuffer::new();
+	cbuf.text_prepared(&text, Vec2::ZERO);
+	cbuf.text_prepared(&text, Vec2(0.0, 100.0));
+	assert_eq!(cbuf.commands.len(), 1);
+	assert_eq!(cbuf.vertices.len(), 4 * 6);
+	assert_eq!(cbuf.vertices[12].pos, cbuf.vertices[0].pos + Vec2(0.0, 100.0));
+	assert_eq!(&cbuf.indices[..6], &[0, 1, 2, 0, 2, 3]);
+
+	assert!(text.prepare(&font, &scribe, Vec2::ZERO, "abcd"));
+	assert!(!text.is_empty());
+}
+
+#[test]
+fn font_chain() {
+	use crate::handle::Handle;
+	let primary = Texture2D::create(1);
+	let fallback = Texture2D::create(2);
+	let primary_shader = Shader::create(1);
+	let fallback_shader = Shader::create(2);
+
+	let mut chain = FontChain::new(FontResource { font: SubsetFont("ab"), texture: primary, shader: primary_shader });
+	chain.push(FontResource { font: SubsetFont("xy"), texture: fallback, shader: fallback_shader });
+	assert_eq!(chain.select('a'), 0);
+	assert_eq!(chain.select('x'), 1);
+	assert_eq!(chain.select('?'), 0);
+
+	let mut cbuf = TextBuffer::new();
+	cbuf.push_uniform(TextUniform { texture: primary, ..Default::default() });
+	let mut scribe = Scribe::default();
+	let mut cursor = Vec2::ZERO;
+	cbuf.text_write(&chain.as_resource(), &mut scribe, &mut cursor, "abxyab");
+
+	assert_eq!(cursor, Vec2(scribe.font_size * 6.0, 0.0));
+	assert_eq!(cbuf.commands.len(), 3);
+	let textures: Vec<_> = cbuf.uniforms.iter().map(|u| u.texture).collect();
+	assert_eq!(textures, [primary, fallback, primary]);
+
+	// Ending on a fallback run restores the primary shader and texture
+	cbuf.text_write(&chain.as_resource(), &mut scribe, &mut cursor, "ax");
+	assert_eq!(cbuf.shader, primary_shader);
+	assert_eq!(cbuf.get_uniform().texture, primary);
+}
+
+#[test]
+fn prepared_font_chain() {
+	use crate::handle::Handle;
+	let primary = Texture2D::create(1);
+	let fallback = Texture2D::create(2);
+	let current = Texture2D::create(3);
+	let primary_shader = Shader::create(1);
+	let fallback_shader = Shader::create(2);
+
+	let mut chain = FontChain::new(FontResource { font: SubsetFont("ab"), texture: primary, shader: primary_shader });
+	chain.push(FontResource { font: SubsetFont("xy"), texture: fallback, shader: fallback_shader });
+	let scribe = Scribe::default();
+
+	let mut text = PreparedText::new();
+	assert!(text.prepare(&chain.as_resource(), &scribe, Vec2::ZERO, "abxy"));
+	assert!(!text.prepare(&chain.as_resource(), &scribe, Vec2::ZERO, "abxy"));
+
+	let mut cbuf = TextBuffer::new();
+	cbuf.push_uniform(TextUniform { texture: current, ..Default::default() });
+	cbuf.text_prepared(&text, Vec2::ZERO);
+
+	// Every run is drawn with the shader and texture of its font
+	assert_eq!(cbuf.vertices.len(), 4 * 4);
+	let shaders: Vec<_> = cbuf.commands.iter().map(|cmd| cmd.shader).collect();
+	assert_eq!(shaders, [primary_shader, fallback_shader]);
+	let textures: Vec<_> = cbuf.commands.iter().map(|cmd| cbuf.uniforms[cmd.uniform_index as usize].texture).collect();
+	assert_eq!(textures, [primary, fallback]);
+
+	// The texture is restored for subsequent text
+	assert_eq!(cbuf.shader, primary_shader);
+	assert_eq!(cbuf.get_uniform().texture, current);
+}
+
+#[test]
+fn bitmap_font() {
+	let mut font = BitmapFont::new(64, 32);
+	font.insert('x', BitmapGlyph {
+		atlas: Rect::c(0.0, 0.0, 32.0, 32.0),
+		plane: Rect::c(0.0, 0.0, 1.0, 1.0),
+		advance: 1.0,
+	});
+	assert!(font.has_glyph('x'));
+	assert!(!font.has_glyph('y'));
+
+	let mut cbuf = TextBuffer::new();
+	let mut scribe = Scribe::default();
+	let mut cursor = Vec2::ZERO;
+	font.write_span(Some(&mut cbuf), &mut scribe, &mut cursor, "xyx");
+
+	assert_eq!(cursor, Vec2(scribe.font_size * 2.0, 0.0));
+	assert_eq!(cbuf.vertices.len(), 8);
+	assert_eq!(cbuf.vertices[0].pos, Vec2(0.0, scribe.font_size));
+	assert_eq!(cbuf.vertices[0].uv, Vec2(0.0, 1.0));
+	assert_eq!(cbuf.vertices[2].uv, Vec2(0.5, 0.0));
+}
+
+struct MockLocale;
+
+impl ILocale for MockLocale {
+	fn id(&self) -> &str {
+		"de"
+	}
+	fn format_number(&self, value: f64, decimals: usize, out: &mut String) {
+		out.push_str(&format!("{:.*}", decimals, value).replace('.', ","));
+	}
+	fn lookup(&self, key: &str, plural: Plural) -> Option<&str> {
+		match (key, plural) {
+			("files", Plural::One) => Some("{n} Datei"),
+			("files", _) => Some("{n} Dateien"),
+			_ => None,
+		}
+	}
+}
+
+#[test]
+fn locale() {
+	let locale: &dyn ILocale = &MockLocale;
+	assert_eq!(locale.plural_text("files", 1), "1 Datei");
+	assert_eq!(locale.plural_text("files", 3), "3 Dateien");
+	assert_eq!(locale.plural_text("missing", 3), "missing");
+
+	let mut number = String::new();
+	locale.format_number(1.5, 2, &mut number);
+	assert_eq!(number, "1,50");
+
+	let scribe = Scribe::default();
+	let mut cache = TextMetricsCache::new();
+	let width = cache.text_width(locale, &MockFont, &scribe, "1 Datei");
+	assert_eq!(width, scribe.font_size * 7.0);
+	assert_eq!(cache.text_width(locale, &MockFont, &scribe, "1 Datei"), width);
+
+	// The same text measured with different fonts is cached separately
+	let narrow = SubsetFont("0123456789");
+	let wide = WideFont(2.0);
+	assert_eq!(cache.text_width(locale, &narrow, &scribe, "42"), scribe.font_size * 2.0);
+	assert_eq!(cache.text_width(locale, &wide, &scribe, "42"), scribe.font_size * 4.0);
+	assert_eq!(cache.text_width(locale, &narrow, &scribe, "42"), scribe.font_size * 2.0);
+
+	// Scribe metrics which change the width are part of the key
+	let small = Scribe { font_size: 8.0, ..scribe.clone() };
+	assert_eq!(cache.text_width(locale, &wide, &small, "42"), small.font_size * 4.0);
+}
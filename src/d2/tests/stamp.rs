@@ -31,3 +31,84 @@ fn stamp_quad() {
 	assert_eq!(cbuf.vertices.len(), 4);
 	assert_eq!(cbuf.indices, &[0, 1, 2, 0, 2, 3]);
 }
+
+#[test]
+fn stamp_rect_clip_convex() {
+	let mut cbuf = CommandBuffer::<MockVertex, MockUniform>::new();
+	let stamp = Stamp {
+		bottom_left: (),
+		top_left: (),
+		top_right: (),
+		bottom_right: (),
+	};
+	let rc = Rect::new(Point2::new(0.0, 0.0), Point2::new(100.0, 100.0));
+	let clip = [
+		Point2::new(50.0, -50.0),
+		Point2::new(150.0, -50.0),
+		Point2::new(150.0, 50.0),
+		Point2::new(50.0, 50.0),
+	];
+	cbuf.stamp_rect_clip_convex(&stamp, &rc, &clip);
+	assert_eq!(cbuf.commands.len(), 1);
+	assert_eq!(cbuf.vertices.len(), 4);
+	assert_eq!(cbuf.indices.len(), 6);
+	for v in &cbuf.vertices {
+		assert!(v.pos.x >= 50.0 && v.pos.x <= 100.0);
+		assert!(v.pos.y >= 0.0 && v.pos.y <= 50.0);
+	}
+
+	// Disjoint clip shapes produce nothing
+	let clip = [Point2::new(200.0, 200.0), Point2::new(300.0, 200.0), Point2::new(300.0, 300.0)];
+	cbuf.stamp_rect_clip_convex(&stamp, &rc, &clip);
+	assert_eq!(cbuf.vertices.len(), 4);
+}
+
+#[test]
+fn stamp_rect_clip_polygon() {
+	let mut cbuf = CommandBuffer::<MockVertex, MockUniform>::new();
+	let stamp = Stamp {
+		bottom_left: (),
+		top_left: (),
+		top_right: (),
+		bottom_right: (),
+	};
+	let rc = Rect::new(Point2::new(0.0, 0.0), Point2::new(100.0, 100.0));
+	// Concave L-shape covering three quarters of the rectangle
+	let clip = [
+		Point2::new(0.0, 0.0),
+		Point2::new(100.0, 0.0),
+		Point2::new(100.0, 50.0),
+		Point2::new(50.0, 50.0),
+		Point2::new(50.0, 100.0),
+		Point2::new(0.0, 100.0),
+	];
+	let triangles = [(0, 1, 2), (0, 2, 3), (0, 3, 5), (3, 4, 5)];
+	cbuf.stamp_rect_clip_polygon(&stamp, &rc, &clip, &triangles);
+	assert_eq!(cbuf.indices.len(), 4 * 3);
+	for v in &cbuf.vertices {
+		assert!(!(v.pos.x > 50.0 && v.pos.y > 50.0));
+	}
+}
+
+#[test]
+fn draw_cursor() {
+	let mut cbuf = CommandBuffer::<MockVertex, MockUniform>::new();
+	let image = CursorImage {
+		stamp: Stamp { bottom_left: (), top_left: (), top_right: (), bottom_right: () },
+		size: Vec2::new(32.0, 32.0),
+		hotspot: Vec2::new(4.0, 2.0),
+	};
+	let mut cursor = Cursor::new(vec![image]);
+	cursor.position = Point2::new(100.0, 50.0);
+	cursor.scale = 2.0;
+	let rc = cursor.rect().unwrap();
+	assert_eq!(rc.mins, Point2::new(92.0, 46.0));
+	assert_eq!(rc.maxs, Point2::new(156.0, 110.0));
+
+	cbuf.draw_cursor(&cursor);
+	assert_eq!(cbuf.vertices.len(), 4);
+
+	cursor.visible = false;
+	cbuf.draw_cursor(&cursor);
+	assert_eq!(cbuf.vertices.len(), 4);
+}
@@ -0,0 +1,139 @@
+use super::*;
+
+/// Critically damped smoothing.
+///
+/// Moves a value towards a target without overshooting, the velocity is carried between updates.
+/// Use it to smooth camera positions and targets.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SmoothDamp<T> {
+	/// Approximate time in seconds to reach the target.
+	pub smooth_time: f32,
+	/// Current velocity.
+	pub velocity: T,
+}
+
+impl<T: Copy + Default + ops::Add<Output = T> + ops::Sub<Output = T> + ops::Mul<f32, Output = T>> SmoothDamp<T> {
+	/// Creates a new smoothing with the smooth time in seconds.
+	#[inline]
+	pub fn new(smooth_time: f32) -> SmoothDamp<T> {
+		SmoothDamp { smooth_time, velocity: T::default() }
+	}
+
+	/// Advances the current value towards the target by the frame time in seconds.
+	pub fn update(&mut self, current: T, target: T, dt: f32) -> T {
+		if self.smooth_time.is_nan() || self.smooth_time <= 0.0 {
+			self.velocity = T::default();
+			return target;
+		}
+		let omega = 2.0 / self.smooth_time;
+		let x = omega * dt;
+		// Pade approximation of exp(-x)
+		let exp = 1.0 / (1.0 + x + 0.48 * x * x + 0.235 * x * x * x);
+		let change = current - target;
+		let temp = (self.velocity + change * omega) * dt;
+		self.velocity = (self.velocity - temp * omega) * exp;
+		target + (change + temp) * exp
+	}
+
+	/// Stops the motion.
+	#[inline]
+	pub fn reset(&mut self) {
+		self.velocity = T::default();
+	}
+}
+
+/// Trauma based shake.
+///
+/// Trauma is added on impacts and decays over time, the shake strength is the square of the trauma.
+/// Apply the offsets as a final transform before building the view matrix.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Shake {
+	/// Current trauma between `0.0` and `1.0`.
+	pub trauma: f32,
+	/// Trauma removed per second.
+	pub decay: f32,
+	/// Translation at full trauma.
+	pub max_offset: cvmath::Vec3<f32>,
+	/// Rotation in radians at full trauma as yaw, pitch and roll.
+	pub max_angle: cvmath::Vec3<f32>,
+	/// Speed of the noise in cycles per second.
+	pub frequency: f32,
+	/// Noise seed, use different seeds for independent shakes.
+	pub seed: u32,
+	time: f32,
+}
+
+impl Shake {
+	/// Creates a new shake without trauma.
+	pub fn new(max_offset: cvmath::Vec3<f32>, max_angle: cvmath::Vec3<f32>) -> Shake {
+		Shake {
+			trauma: 0.0,
+			decay: 1.0,
+			max_offset,
+			max_angle,
+			frequency: 15.0,
+			seed: 0,
+			time: 0.0,
+		}
+	}
+
+	/// Adds trauma, the total is clamped to `1.0`.
+	#[inline]
+	pub fn add_trauma(&mut self, amount: f32) {
+		self.trauma = (self.trauma + amount).clamp(0.0, 1.0);
+	}
+
+	/// Advances the shake by the frame time in seconds.
+	#[inline]
+	pub fn update(&mut self, dt: f32) {
+		self.time += dt;
+		self.trauma = f32::max(0.0, self.trauma - self.decay * dt);
+	}
+
+	/// Returns the current translation offset.
+	pub fn offset(&self) -> cvmath::Vec3<f32> {
+		let s = self.strength();
+		cvmath::Vec3(
+			self.max_offset.x * s * self.noise(0),
+			self.max_offset.y * s * self.noise(1),
+			self.max_offset.z * s * self.noise(2))
+	}
+
+	/// Returns the current rotation offset in radians as yaw, pitch and roll.
+	pub fn angle(&self) -> cvmath::Vec3<f32> {
+		let s = self.strength();
+		cvmath::Vec3(
+			self.max_angle.x * s * self.noise(3),
+			self.max_angle.y * s * self.noise(4),
+			self.max_angle.z * s * self.noise(5))
+	}
+
+	#[inline]
+	fn strength(&self) -> f32 {
+		self.trauma * self.trauma
+	}
+
+	/// Smooth noise between `-1.0` and `1.0` for the channel.
+	fn noise(&self, channel: u32) -> f32 {
+		let t = self.time * self.frequency;
+		let i = t.floor();
+		let f = t - i;
+		let seed = self.seed.wrapping_mul(6).wrapping_add(channel);
+		let a = hash_noise(seed, i as i32);
+		let b = hash_noise(seed, i as i32 + 1);
+		// Smoothstep between the lattice values
+		let f = f * f * (3.0 - 2.0 * f);
+		a + (b - a) * f
+	}
+}
+
+/// Hashes the lattice point to a value between `-1.0` and `1.0`.
+fn hash_noise(seed: u32, i: i32) -> f32 {
+	let mut h = (i as u32).wrapping_mul(0x27d4eb2d) ^ seed.wrapping_mul(0x165667b1);
+	h ^= h >> 15;
+	h = h.wrapping_mul(0x2c1b3c6d);
+	h ^= h >> 12;
+	h = h.wrapping_mul(0x297a2d39);
+	h ^= h >> 15;
+	(h as f32 / u32::MAX as f32) * 2.0 - 1.0
+}
@@ -0,0 +1,53 @@
+/// Size and scale of the display.
+///
+/// Retained buffers laid out for the display remember its generation and are rebuilt when the generation changes.
+///
+/// # Examples
+///
+/// ```rust
+/// use shade::cvmath::Vec2;
+///
+/// let mut display = shade::DisplayInfo::default();
+/// assert!(display.update(Vec2(800, 600), 1.0));
+/// let generation = display.generation;
+///
+/// // Nothing changed
+/// assert!(!display.update(Vec2(800, 600), 1.0));
+/// assert_eq!(display.generation, generation);
+///
+/// // The window moved to a high DPI monitor
+/// assert!(display.update(Vec2(800, 600), 2.0));
+/// assert_ne!(display.generation, generation);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct DisplayInfo {
+	/// Size of the back buffer in pixels.
+	pub size: cvmath::Vec2<i32>,
+	/// Pixels per logical unit.
+	pub scale: f32,
+	/// Incremented every time the size or scale changes.
+	pub generation: u32,
+}
+
+impl Default for DisplayInfo {
+	fn default() -> Self {
+		DisplayInfo {
+			size: cvmath::Vec2(0, 0),
+			scale: 1.0,
+			generation: 0,
+		}
+	}
+}
+
+impl DisplayInfo {
+	/// Updates the size and scale, returns if they changed.
+	pub fn update(&mut self, size: cvmath::Vec2<i32>, scale: f32) -> bool {
+		if self.size == size && self.scale == scale {
+			return false;
+		}
+		self.size = size;
+		self.scale = scale;
+		self.generation = self.generation.wrapping_add(1);
+		true
+	}
+}
@@ -0,0 +1,176 @@
+use std::f32::consts::PI;
+use cvmath::Vec3;
+
+/// Face of a cubemap.
+///
+/// The faces are in the order of the OpenGL cubemap targets, the texel coordinates follow the OpenGL conventions.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum CubeFace {
+	PosX,
+	NegX,
+	PosY,
+	NegY,
+	PosZ,
+	NegZ,
+}
+
+impl CubeFace {
+	/// All faces in order.
+	pub const ALL: [CubeFace; 6] = [CubeFace::PosX, CubeFace::NegX, CubeFace::PosY, CubeFace::NegY, CubeFace::PosZ, CubeFace::NegZ];
+
+	/// Returns the unnormalized direction through the face coordinates in the range `0.0` to `1.0`.
+	pub fn direction(self, s: f32, t: f32) -> Vec3<f32> {
+		let sc = s * 2.0 - 1.0;
+		let tc = t * 2.0 - 1.0;
+		match self {
+			CubeFace::PosX => Vec3(1.0, -tc, -sc),
+			CubeFace::NegX => Vec3(-1.0, -tc, sc),
+			CubeFace::PosY => Vec3(sc, 1.0, tc),
+			CubeFace::NegY => Vec3(sc, -1.0, -tc),
+			CubeFace::PosZ => Vec3(sc, -tc, 1.0),
+			CubeFace::NegZ => Vec3(-sc, -tc, -1.0),
+		}
+	}
+
+	/// Returns the face and face coordinates hit by the direction.
+	pub fn from_direction(dir: Vec3<f32>) -> (CubeFace, f32, f32) {
+		let (ax, ay, az) = (dir.x.abs(), dir.y.abs(), dir.z.abs());
+		let (face, sc, tc, ma) = if ax >= ay && ax >= az {
+			if dir.x >= 0.0 { (CubeFace::PosX, -dir.z, -dir.y, ax) } else { (CubeFace::NegX, dir.z, -dir.y, ax) }
+		}
+		else if ay >= az {
+			if dir.y >= 0.0 { (CubeFace::PosY, dir.x, dir.z, ay) } else { (CubeFace::NegY, dir.x, -dir.z, ay) }
+		}
+		else if dir.z >= 0.0 {
+			(CubeFace::PosZ, dir.x, -dir.y, az)
+		}
+		else {
+			(CubeFace::NegZ, -dir.x, -dir.y, az)
+		};
+		let ma = f32::max(ma, f32::MIN_POSITIVE);
+		(face, (sc / ma + 1.0) * 0.5, (tc / ma + 1.0) * 0.5)
+	}
+}
+
+/// Returns the direction through the equirectangular coordinates in the range `0.0` to `1.0`.
+///
+/// The horizontal coordinate is the longitude starting at `-X` going through `-Z`, the vertical coordinate goes from `+Y` at the top to `-Y` at the bottom.
+pub fn equirect_direction(u: f32, v: f32) -> Vec3<f32> {
+	let (sin_lon, cos_lon) = ((u - 0.5) * 2.0 * PI).sin_cos();
+	let (sin_lat, cos_lat) = (v * PI).sin_cos();
+	Vec3(sin_lat * cos_lon, cos_lat, sin_lat * sin_lon)
+}
+
+/// Returns the equirectangular coordinates of the direction.
+pub fn equirect_coords(dir: Vec3<f32>) -> (f32, f32) {
+	let len = f32::max((dir.x * dir.x + dir.y * dir.y + dir.z * dir.z).sqrt(), f32::MIN_POSITIVE);
+	let u = dir.z.atan2(dir.x) / (2.0 * PI) + 0.5;
+	let v = (dir.y / len).clamp(-1.0, 1.0).acos() / PI;
+	(u, v)
+}
+
+/// Converts an 8-bit Rgba equirectangular image to the six faces of a cubemap.
+///
+/// The image is sampled bilinearly, wrapping around horizontally so there is no seam at the edge of the image.
+///
+/// # Panics
+///
+/// Panics if the image is smaller than its dimensions.
+///
+/// # Examples
+///
+/// ```rust
+/// let image = [10, 20, 30, 255].repeat(8 * 4);
+/// let faces = shade::equirect_to_cube(&image, 8, 4, 2);
+/// for face in &faces {
+///     assert_eq!(face, &[10, 20, 30, 255].repeat(2 * 2));
+/// }
+/// ```
+pub fn equirect_to_cube(image: &[u8], width: usize, height: usize, size: usize) -> [Vec<u8>; 6] {
+	CubeFace::ALL.map(|face| {
+		let mut pixels = Vec::with_capacity(size * size * 4);
+		for y in 0..size {
+			for x in 0..size {
+				let dir = face.direction((x as f32 + 0.5) / size as f32, (y as f32 + 0.5) / size as f32);
+				let (u, v) = equirect_coords(dir);
+				pixels.extend(to_rgba8(sample_equirect(image, width, height, u, v)));
+			}
+		}
+		pixels
+	})
+}
+
+/// Converts the six 8-bit Rgba faces of a cubemap to an equirectangular image.
+///
+/// The faces are sampled bilinearly, texels across the edge of a face are fetched from the neighbouring face so there are no seams between the faces.
+///
+/// # Panics
+///
+/// Panics if a face is smaller than its dimensions.
+pub fn cube_to_equirect(faces: &[&[u8]; 6], size: usize, width: usize, height: usize) -> Vec<u8> {
+	let mut pixels = Vec::with_capacity(width * height * 4);
+	for y in 0..height {
+		for x in 0..width {
+			let dir = equirect_direction((x as f32 + 0.5) / width as f32, (y as f32 + 0.5) / height as f32);
+			pixels.extend(to_rgba8(sample_cube(faces, size, dir)));
+		}
+	}
+	pixels
+}
+
+fn sample_equirect(image: &[u8], width: usize, height: usize, u: f32, v: f32) -> [f32; 4] {
+	let x = u * width as f32 - 0.5;
+	let y = v * height as f32 - 0.5;
+	let (x0, y0) = (x.floor(), y.floor());
+	let (fx, fy) = (x - x0, y - y0);
+	bilinear(fx, fy, |dx, dy| {
+		// Wrap around horizontally and clamp at the poles
+		let px = (x0 as i32 + dx).rem_euclid(width as i32) as usize;
+		let py = (y0 as i32 + dy).clamp(0, height as i32 - 1) as usize;
+		texel(image, (py * width + px) * 4)
+	})
+}
+
+fn sample_cube(faces: &[&[u8]; 6], size: usize, dir: Vec3<f32>) -> [f32; 4] {
+	let (face, s, t) = CubeFace::from_direction(dir);
+	let x = s * size as f32 - 0.5;
+	let y = t * size as f32 - 0.5;
+	let (x0, y0) = (x.floor(), y.floor());
+	let (fx, fy) = (x - x0, y - y0);
+	bilinear(fx, fy, |dx, dy| cube_texel(faces, size, face, x0 as i32 + dx, y0 as i32 + dy))
+}
+
+fn cube_texel(faces: &[&[u8]; 6], size: usize, face: CubeFace, x: i32, y: i32) -> [f32; 4] {
+	let n = size as i32;
+	let (face, x, y) = if x >= 0 && x < n && y >= 0 && y < n {
+		(face, x, y)
+	}
+	else {
+		// Project the texel outside the face onto the neighbouring face
+		let dir = face.direction((x as f32 + 0.5) / size as f32, (y as f32 + 0.5) / size as f32);
+		let (face, s, t) = CubeFace::from_direction(dir);
+		let x = ((s * size as f32) as i32).clamp(0, n - 1);
+		let y = ((t * size as f32) as i32).clamp(0, n - 1);
+		(face, x, y)
+	};
+	texel(faces[face as usize], (y as usize * size + x as usize) * 4)
+}
+
+fn bilinear<F: FnMut(i32, i32) -> [f32; 4]>(fx: f32, fy: f32, mut fetch: F) -> [f32; 4] {
+	let (a, b, c, d) = (fetch(0, 0), fetch(1, 0), fetch(0, 1), fetch(1, 1));
+	std::array::from_fn(|i| {
+		let top = a[i] + (b[i] - a[i]) * fx;
+		let bottom = c[i] + (d[i] - c[i]) * fx;
+		top + (bottom - top) * fy
+	})
+}
+
+#[inline]
+fn texel(image: &[u8], offset: usize) -> [f32; 4] {
+	[image[offset] as f32, image[offset + 1] as f32, image[offset + 2] as f32, image[offset + 3] as f32]
+}
+
+#[inline]
+fn to_rgba8(color: [f32; 4]) -> [u8; 4] {
+	color.map(|c| (c + 0.5) as u8)
+}
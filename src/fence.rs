@@ -0,0 +1,54 @@
+use std::collections::VecDeque;
+
+use super::*;
+
+define_handle!(Fence);
+
+/// Frame pacer.
+///
+/// Limits how many frames the CPU may queue ahead of the GPU.
+/// Call [`end_frame`](FramePacer::end_frame) after submitting the commands of every frame.
+pub struct FramePacer {
+	fences: VecDeque<Fence>,
+	/// Maximum number of frames in flight.
+	///
+	/// A value of `1` waits for the GPU every frame, trading throughput for the lowest input latency.
+	pub max_frames: usize,
+	/// Timeout in nanoseconds when waiting for a frame to complete.
+	pub timeout_ns: u64,
+}
+
+impl FramePacer {
+	/// Creates a new frame pacer.
+	pub fn new(max_frames: usize) -> FramePacer {
+		FramePacer {
+			fences: VecDeque::new(),
+			max_frames: cmp::max(1, max_frames),
+			timeout_ns: 100_000_000,
+		}
+	}
+
+	/// Inserts a fence for the current frame and waits until the oldest frames complete.
+	///
+	/// Returns false if waiting timed out.
+	pub fn end_frame(&mut self, g: &mut Graphics) -> Result<bool, GfxError> {
+		let fence = g.frame_fence()?;
+		self.fences.push_back(fence);
+		let mut completed = true;
+		while self.fences.len() > self.max_frames {
+			let Some(fence) = self.fences.pop_front() else { break };
+			completed &= g.fence_wait(fence, self.timeout_ns)?;
+			g.fence_delete(fence)?;
+		}
+		Ok(completed)
+	}
+
+	/// Waits for all frames in flight to complete.
+	pub fn flush(&mut self, g: &mut Graphics) -> Result<(), GfxError> {
+		while let Some(fence) = self.fences.pop_front() {
+			g.fence_wait(fence, self.timeout_ns)?;
+			g.fence_delete(fence)?;
+		}
+		Ok(())
+	}
+}
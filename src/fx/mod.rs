@@ -0,0 +1,11 @@
+/*!
+Post processing effects.
+*/
+
+use super::*;
+
+pub mod transitions;
+pub mod targets;
+
+pub use self::transitions::{Transition, TransitionPass};
+pub use self::targets::{TargetView, TargetRegistry, TargetViewer};
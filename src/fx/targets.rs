@@ -0,0 +1,275 @@
+/*!
+Named render targets and a debug viewer.
+
+Register the intermediate textures of a multi-pass pipeline (shadow maps, G-buffer layers, post buffers) by name.
+The viewer draws them as thumbnails or cycles through them full screen, toggle it at runtime from a debug key binding.
+*/
+
+use super::*;
+use super::transitions::TransitionVertex;
+use cvmath::{Rect, Vec2};
+
+/// How the texture of a target is visualized.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum TargetView {
+	/// The RGB channels.
+	#[default]
+	Color,
+	/// The red channel as grayscale.
+	Red,
+	/// The green channel as grayscale.
+	Green,
+	/// The blue channel as grayscale.
+	Blue,
+	/// The alpha channel as grayscale.
+	Alpha,
+	/// Normals in the range `-1.0` to `1.0` remapped to colors.
+	Normal,
+	/// Depth stored in the red channel, linearized between the near and far planes.
+	Depth { near: f32, far: f32 },
+}
+
+/// Named render target.
+#[derive(Clone, Debug)]
+struct Target {
+	name: String,
+	texture: Texture2D,
+	view: TargetView,
+}
+
+/// Registry of named render targets.
+#[derive(Clone, Debug, Default)]
+pub struct TargetRegistry {
+	targets: Vec<Target>,
+}
+
+impl TargetRegistry {
+	/// Creates a new empty registry.
+	pub fn new() -> TargetRegistry {
+		TargetRegistry::default()
+	}
+
+	/// Registers a texture by name, replaces the target with the same name.
+	pub fn register(&mut self, name: &str, texture: Texture2D, view: TargetView) {
+		if let Some(target) = self.targets.iter_mut().find(|target| target.name == name) {
+			target.texture = texture;
+			target.view = view;
+		}
+		else {
+			self.targets.push(Target { name: name.to_string(), texture, view });
+		}
+	}
+
+	/// Removes the target with the name.
+	pub fn unregister(&mut self, name: &str) {
+		self.targets.retain(|target| target.name != name);
+	}
+
+	/// Finds the texture of the target with the name.
+	pub fn find(&self, name: &str) -> Option<Texture2D> {
+		self.targets.iter().find(|target| target.name == name).map(|target| target.texture)
+	}
+
+	/// Returns the number of targets.
+	#[inline]
+	pub fn len(&self) -> usize {
+		self.targets.len()
+	}
+
+	/// Returns true if there are no targets.
+	#[inline]
+	pub fn is_empty(&self) -> bool {
+		self.targets.is_empty()
+	}
+
+	/// Iterates over the name, texture and view of the targets in registration order.
+	pub fn iter(&self) -> impl Iterator<Item = (&str, Texture2D, TargetView)> {
+		self.targets.iter().map(|target| (target.name.as_str(), target.texture, target.view))
+	}
+
+	/// Removes all targets.
+	#[inline]
+	pub fn clear(&mut self) {
+		self.targets.clear();
+	}
+}
+
+/// Target viewer uniform.
+#[derive(Copy, Clone, Debug, Default, dataview::Pod)]
+#[repr(C)]
+pub struct TargetViewUniform {
+	pub texture: Texture2D,
+	pub mode: i32,
+	pub near: f32,
+	pub far: f32,
+}
+
+unsafe impl TUniform for TargetViewUniform {
+	const UNIFORM_LAYOUT: &'static UniformLayout = &UniformLayout {
+		size: std::mem::size_of::<TargetViewUniform>() as u16,
+		alignment: std::mem::align_of::<TargetViewUniform>() as u16,
+		attributes: &[
+			UniformAttribute {
+				name: "u_texture",
+				ty: UniformType::Sampler2D(0),
+				offset: dataview::offset_of!(TargetViewUniform.texture) as u16,
+				len: 1,
+			},
+			UniformAttribute {
+				name: "u_mode",
+				ty: UniformType::I1,
+				offset: dataview::offset_of!(TargetViewUniform.mode) as u16,
+				len: 1,
+			},
+			UniformAttribute {
+				name: "u_near",
+				ty: UniformType::F1,
+				offset: dataview::offset_of!(TargetViewUniform.near) as u16,
+				len: 1,
+			},
+			UniformAttribute {
+				name: "u_far",
+				ty: UniformType::F1,
+				offset: dataview::offset_of!(TargetViewUniform.far) as u16,
+				len: 1,
+			},
+		],
+	};
+}
+
+impl TargetViewUniform {
+	/// Creates the uniform for a target.
+	pub fn new(texture: Texture2D, view: TargetView) -> TargetViewUniform {
+		let (mode, near, far) = match view {
+			TargetView::Color => (0, 0.0, 1.0),
+			TargetView::Red => (1, 0.0, 1.0),
+			TargetView::Green => (2, 0.0, 1.0),
+			TargetView::Blue => (3, 0.0, 1.0),
+			TargetView::Alpha => (4, 0.0, 1.0),
+			TargetView::Normal => (5, 0.0, 1.0),
+			TargetView::Depth { near, far } => (6, near, far),
+		};
+		TargetViewUniform { texture, mode, near, far }
+	}
+}
+
+/// Debug viewer overlay for the registered targets.
+///
+/// Compile the shader from `gl::TRANSITION_VS` and `gl::TARGET_VIEW_FS`.
+pub struct TargetViewer {
+	/// The viewer shader.
+	pub shader: Shader,
+	/// The viewer is drawn.
+	pub enabled: bool,
+	/// Index of the target shown full screen, thumbnails of all targets are shown if `None`.
+	pub current: Option<usize>,
+	/// Spacing between the thumbnails in pixels.
+	pub spacing: i32,
+	vertices: VertexBuffer,
+	uniforms: UniformBuffer,
+}
+
+impl TargetViewer {
+	/// Creates the buffers for the viewer, it starts disabled.
+	pub fn new(g: &mut Graphics, shader: Shader) -> Result<TargetViewer, GfxError> {
+		let vertices = [
+			TransitionVertex { pos: Vec2(-1.0, -1.0), uv: Vec2(0.0, 0.0) },
+			TransitionVertex { pos: Vec2(-1.0, 1.0), uv: Vec2(0.0, 1.0) },
+			TransitionVertex { pos: Vec2(1.0, 1.0), uv: Vec2(1.0, 1.0) },
+			TransitionVertex { pos: Vec2(-1.0, -1.0), uv: Vec2(0.0, 0.0) },
+			TransitionVertex { pos: Vec2(1.0, 1.0), uv: Vec2(1.0, 1.0) },
+			TransitionVertex { pos: Vec2(1.0, -1.0), uv: Vec2(1.0, 0.0) },
+		];
+		let vertices = g.vertex_buffer(None, &vertices, BufferUsage::Static)?;
+		let uniforms = g.uniform_buffer_create::<TargetViewUniform>(None, 1)?;
+		Ok(TargetViewer { shader, enabled: false, current: None, spacing: 4, vertices, uniforms })
+	}
+
+	/// Toggles the viewer on and off.
+	#[inline]
+	pub fn toggle(&mut self) {
+		self.enabled = !self.enabled;
+	}
+
+	/// Cycles forward through the thumbnails and the targets.
+	pub fn next(&mut self, registry: &TargetRegistry) {
+		self.current = match self.current {
+			None if !registry.is_empty() => Some(0),
+			Some(index) if index + 1 < registry.len() => Some(index + 1),
+			_ => None,
+		};
+	}
+
+	/// Cycles backward through the thumbnails and the targets.
+	pub fn prev(&mut self, registry: &TargetRegistry) {
+		self.current = match self.current {
+			None => registry.len().checked_sub(1),
+			Some(index) => index.checked_sub(1),
+		};
+	}
+
+	/// Returns the name of the target shown full screen, for labeling it.
+	pub fn current_name<'a>(&self, registry: &'a TargetRegistry) -> Option<&'a str> {
+		registry.targets.get(self.current?).map(|target| target.name.as_str())
+	}
+
+	/// Draws the viewer over the surface if it is enabled.
+	pub fn draw(&mut self, g: &mut Graphics, registry: &TargetRegistry, surface: Surface, viewport: Rect<i32>) -> Result<(), GfxError> {
+		if !self.enabled || registry.is_empty() {
+			return Ok(());
+		}
+
+		if let Some(target) = self.current.and_then(|index| registry.targets.get(index)) {
+			g.uniform_buffer_set_data(self.uniforms, &[TargetViewUniform::new(target.texture, target.view)])?;
+			return self.draw_quad(g, surface, viewport, 0);
+		}
+
+		// Lay out the thumbnails in a grid
+		let count = registry.len() as i32;
+		let columns = (count as f32).sqrt().ceil() as i32;
+		let rows = (count + columns - 1) / columns;
+		let cell = Vec2(viewport.width() / columns, viewport.height() / rows);
+
+		let uniforms: Vec<TargetViewUniform> = registry.targets.iter().map(|target| TargetViewUniform::new(target.texture, target.view)).collect();
+		g.uniform_buffer_set_data(self.uniforms, &uniforms)?;
+
+		for index in 0..count {
+			let x = viewport.mins.x + (index % columns) * cell.x;
+			let y = viewport.maxs.y - (index / columns + 1) * cell.y;
+			let thumbnail = Rect::c(x + self.spacing, y + self.spacing, x + cell.x - self.spacing, y + cell.y - self.spacing);
+			if thumbnail.width() > 0 && thumbnail.height() > 0 {
+				self.draw_quad(g, surface, thumbnail, index as u32)?;
+			}
+		}
+		Ok(())
+	}
+
+	fn draw_quad(&self, g: &mut Graphics, surface: Surface, viewport: Rect<i32>, uniform_index: u32) -> Result<(), GfxError> {
+		g.draw(&DrawArgs {
+			surface,
+			viewport,
+			scissor: None,
+			blend_mode: BlendMode::Solid,
+			depth_test: None,
+			cull_mode: None,
+			fill_mode: FillMode::Solid,
+			alpha_to_coverage: false,
+			stencil: None,
+			prim_type: PrimType::Triangles,
+			shader: self.shader,
+			vertices: self.vertices,
+			uniforms: self.uniforms,
+			vertex_start: 0,
+			vertex_end: 6,
+			uniform_index,
+			instances: -1,
+		})
+	}
+
+	/// Releases the buffers, the shader is left to the caller.
+	pub fn delete(self, g: &mut Graphics) -> Result<(), GfxError> {
+		g.uniform_buffer_delete(self.uniforms, true)?;
+		g.vertex_buffer_delete(self.vertices, true)?;
+		Ok(())
+	}
+}
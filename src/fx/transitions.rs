@@ -0,0 +1,228 @@
+/*!
+Screen transition effects.
+
+Blends between two frames with a progress parameter going from `0.0` (the old frame) to `1.0` (the new frame).
+Capture the frames by rendering them to offscreen surfaces and passing the textures with [`surface_get_texture`](IGraphics::surface_get_texture).
+Keep rendering the live scene to the new frame's surface to transition into an animated scene.
+*/
+
+use super::*;
+use cvmath::{Rect, Vec2};
+
+/// Transition effect.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum Transition {
+	/// Fades from one frame to the other.
+	#[default]
+	Crossfade,
+	/// Sweeps the new frame across the old frame in a direction.
+	Wipe {
+		direction: Vec2<f32>,
+		/// Width of the blended edge as a fraction of the screen.
+		softness: f32,
+	},
+	/// Opens a number of blinds in a direction.
+	Shutters {
+		direction: Vec2<f32>,
+		count: i32,
+		/// Width of the blended edge as a fraction of a blind.
+		softness: f32,
+	},
+	/// Pixelates the old frame and resolves the new frame.
+	Pixelate {
+		/// Size of the pixels in the middle of the transition.
+		max_size: f32,
+	},
+	/// Dissolves the old frame with noise.
+	Dissolve {
+		/// Size of the noise cells in pixels.
+		cell_size: f32,
+		/// Width of the blended edge in noise values.
+		softness: f32,
+	},
+}
+
+/// Transition vertex.
+#[derive(Copy, Clone, Debug, Default, dataview::Pod)]
+#[repr(C)]
+pub struct TransitionVertex {
+	pub pos: Vec2<f32>,
+	pub uv: Vec2<f32>,
+}
+
+unsafe impl TVertex for TransitionVertex {
+	const VERTEX_LAYOUT: &'static VertexLayout = &VertexLayout {
+		size: std::mem::size_of::<TransitionVertex>() as u16,
+		alignment: std::mem::align_of::<TransitionVertex>() as u16,
+		instance_divisor: 0,
+		attributes: &[
+			VertexAttribute {
+				format: VertexAttributeFormat::F32,
+				len: 2,
+				offset: dataview::offset_of!(TransitionVertex.pos) as u16,
+			},
+			VertexAttribute {
+				format: VertexAttributeFormat::F32,
+				len: 2,
+				offset: dataview::offset_of!(TransitionVertex.uv) as u16,
+			},
+		],
+	};
+}
+
+/// Transition uniform.
+#[derive(Copy, Clone, Debug, Default, dataview::Pod)]
+#[repr(C)]
+pub struct TransitionUniform {
+	pub from: Texture2D,
+	pub to: Texture2D,
+	pub mode: i32,
+	pub progress: f32,
+	pub direction: Vec2<f32>,
+	pub count: f32,
+	pub softness: f32,
+	pub resolution: Vec2<f32>,
+}
+
+unsafe impl TUniform for TransitionUniform {
+	const UNIFORM_LAYOUT: &'static UniformLayout = &UniformLayout {
+		size: std::mem::size_of::<TransitionUniform>() as u16,
+		alignment: std::mem::align_of::<TransitionUniform>() as u16,
+		attributes: &[
+			UniformAttribute {
+				name: "u_from",
+				ty: UniformType::Sampler2D(0),
+				offset: dataview::offset_of!(TransitionUniform.from) as u16,
+				len: 1,
+			},
+			UniformAttribute {
+				name: "u_to",
+				ty: UniformType::Sampler2D(1),
+				offset: dataview::offset_of!(TransitionUniform.to) as u16,
+				len: 1,
+			},
+			UniformAttribute {
+				name: "u_mode",
+				ty: UniformType::I1,
+				offset: dataview::offset_of!(TransitionUniform.mode) as u16,
+				len: 1,
+			},
+			UniformAttribute {
+				name: "u_progress",
+				ty: UniformType::F1,
+				offset: dataview::offset_of!(TransitionUniform.progress) as u16,
+				len: 1,
+			},
+			UniformAttribute {
+				name: "u_direction",
+				ty: UniformType::F2,
+				offset: dataview::offset_of!(TransitionUniform.direction) as u16,
+				len: 1,
+			},
+			UniformAttribute {
+				name: "u_count",
+				ty: UniformType::F1,
+				offset: dataview::offset_of!(TransitionUniform.count) as u16,
+				len: 1,
+			},
+			UniformAttribute {
+				name: "u_softness",
+				ty: UniformType::F1,
+				offset: dataview::offset_of!(TransitionUniform.softness) as u16,
+				len: 1,
+			},
+			UniformAttribute {
+				name: "u_resolution",
+				ty: UniformType::F2,
+				offset: dataview::offset_of!(TransitionUniform.resolution) as u16,
+				len: 1,
+			},
+		],
+	};
+}
+
+impl TransitionUniform {
+	/// Creates the uniform for a transition.
+	pub fn new(transition: &Transition, from: Texture2D, to: Texture2D, progress: f32, resolution: Vec2<f32>) -> TransitionUniform {
+		let (mode, direction, count, softness) = match *transition {
+			Transition::Crossfade => (0, Vec2(1.0, 0.0), 1.0, 0.0),
+			Transition::Wipe { direction, softness } => (1, direction, 1.0, softness),
+			Transition::Shutters { direction, count, softness } => (2, direction, count as f32, softness),
+			Transition::Pixelate { max_size } => (3, Vec2(1.0, 0.0), max_size, 0.0),
+			Transition::Dissolve { cell_size, softness } => (4, Vec2(1.0, 0.0), cell_size, softness),
+		};
+		TransitionUniform {
+			from,
+			to,
+			mode,
+			progress: progress.clamp(0.0, 1.0),
+			direction,
+			count,
+			// Avoid undefined smoothstep with equal edges
+			softness: f32::max(softness, 0.0001),
+			resolution,
+		}
+	}
+}
+
+/// Draws transitions as a full screen post pass.
+///
+/// Compile the shader from `gl::TRANSITION_VS` and `gl::TRANSITION_FS`.
+pub struct TransitionPass {
+	/// The transition shader.
+	pub shader: Shader,
+	/// The transition effect.
+	pub transition: Transition,
+	vertices: VertexBuffer,
+	uniforms: UniformBuffer,
+}
+
+impl TransitionPass {
+	/// Creates the buffers for the transition pass.
+	pub fn new(g: &mut Graphics, shader: Shader) -> Result<TransitionPass, GfxError> {
+		let vertices = [
+			TransitionVertex { pos: Vec2(-1.0, -1.0), uv: Vec2(0.0, 0.0) },
+			TransitionVertex { pos: Vec2(-1.0, 1.0), uv: Vec2(0.0, 1.0) },
+			TransitionVertex { pos: Vec2(1.0, 1.0), uv: Vec2(1.0, 1.0) },
+			TransitionVertex { pos: Vec2(-1.0, -1.0), uv: Vec2(0.0, 0.0) },
+			TransitionVertex { pos: Vec2(1.0, 1.0), uv: Vec2(1.0, 1.0) },
+			TransitionVertex { pos: Vec2(1.0, -1.0), uv: Vec2(1.0, 0.0) },
+		];
+		let vertices = g.vertex_buffer(None, &vertices, BufferUsage::Static)?;
+		let uniforms = g.uniform_buffer_create::<TransitionUniform>(None, 1)?;
+		Ok(TransitionPass { shader, transition: Transition::Crossfade, vertices, uniforms })
+	}
+
+	/// Draws the transition between two frames to the surface.
+	pub fn draw(&mut self, g: &mut Graphics, surface: Surface, viewport: Rect<i32>, from: Texture2D, to: Texture2D, progress: f32) -> Result<(), GfxError> {
+		let resolution = Vec2(viewport.width() as f32, viewport.height() as f32);
+		let uniform = TransitionUniform::new(&self.transition, from, to, progress, resolution);
+		g.uniform_buffer_set_data(self.uniforms, &[uniform])?;
+		g.draw(&DrawArgs {
+			surface,
+			viewport,
+			scissor: None,
+			blend_mode: BlendMode::Solid,
+			depth_test: None,
+			cull_mode: None,
+			fill_mode: FillMode::Solid,
+			alpha_to_coverage: false,
+			stencil: None,
+			prim_type: PrimType::Triangles,
+			shader: self.shader,
+			vertices: self.vertices,
+			uniforms: self.uniforms,
+			vertex_start: 0,
+			vertex_end: 6,
+			uniform_index: 0,
+			instances: -1,
+		})
+	}
+
+	/// Releases the buffers, the shader is left to the caller.
+	pub fn delete(self, g: &mut Graphics) -> Result<(), GfxError> {
+		g.uniform_buffer_delete(self.uniforms, true)?;
+		g.vertex_buffer_delete(self.vertices, true)?;
+		Ok(())
+	}
+}
@@ -2,13 +2,29 @@
 OpenGL graphics backend.
 */
 
-use std::{mem, ops};
+use std::{cmp, mem, ops};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 
 /// Re-exported OpenGL bindings.
 pub use gl as capi;
 
 pub const MTSDF_FS: &str = include_str!("shaders/mtsdf.fs.glsl");
 pub const MTSDF_VS: &str = include_str!("shaders/mtsdf.vs.glsl");
+/// Fragment shader for color bitmap glyphs, use with [`MTSDF_VS`].
+pub const COLOR_GLYPH_FS: &str = include_str!("shaders/color.fs.glsl");
+/// Shaders for [`fx::TransitionPass`](crate::fx::TransitionPass).
+pub const TRANSITION_VS: &str = include_str!("shaders/transition.vs.glsl");
+pub const TRANSITION_FS: &str = include_str!("shaders/transition.fs.glsl");
+/// Fragment shader for [`fx::TargetViewer`](crate::fx::TargetViewer), use with [`TRANSITION_VS`].
+pub const TARGET_VIEW_FS: &str = include_str!("shaders/target.fs.glsl");
+/// Equirectangular sampling helpers to paste into shaders, see [`equirect_coords`](crate::equirect_coords).
+pub const EQUIRECT_GLSL: &str = include_str!("shaders/equirect.glsl");
+/// Shaders for [`d2::SpriteBatch`](crate::d2::SpriteBatch).
+pub const SPRITE_VS: &str = include_str!("shaders/sprite.vs.glsl");
+pub const SPRITE_FS: &str = include_str!("shaders/sprite.fs.glsl");
+/// Shaders for [`d2::PaletteBuffer`](crate::d2::PaletteBuffer).
+pub const PALETTE_VS: &str = include_str!("shaders/palette.vs.glsl");
+pub const PALETTE_FS: &str = include_str!("shaders/palette.fs.glsl");
 
 use crate::resources::{Resource, ResourceMap};
 use crate::handle::Handle;
@@ -43,6 +59,32 @@ impl Resource for GlUniformBuffer {
 	type Handle = crate::UniformBuffer;
 }
 
+impl GlUniformBuffer {
+	fn element(&self, uniform_index: u32) -> &[u8] {
+		let size = self.layout.size as usize;
+		let start = cmp::min(self.data.len(), size * uniform_index as usize);
+		&self.data[start..cmp::min(self.data.len(), start + size)]
+	}
+}
+
+/// Uniform buffer element bound to a named uniform block, the binding point is its index.
+struct GlUniformBlock {
+	name: String,
+	uniforms: crate::UniformBuffer,
+	uniform_index: u32,
+	buffer: gl::types::GLuint,
+}
+
+impl GlUniformBlock {
+	fn upload(&self, ub: &GlUniformBuffer) {
+		let mut data = Vec::new();
+		ub.layout.pack_std140(ub.element(self.uniform_index), &mut data);
+		check(|| unsafe { gl::BindBuffer(gl::UNIFORM_BUFFER, self.buffer) });
+		check(|| unsafe { gl::BufferData(gl::UNIFORM_BUFFER, data.len() as isize, data.as_ptr() as *const _, gl::DYNAMIC_DRAW) });
+		check(|| unsafe { gl::BindBuffer(gl::UNIFORM_BUFFER, 0) });
+	}
+}
+
 struct GlShaderActiveUniform {
 	location: gl::types::GLint,
 	namelen: u8,
@@ -56,11 +98,26 @@ impl GlShaderActiveUniform {
 	}
 }
 
+struct GlShaderActiveBlock {
+	index: gl::types::GLuint,
+	namelen: u8,
+	namebuf: [u8; 64],
+}
+impl GlShaderActiveBlock {
+	fn name(&self) -> &str {
+		std::str::from_utf8(&self.namebuf[..self.namelen as usize]).unwrap_or("err")
+	}
+}
+
 struct GlShader {
 	program: gl::types::GLuint,
 	compile_log: String,
+	defaults: crate::ParamBlock,
 
 	active_uniforms: Vec<GlShaderActiveUniform>,
+	active_blocks: Vec<GlShaderActiveBlock>,
+	/// Uniforms which were already reported as not found.
+	missing_uniforms: std::cell::RefCell<Vec<&'static str>>,
 }
 impl GlShader {
 	fn uniform_location(&self, name: &str) -> Option<gl::types::GLint> {
@@ -69,7 +126,19 @@ impl GlShader {
 				return Some(au.location);
 			}
 		}
-		return None;
+		None
+	}
+	fn block_index(&self, name: &str) -> Option<gl::types::GLuint> {
+		self.active_blocks.iter().find(|ab| ab.name() == name).map(|ab| ab.index)
+	}
+	/// Returns true the first time the uniform is reported missing.
+	fn report_missing(&self, name: &'static str) -> bool {
+		let mut missing = self.missing_uniforms.borrow_mut();
+		if missing.contains(&name) {
+			return false;
+		}
+		missing.push(name);
+		true
 	}
 }
 
@@ -86,6 +155,41 @@ impl Resource for GlTexture2D {
 	type Handle = crate::Texture2D;
 }
 
+struct GlTextureCube {
+	texture: gl::types::GLuint,
+	info: crate::TextureCubeInfo,
+}
+
+impl Resource for GlTextureCube {
+	type Handle = crate::TextureCube;
+}
+
+struct GlTexture3D {
+	texture: gl::types::GLuint,
+	info: crate::Texture3DInfo,
+}
+
+impl Resource for GlTexture3D {
+	type Handle = crate::Texture3D;
+}
+
+struct GlTexture2DArray {
+	texture: gl::types::GLuint,
+	info: crate::Texture2DArrayInfo,
+}
+
+impl Resource for GlTexture2DArray {
+	type Handle = crate::Texture2DArray;
+}
+
+/// Textures bound by the sampler uniforms.
+struct GlSamplers<'a> {
+	textures: &'a ResourceMap<GlTexture2D>,
+	cubes: &'a ResourceMap<GlTextureCube>,
+	volumes: &'a ResourceMap<GlTexture3D>,
+	arrays: &'a ResourceMap<GlTexture2DArray>,
+}
+
 #[allow(dead_code)]
 struct GlSurface {
 	texture: crate::Texture2D,
@@ -93,6 +197,7 @@ struct GlSurface {
 	depth_buf: gl::types::GLuint,
 	tex_buf: gl::types::GLuint,
 	format: crate::SurfaceFormat,
+	depth_format: crate::DepthFormat,
 	width: i32,
 	height: i32,
 }
@@ -101,6 +206,14 @@ impl Resource for GlSurface {
 	type Handle = crate::Surface;
 }
 
+struct GlFence {
+	sync: gl::types::GLsync,
+}
+
+impl Resource for GlFence {
+	type Handle = crate::Fence;
+}
+
 struct GlBlend {
 	sfactor: gl::types::GLenum,
 	dfactor: gl::types::GLenum,
@@ -118,6 +231,11 @@ fn gl_blend(blend_mode: crate::BlendMode) {
 			dfactor: gl::ONE_MINUS_SRC_ALPHA,
 			equation: gl::FUNC_ADD,
 		},
+		crate::BlendMode::PremultipliedAlpha => GlBlend {
+			sfactor: gl::ONE,
+			dfactor: gl::ONE_MINUS_SRC_ALPHA,
+			equation: gl::FUNC_ADD,
+		},
 		crate::BlendMode::Additive => GlBlend {
 			sfactor: gl::ONE,
 			dfactor: gl::ONE,
@@ -149,6 +267,31 @@ fn gl_blend(blend_mode: crate::BlendMode) {
 	check(|| unsafe { gl::BlendEquation(p.equation) });
 }
 
+fn gl_prim_type(prim_type: crate::PrimType) -> gl::types::GLenum {
+	match prim_type {
+		crate::PrimType::Triangles => gl::TRIANGLES,
+		crate::PrimType::Lines => gl::LINES,
+		crate::PrimType::Points => gl::POINTS,
+		crate::PrimType::LineStrip => gl::LINE_STRIP,
+		crate::PrimType::LineLoop => gl::LINE_LOOP,
+		crate::PrimType::TriangleStrip => gl::TRIANGLE_STRIP,
+		crate::PrimType::TriangleFan => gl::TRIANGLE_FAN,
+	}
+}
+
+fn gl_prim_state(prim_type: crate::PrimType) {
+	if prim_type == crate::PrimType::Points {
+		check(|| unsafe { gl::Enable(gl::PROGRAM_POINT_SIZE) });
+	}
+	if prim_type.is_list() {
+		check(|| unsafe { gl::Disable(gl::PRIMITIVE_RESTART) });
+	}
+	else {
+		check(|| unsafe { gl::Enable(gl::PRIMITIVE_RESTART) });
+		check(|| unsafe { gl::PrimitiveRestartIndex(u32::MAX) });
+	}
+}
+
 fn gl_scissor(scissor: &Option<cvmath::Rect<i32>>) {
 	if let Some(scissor) = scissor {
 		check(|| unsafe { gl::Enable(gl::SCISSOR_TEST) });
@@ -161,10 +304,10 @@ fn gl_scissor(scissor: &Option<cvmath::Rect<i32>>) {
 
 fn gl_texture_id(textures: &ResourceMap<GlTexture2D>, id: crate::Texture2D) -> gl::types::GLuint {
 	if let Some(texture) = textures.get(id) {
-		return texture.texture;
+		texture.texture
 	}
 	else {
-		return 0;
+		0
 	}
 }
 
@@ -202,6 +345,52 @@ fn gl_cull_face(cull_mode: Option<crate::CullMode>) {
 	}
 }
 
+fn gl_fill_mode(fill_mode: crate::FillMode) {
+	let mode = match fill_mode {
+		crate::FillMode::Solid => gl::FILL,
+		crate::FillMode::Wireframe => gl::LINE,
+		crate::FillMode::Points => gl::POINT,
+	};
+	check(|| unsafe { gl::PolygonMode(gl::FRONT_AND_BACK, mode) });
+}
+
+fn gl_stencil(stencil: Option<crate::StencilMode>) {
+	match stencil {
+		None => {
+			check(|| unsafe { gl::Disable(gl::STENCIL_TEST) });
+			check(|| unsafe { gl::ColorMask(gl::TRUE, gl::TRUE, gl::TRUE, gl::TRUE) });
+		}
+		Some(crate::StencilMode::Winding) => {
+			check(|| unsafe { gl::Enable(gl::STENCIL_TEST) });
+			check(|| unsafe { gl::StencilFunc(gl::ALWAYS, 0, 0xff) });
+			check(|| unsafe { gl::StencilOpSeparate(gl::FRONT, gl::KEEP, gl::KEEP, gl::INCR_WRAP) });
+			check(|| unsafe { gl::StencilOpSeparate(gl::BACK, gl::KEEP, gl::KEEP, gl::DECR_WRAP) });
+			check(|| unsafe { gl::ColorMask(gl::FALSE, gl::FALSE, gl::FALSE, gl::FALSE) });
+		}
+		Some(crate::StencilMode::Invert) => {
+			check(|| unsafe { gl::Enable(gl::STENCIL_TEST) });
+			check(|| unsafe { gl::StencilFunc(gl::ALWAYS, 0, 0xff) });
+			check(|| unsafe { gl::StencilOp(gl::KEEP, gl::KEEP, gl::INVERT) });
+			check(|| unsafe { gl::ColorMask(gl::FALSE, gl::FALSE, gl::FALSE, gl::FALSE) });
+		}
+		Some(crate::StencilMode::Cover) => {
+			check(|| unsafe { gl::Enable(gl::STENCIL_TEST) });
+			check(|| unsafe { gl::StencilFunc(gl::NOTEQUAL, 0, 0xff) });
+			check(|| unsafe { gl::StencilOp(gl::ZERO, gl::ZERO, gl::ZERO) });
+			check(|| unsafe { gl::ColorMask(gl::TRUE, gl::TRUE, gl::TRUE, gl::TRUE) });
+		}
+	}
+}
+
+fn gl_alpha_to_coverage(enable: bool) {
+	if enable {
+		check(|| unsafe { gl::Enable(gl::SAMPLE_ALPHA_TO_COVERAGE) });
+	}
+	else {
+		check(|| unsafe { gl::Disable(gl::SAMPLE_ALPHA_TO_COVERAGE) });
+	}
+}
+
 #[inline]
 fn gl_mat_order(order: crate::UniformMatOrder) -> gl::types::GLboolean {
 	match order {
@@ -210,15 +399,83 @@ fn gl_mat_order(order: crate::UniformMatOrder) -> gl::types::GLboolean {
 	}
 }
 
-fn gl_uniforms(ub: &GlUniformBuffer, shader: &GlShader, uniform_index: u32, textures: &ResourceMap<GlTexture2D>) {
+/// Logs a warning about a missing feature only once.
+fn warn_once(flag: &std::sync::atomic::AtomicBool, category: crate::LogCategory, message: &str) {
+	if !flag.swap(true, std::sync::atomic::Ordering::Relaxed) && crate::log::log_enabled(crate::LogLevel::Warn) {
+		crate::log::log(crate::LogLevel::Warn, category, format_args!("{}", message));
+	}
+}
+
+/// Uploads double precision uniforms as single precision when doubles are not supported.
+fn gl_uniform_f64_fallback(location: gl::types::GLint, len: u16, n: usize, data_ptr: *const u8) {
+	static WARNED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+	warn_once(&WARNED, crate::LogCategory::Shader, "double precision uniforms are not supported, falling back to single precision");
+
+	let data = unsafe { std::slice::from_raw_parts(data_ptr as *const f64, len as usize * n) };
+	let data: Vec<f32> = data.iter().map(|&v| v as f32).collect();
+	match n {
+		1 => check(|| unsafe { gl::Uniform1fv(location, len as i32, data.as_ptr()) }),
+		2 => check(|| unsafe { gl::Uniform2fv(location, len as i32, data.as_ptr()) }),
+		3 => check(|| unsafe { gl::Uniform3fv(location, len as i32, data.as_ptr()) }),
+		_ => check(|| unsafe { gl::Uniform4fv(location, len as i32, data.as_ptr()) }),
+	}
+}
+
+fn gl_uniform_value(location: gl::types::GLint, value: &crate::UniformValue) {
+	match value {
+		crate::UniformValue::F1(v) => check(|| unsafe { gl::Uniform1f(location, *v) }),
+		crate::UniformValue::F2(v) => check(|| unsafe { gl::Uniform2fv(location, 1, v.as_ptr()) }),
+		crate::UniformValue::F3(v) => check(|| unsafe { gl::Uniform3fv(location, 1, v.as_ptr()) }),
+		crate::UniformValue::F4(v) => check(|| unsafe { gl::Uniform4fv(location, 1, v.as_ptr()) }),
+		crate::UniformValue::I1(v) => check(|| unsafe { gl::Uniform1i(location, *v) }),
+		crate::UniformValue::I2(v) => check(|| unsafe { gl::Uniform2iv(location, 1, v.as_ptr()) }),
+		crate::UniformValue::I3(v) => check(|| unsafe { gl::Uniform3iv(location, 1, v.as_ptr()) }),
+		crate::UniformValue::I4(v) => check(|| unsafe { gl::Uniform4iv(location, 1, v.as_ptr()) }),
+		crate::UniformValue::U1(v) => check(|| unsafe { gl::Uniform1ui(location, *v) }),
+		crate::UniformValue::U2(v) => check(|| unsafe { gl::Uniform2uiv(location, 1, v.as_ptr()) }),
+		crate::UniformValue::U3(v) => check(|| unsafe { gl::Uniform3uiv(location, 1, v.as_ptr()) }),
+		crate::UniformValue::U4(v) => check(|| unsafe { gl::Uniform4uiv(location, 1, v.as_ptr()) }),
+	}
+}
+
+fn gl_uniforms(ub: &GlUniformBuffer, shader: &GlShader, uniform_index: u32, blocks: &[GlUniformBlock], uniforms: &ResourceMap<GlUniformBuffer>, samplers: &GlSamplers, caps: &crate::Caps) {
 	check(|| unsafe { gl::UseProgram(shader.program) });
 
+	// Apply the defaults for uniforms not set by the draw
+	for (name, value) in shader.defaults.iter() {
+		if ub.layout.attributes.iter().any(|uattr| uattr.name == name) {
+			continue;
+		}
+		if let Some(location) = shader.uniform_location(name) {
+			gl_uniform_value(location, &value);
+		}
+	}
+
+	// Bind the shared uniform blocks, fall back to loose uniforms if the shader does not declare the block
+	for (binding, block) in blocks.iter().enumerate() {
+		if let Some(index) = shader.block_index(&block.name) {
+			check(|| unsafe { gl::UniformBlockBinding(shader.program, index, binding as u32) });
+			check(|| unsafe { gl::BindBufferBase(gl::UNIFORM_BUFFER, binding as u32, block.buffer) });
+		}
+		else if let Some(block_ub) = uniforms.get(block.uniforms) {
+			gl_uniform_attributes(block_ub, shader, block.uniform_index, samplers, caps);
+		}
+	}
+
+	gl_uniform_attributes(ub, shader, uniform_index, samplers, caps);
+}
+
+fn gl_uniform_attributes(ub: &GlUniformBuffer, shader: &GlShader, uniform_index: u32, samplers: &GlSamplers, caps: &crate::Caps) {
 	let data_ptr = unsafe { ub.data.as_ptr().add(ub.layout.size as usize * uniform_index as usize) };
 	for uattr in ub.layout.attributes {
 		let data_ptr = unsafe { data_ptr.offset(uattr.offset as isize) };
 		if let Some(location) = shader.uniform_location(uattr.name) {
 			// println!("Uniform: {} (index: {})", uattr.name, i);
 			match uattr.ty {
+				crate::UniformType::D1 if !caps.f64 => gl_uniform_f64_fallback(location, uattr.len, 1, data_ptr),
+				crate::UniformType::D2 if !caps.f64 => gl_uniform_f64_fallback(location, uattr.len, 2, data_ptr),
+				crate::UniformType::D3 if !caps.f64 => gl_uniform_f64_fallback(location, uattr.len, 3, data_ptr),
+				crate::UniformType::D4 if !caps.f64 => gl_uniform_f64_fallback(location, uattr.len, 4, data_ptr),
 				crate::UniformType::D1 => check(|| unsafe { gl::Uniform1dv(location, uattr.len as i32, data_ptr as *const _) }),
 				crate::UniformType::D2 => check(|| unsafe { gl::Uniform2dv(location, uattr.len as i32, data_ptr as *const _) }),
 				crate::UniformType::D3 => check(|| unsafe { gl::Uniform3dv(location, uattr.len as i32, data_ptr as *const _) }),
@@ -250,16 +507,73 @@ fn gl_uniforms(ub: &GlUniformBuffer, shader: &GlShader, uniform_index: u32, text
 				crate::UniformType::Mat4x4 { order } => check(|| unsafe { gl::UniformMatrix4fv(location, uattr.len as i32, gl_mat_order(order), data_ptr as *const _) }),
 				crate::UniformType::Sampler2D(index) => {
 					let id = unsafe { *(data_ptr as *const crate::Texture2D) };
-					let texture = gl_texture_id(textures, id);
+					let texture = gl_texture_id(samplers.textures, id);
 					check(|| unsafe { gl::ActiveTexture(gl::TEXTURE0 + index as u32) });
 					check(|| unsafe { gl::BindTexture(gl::TEXTURE_2D, texture) });
+					check(|| unsafe { gl::Uniform1i(location, index as i32) });
+				}
+				crate::UniformType::SamplerCube(index) => {
+					let id = unsafe { *(data_ptr as *const crate::TextureCube) };
+					let texture = samplers.cubes.get(id).map_or(0, |cube| cube.texture);
+					check(|| unsafe { gl::ActiveTexture(gl::TEXTURE0 + index as u32) });
+					check(|| unsafe { gl::BindTexture(gl::TEXTURE_CUBE_MAP, texture) });
+					check(|| unsafe { gl::Uniform1i(location, index as i32) });
+				}
+				crate::UniformType::Sampler3D(index) => {
+					let id = unsafe { *(data_ptr as *const crate::Texture3D) };
+					let texture = samplers.volumes.get(id).map_or(0, |volume| volume.texture);
+					check(|| unsafe { gl::ActiveTexture(gl::TEXTURE0 + index as u32) });
+					check(|| unsafe { gl::BindTexture(gl::TEXTURE_3D, texture) });
+					check(|| unsafe { gl::Uniform1i(location, index as i32) });
+				}
+				crate::UniformType::Sampler2DArray(index) => {
+					let id = unsafe { *(data_ptr as *const crate::Texture2DArray) };
+					let texture = samplers.arrays.get(id).map_or(0, |array| array.texture);
+					check(|| unsafe { gl::ActiveTexture(gl::TEXTURE0 + index as u32) });
+					check(|| unsafe { gl::BindTexture(gl::TEXTURE_2D_ARRAY, texture) });
+					check(|| unsafe { gl::Uniform1i(location, index as i32) });
 				}
 			}
 		}
+		else if shader.report_missing(uattr.name) {
+			gfx_log!(Debug, Shader, "Uniform not found: {}", uattr.name);
+		}
+	}
+}
+
+fn gl_capture_uniforms(ub: &GlUniformBuffer, uniform_index: u32, shader: &GlShader) -> Vec<crate::UniformCapture> {
+	let uniform_start = ub.layout.size as usize * uniform_index as usize;
+	ub.layout.attributes.iter().map(|uattr| {
+		let start = cmp::min(ub.data.len(), uniform_start + uattr.offset as usize);
+		let end = if let Some(next) = ub.layout.attributes.iter().map(|a| a.offset).filter(|&offset| offset > uattr.offset).min() {
+			uniform_start + next as usize
+		}
 		else {
-			// panic!("Uniform not found: {}", uattr.name);
+			uniform_start + ub.layout.size as usize
+		};
+		crate::UniformCapture {
+			name: uattr.name,
+			ty: uattr.ty,
+			len: uattr.len,
+			active: shader.uniform_location(uattr.name).is_some(),
+			data: ub.data[start..cmp::min(ub.data.len(), end)].to_vec(),
 		}
+	}).collect()
+}
+
+fn gl_capture_vertices(vb: &GlVertexBuffer, vertex_start: u32, max_vertices: usize) -> Vec<u8> {
+	let stride = vb._layout.size as usize;
+	let mut buffer_size = 0;
+	check(|| unsafe { gl::BindBuffer(gl::ARRAY_BUFFER, vb.buffer) });
+	check(|| unsafe { gl::GetBufferParameteriv(gl::ARRAY_BUFFER, gl::BUFFER_SIZE, &mut buffer_size) });
+	let offset = cmp::min(buffer_size as usize, vertex_start as usize * stride);
+	let len = cmp::min(buffer_size as usize - offset, max_vertices * stride);
+	let mut vertex_data = vec![0u8; len];
+	if len > 0 {
+		check(|| unsafe { gl::GetBufferSubData(gl::ARRAY_BUFFER, offset as isize, len as isize, vertex_data.as_mut_ptr() as *mut _) });
 	}
+	check(|| unsafe { gl::BindBuffer(gl::ARRAY_BUFFER, 0) });
+	vertex_data
 }
 
 fn gl_texture_wrap(wrap: crate::TextureWrap) -> gl::types::GLenum {
@@ -274,40 +588,338 @@ fn gl_texture_filter(filter: crate::TextureFilter) -> gl::types::GLenum {
 	match filter {
 		crate::TextureFilter::Nearest => gl::NEAREST,
 		crate::TextureFilter::Linear => gl::LINEAR,
+		crate::TextureFilter::NearestMipmapNearest => gl::NEAREST_MIPMAP_NEAREST,
+		crate::TextureFilter::LinearMipmapNearest => gl::LINEAR_MIPMAP_NEAREST,
+		crate::TextureFilter::NearestMipmapLinear => gl::NEAREST_MIPMAP_LINEAR,
+		crate::TextureFilter::LinearMipmapLinear => gl::LINEAR_MIPMAP_LINEAR,
+	}
+}
+
+fn gl_texture_filter_mipmap(filter: crate::TextureFilter) -> gl::types::GLenum {
+	match filter {
+		crate::TextureFilter::Nearest => gl::NEAREST_MIPMAP_NEAREST,
+		crate::TextureFilter::Linear => gl::LINEAR_MIPMAP_LINEAR,
+		filter => gl_texture_filter(filter),
+	}
+}
+
+fn gl_texture_props(props: &crate::TextureProps, max_anisotropy: i32) {
+	let border_color = props.border_color.map(|c| c as f32 / 255.0);
+	check(|| unsafe { gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl_texture_wrap(props.wrap_u) as gl::types::GLint) });
+	check(|| unsafe { gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl_texture_wrap(props.wrap_v) as gl::types::GLint) });
+	check(|| unsafe { gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl_texture_filter(props.filter_mag.base()) as gl::types::GLint) });
+	check(|| unsafe { gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl_texture_filter(props.filter_min) as gl::types::GLint) });
+	check(|| unsafe { gl::TexParameterfv(gl::TEXTURE_2D, gl::TEXTURE_BORDER_COLOR, border_color.as_ptr()) });
+	if max_anisotropy > 0 {
+		let anisotropy = props.anisotropy.clamp(1.0, max_anisotropy as f32);
+		check(|| unsafe { gl::TexParameterf(gl::TEXTURE_2D, GL_TEXTURE_MAX_ANISOTROPY, anisotropy) });
+	}
+}
+
+/// Limits the mip levels of the bound 2D texture and generates them from the base level.
+///
+/// The mip levels of compressed textures are uploaded instead.
+fn gl_texture_levels(info: &crate::Texture2DInfo) {
+	let levels = info.mip_levels.count(info.width, info.height);
+	check(|| unsafe { gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAX_LEVEL, levels as gl::types::GLint - 1) });
+	if levels > 1 && !info.format.is_compressed() {
+		check(|| unsafe { gl::GenerateMipmap(gl::TEXTURE_2D) });
+	}
+}
+
+/// Uploads consecutive layers of a 2D texture array and regenerates its mipmaps.
+fn gl_texture2darray_upload(array: &GlTexture2DArray, layer: i32, layers: i32, data: &[u8]) -> Result<(), crate::GfxError> {
+	let info = &array.info;
+	let Some((_, format, ty)) = gl_texture_format(info.format) else { return Err(crate::GfxError::Unsupported("texture2darray upload with a compressed format")) };
+	if data.len() < info.format.data_size(info.width, info.height) * i32::max(0, layers) as usize {
+		return Err(crate::GfxError::IndexOutOfBounds);
+	}
+	check(|| unsafe { gl::BindTexture(gl::TEXTURE_2D_ARRAY, array.texture) });
+	check(|| unsafe { gl::TexSubImage3D(gl::TEXTURE_2D_ARRAY, 0, 0, 0, layer, info.width, info.height, layers, format, ty, data.as_ptr() as *const _) });
+	if info.mipmaps {
+		check(|| unsafe { gl::GenerateMipmap(gl::TEXTURE_2D_ARRAY) });
+	}
+	check(|| unsafe { gl::BindTexture(gl::TEXTURE_2D_ARRAY, 0) });
+	Ok(())
+}
+
+/// Returns the internal format, format and type of a surface color attachment.
+fn gl_surface_format(format: crate::SurfaceFormat) -> (gl::types::GLenum, gl::types::GLenum, gl::types::GLenum) {
+	match format {
+		crate::SurfaceFormat::R8G8B8A8 => (gl::RGBA8, gl::RGBA, gl::UNSIGNED_BYTE),
+		crate::SurfaceFormat::R8G8B8 => (gl::RGB8, gl::RGB, gl::UNSIGNED_BYTE),
+		crate::SurfaceFormat::R16G16B16A16F => (gl::RGBA16F, gl::RGBA, gl::HALF_FLOAT),
+		crate::SurfaceFormat::R32G32B32A32F => (gl::RGBA32F, gl::RGBA, gl::FLOAT),
+		crate::SurfaceFormat::R11G11B10F => (gl::R11F_G11F_B10F, gl::RGB, gl::UNSIGNED_INT_10F_11F_11F_REV),
+	}
+}
+
+/// Returns the internal format, format and type of an uncompressed texture format.
+fn gl_texture_format(format: crate::TextureFormat) -> Option<(gl::types::GLenum, gl::types::GLenum, gl::types::GLenum)> {
+	match format {
+		crate::TextureFormat::R8G8B8A8 => Some((gl::RGBA8, gl::RGBA, gl::UNSIGNED_BYTE)),
+		crate::TextureFormat::R16G16B16A16F => Some((gl::RGBA16F, gl::RGBA, gl::HALF_FLOAT)),
+		crate::TextureFormat::R32G32B32A32F => Some((gl::RGBA32F, gl::RGBA, gl::FLOAT)),
+		crate::TextureFormat::R11G11B10F => Some((gl::R11F_G11F_B10F, gl::RGB, gl::UNSIGNED_INT_10F_11F_11F_REV)),
+		_ => None,
+	}
+}
+
+/// Returns the renderbuffer format and framebuffer attachment of a surface depth attachment.
+fn gl_depth_format(format: crate::DepthFormat) -> (gl::types::GLenum, gl::types::GLenum) {
+	match format {
+		crate::DepthFormat::D24 => (gl::DEPTH_COMPONENT24, gl::DEPTH_ATTACHMENT),
+		crate::DepthFormat::D24S8 => (gl::DEPTH24_STENCIL8, gl::DEPTH_STENCIL_ATTACHMENT),
+		crate::DepthFormat::D32F => (gl::DEPTH_COMPONENT32F, gl::DEPTH_ATTACHMENT),
+	}
+}
+
+const GL_COMPRESSED_RGBA_S3TC_DXT1: gl::types::GLenum = 0x83F1;
+const GL_COMPRESSED_RGBA_S3TC_DXT3: gl::types::GLenum = 0x83F2;
+const GL_COMPRESSED_RGBA_S3TC_DXT5: gl::types::GLenum = 0x83F3;
+const GL_COMPRESSED_RGBA_ASTC_4X4: gl::types::GLenum = 0x93B0;
+
+/// Returns the internal format of a compressed texture format.
+fn gl_compressed_format(format: crate::TextureFormat) -> Option<gl::types::GLenum> {
+	match format {
+		crate::TextureFormat::R8G8B8A8 | crate::TextureFormat::R16G16B16A16F | crate::TextureFormat::R32G32B32A32F | crate::TextureFormat::R11G11B10F => None,
+		crate::TextureFormat::BC1 => Some(GL_COMPRESSED_RGBA_S3TC_DXT1),
+		crate::TextureFormat::BC2 => Some(GL_COMPRESSED_RGBA_S3TC_DXT3),
+		crate::TextureFormat::BC3 => Some(GL_COMPRESSED_RGBA_S3TC_DXT5),
+		crate::TextureFormat::Etc2Rgb8 => Some(gl::COMPRESSED_RGB8_ETC2),
+		crate::TextureFormat::Etc2Rgba8 => Some(gl::COMPRESSED_RGBA8_ETC2_EAC),
+		crate::TextureFormat::Astc4x4 => Some(GL_COMPRESSED_RGBA_ASTC_4X4),
+	}
+}
+
+const GL_TEXTURE_MAX_ANISOTROPY: gl::types::GLenum = 0x84FE;
+const GL_MAX_TEXTURE_MAX_ANISOTROPY: gl::types::GLenum = 0x84FF;
+
+fn gl_extensions() -> Vec<String> {
+	let mut count = 0;
+	check(|| unsafe { gl::GetIntegerv(gl::NUM_EXTENSIONS, &mut count) });
+	let mut extensions = Vec::with_capacity(count as usize);
+	for i in 0..count {
+		let name = check(|| unsafe { gl::GetStringi(gl::EXTENSIONS, i as u32) });
+		if !name.is_null() {
+			let name = unsafe { std::ffi::CStr::from_ptr(name as *const _) };
+			extensions.push(name.to_string_lossy().into_owned());
+		}
+	}
+	extensions
+}
+
+fn gl_capabilities() -> crate::Caps {
+	let mut major = 0;
+	let mut minor = 0;
+	check(|| unsafe { gl::GetIntegerv(gl::MAJOR_VERSION, &mut major) });
+	check(|| unsafe { gl::GetIntegerv(gl::MINOR_VERSION, &mut minor) });
+	let version = (major, minor);
+	let extensions = gl_extensions();
+	let has_extension = |name: &str| extensions.iter().any(|ext| ext == name);
+
+	let mut caps = crate::Caps::default();
+	check(|| unsafe { gl::GetIntegerv(gl::MAX_TEXTURE_SIZE, &mut caps.max_texture_size) });
+	check(|| unsafe { gl::GetIntegerv(gl::MAX_VERTEX_ATTRIBS, &mut caps.max_vertex_attributes) });
+	check(|| unsafe { gl::GetIntegerv(gl::MAX_TEXTURE_IMAGE_UNITS, &mut caps.max_texture_units) });
+	check(|| unsafe { gl::GetIntegerv(gl::MAX_COLOR_ATTACHMENTS, &mut caps.max_color_attachments) });
+	if version >= (4, 6) || has_extension("GL_EXT_texture_filter_anisotropic") || has_extension("GL_ARB_texture_filter_anisotropic") {
+		let mut max_anisotropy = 0.0;
+		check(|| unsafe { gl::GetFloatv(GL_MAX_TEXTURE_MAX_ANISOTROPY, &mut max_anisotropy) });
+		caps.max_anisotropy = max_anisotropy as i32;
+	}
+	caps.instancing = version >= (3, 1);
+	caps.float_textures = version >= (3, 0) || has_extension("GL_ARB_texture_float");
+	caps.f64 = version >= (4, 0) || has_extension("GL_ARB_gpu_shader_fp64");
+	caps.compute = version >= (4, 3) || has_extension("GL_ARB_compute_shader");
+	caps.s3tc = has_extension("GL_EXT_texture_compression_s3tc");
+	caps.etc2 = version >= (4, 3) || has_extension("GL_ARB_ES3_compatibility");
+	caps.astc = has_extension("GL_KHR_texture_compression_astc_ldr");
+	caps
+}
+
+/// OpenGL backend configuration.
+#[derive(Copy, Clone, Debug)]
+pub struct GlConfig {
+	/// Check for OpenGL errors after every call.
+	///
+	/// Errors are logged and can be retrieved with [`last_error`](crate::IGraphics::last_error).
+	/// Always enabled in debug builds where errors cause a panic.
+	pub check_errors: bool,
+	/// Register a `KHR_debug` message callback when available.
+	///
+	/// Messages from the driver are forwarded to the [logger](crate::set_logger).
+	pub debug_output: bool,
+	/// Panic on high severity debug messages in debug builds.
+	pub break_on_error: bool,
+}
+
+impl Default for GlConfig {
+	fn default() -> Self {
+		GlConfig {
+			check_errors: cfg!(debug_assertions),
+			debug_output: cfg!(debug_assertions),
+			break_on_error: false,
+		}
+	}
+}
+
+fn gl_debug_source(source: gl::types::GLenum) -> &'static str {
+	match source {
+		gl::DEBUG_SOURCE_API => "api",
+		gl::DEBUG_SOURCE_WINDOW_SYSTEM => "window system",
+		gl::DEBUG_SOURCE_SHADER_COMPILER => "shader compiler",
+		gl::DEBUG_SOURCE_THIRD_PARTY => "third party",
+		gl::DEBUG_SOURCE_APPLICATION => "application",
+		_ => "other",
+	}
+}
+
+fn gl_debug_type(ty: gl::types::GLenum) -> &'static str {
+	match ty {
+		gl::DEBUG_TYPE_ERROR => "error",
+		gl::DEBUG_TYPE_DEPRECATED_BEHAVIOR => "deprecated behavior",
+		gl::DEBUG_TYPE_UNDEFINED_BEHAVIOR => "undefined behavior",
+		gl::DEBUG_TYPE_PORTABILITY => "portability",
+		gl::DEBUG_TYPE_PERFORMANCE => "performance",
+		gl::DEBUG_TYPE_MARKER => "marker",
+		_ => "other",
 	}
 }
 
+extern "system" fn gl_debug_callback(
+	source: gl::types::GLenum,
+	ty: gl::types::GLenum,
+	id: gl::types::GLuint,
+	severity: gl::types::GLenum,
+	length: gl::types::GLsizei,
+	message: *const gl::types::GLchar,
+	_user_param: *mut std::ffi::c_void,
+) {
+	let message = unsafe { std::slice::from_raw_parts(message as *const u8, length as usize) };
+	let message = String::from_utf8_lossy(message);
+	let (source, ty) = (gl_debug_source(source), gl_debug_type(ty));
+	match severity {
+		gl::DEBUG_SEVERITY_HIGH => {
+			gfx_log!(Error, General, "OpenGL {} {} ({}): {}", source, ty, id, message);
+			// Panicking here would abort, defer to the check after the offending call
+			if cfg!(debug_assertions) && BREAK_ON_ERROR.load(Ordering::Relaxed) {
+				DEBUG_BREAK.store(true, Ordering::Relaxed);
+			}
+		}
+		gl::DEBUG_SEVERITY_MEDIUM => gfx_log!(Warn, General, "OpenGL {} {} ({}): {}", source, ty, id, message),
+		gl::DEBUG_SEVERITY_LOW => gfx_log!(Info, General, "OpenGL {} {} ({}): {}", source, ty, id, message),
+		_ => gfx_log!(Debug, General, "OpenGL {} {} ({}): {}", source, ty, id, message),
+	}
+}
+
+fn gl_debug_output(config: &GlConfig) {
+	if !config.debug_output || !gl::DebugMessageCallback::is_loaded() {
+		return;
+	}
+	BREAK_ON_ERROR.store(config.break_on_error, Ordering::Relaxed);
+	check(|| unsafe { gl::Enable(gl::DEBUG_OUTPUT) });
+	check(|| unsafe { gl::Enable(gl::DEBUG_OUTPUT_SYNCHRONOUS) });
+	check(|| unsafe { gl::DebugMessageCallback(Some(gl_debug_callback), std::ptr::null()) });
+}
+
 pub struct GlGraphics {
 	vertices: ResourceMap<GlVertexBuffer>,
 	indices: ResourceMap<GlIndexBuffer>,
 	uniforms: ResourceMap<GlUniformBuffer>,
 	shaders: ResourceMap<GlShader>,
 	textures: ResourceMap<GlTexture2D>,
+	cubes: ResourceMap<GlTextureCube>,
+	volumes: ResourceMap<GlTexture3D>,
+	arrays: ResourceMap<GlTexture2DArray>,
 	surfaces: ResourceMap<GlSurface>,
+	fences: ResourceMap<GlFence>,
+	caps: Option<crate::Caps>,
 	drawing: bool,
+	display: crate::DisplayInfo,
+	draw_count: u32,
+	capture: Option<(u32, usize)>,
+	captured: Option<crate::DrawCapture>,
+	blocks: Vec<GlUniformBlock>,
+}
+
+impl Default for GlGraphics {
+	#[inline]
+	fn default() -> Self {
+		GlGraphics::new()
+	}
 }
 
 impl GlGraphics {
 	pub fn new() -> Self {
+		GlGraphics::with_config(&GlConfig::default())
+	}
+
+	pub fn with_config(config: &GlConfig) -> Self {
+		CHECK_ERRORS.store(config.check_errors, Ordering::Relaxed);
+		gl_debug_output(config);
 		GlGraphics {
 			vertices: ResourceMap::new(),
 			indices: ResourceMap::new(),
 			uniforms: ResourceMap::new(),
 			shaders: ResourceMap::new(),
 			textures: ResourceMap::new(),
+			cubes: ResourceMap::new(),
+			volumes: ResourceMap::new(),
+			arrays: ResourceMap::new(),
 			surfaces: ResourceMap::new(),
+			fences: ResourceMap::new(),
+			caps: None,
 			drawing: false,
+			display: crate::DisplayInfo::default(),
+			draw_count: 0,
+			capture: None,
+			captured: None,
+			blocks: Vec::new(),
+		}
+	}
+
+	fn samplers(&self) -> GlSamplers<'_> {
+		GlSamplers { textures: &self.textures, cubes: &self.cubes, volumes: &self.volumes, arrays: &self.arrays }
+	}
+
+	fn surface_frame_buf(&self, id: crate::Surface) -> Result<gl::types::GLuint, crate::GfxError> {
+		if id == crate::Surface::BACK_BUFFER {
+			return Ok(0);
 		}
+		let Some(surface) = self.surfaces.get(id) else { return Err(crate::GfxError::InvalidSurfaceHandle) };
+		Ok(surface.frame_buf)
 	}
 }
 
 impl crate::IGraphics for GlGraphics {
+	fn capabilities(&mut self) -> crate::Caps {
+		*self.caps.get_or_insert_with(gl_capabilities)
+	}
+
+	fn last_error(&mut self) -> Option<crate::GfxError> {
+		match LAST_ERROR.swap(gl::NO_ERROR, Ordering::Relaxed) {
+			gl::NO_ERROR => None,
+			error => Some(crate::GfxError::BackendError(error)),
+		}
+	}
+
+	fn display_update(&mut self, size: cvmath::Vec2<i32>, scale: f32) {
+		self.display.update(size, scale);
+	}
+
+	fn display_info(&self) -> crate::DisplayInfo {
+		self.display
+	}
+
 	fn begin(&mut self) -> Result<(), crate::GfxError> {
 		if self.drawing {
 			return Err(crate::GfxError::InvalidDrawCallTime);
 		}
 
+		crate::log::frame_reset();
 		self.drawing = true;
+		self.draw_count = 0;
 		Ok(())
 	}
 
@@ -324,10 +936,27 @@ impl crate::IGraphics for GlGraphics {
 			check(|| unsafe { gl::Disable(gl::SCISSOR_TEST) });
 		}
 
+		// Integer attachments and individual attachments are cleared one at a time
+		let drawbuffer = args.attachment.unwrap_or(0) as gl::types::GLint;
+		if let Some(color) = args.color_int {
+			let value = [color.x, color.y, color.z, color.w];
+			check(|| unsafe { gl::ClearBufferiv(gl::COLOR, drawbuffer, value.as_ptr()) });
+		}
+		if let Some(color) = args.color_uint {
+			let value = [color.x, color.y, color.z, color.w];
+			check(|| unsafe { gl::ClearBufferuiv(gl::COLOR, drawbuffer, value.as_ptr()) });
+		}
+
 		let mut mask = 0;
 		if let Some(color) = args.color {
-			check(|| unsafe { gl::ClearColor(color.x, color.y, color.z, color.w) });
-			mask |= gl::COLOR_BUFFER_BIT;
+			if args.attachment.is_some() {
+				let value = [color.x, color.y, color.z, color.w];
+				check(|| unsafe { gl::ClearBufferfv(gl::COLOR, drawbuffer, value.as_ptr()) });
+			}
+			else {
+				check(|| unsafe { gl::ClearColor(color.x, color.y, color.z, color.w) });
+				mask |= gl::COLOR_BUFFER_BIT;
+			}
 		}
 		if let Some(depth) = args.depth {
 			check(|| unsafe { gl::ClearDepth(depth as f64) });
@@ -347,6 +976,11 @@ impl crate::IGraphics for GlGraphics {
 			return Err(crate::GfxError::InvalidDrawCallTime);
 		}
 
+		let caps = self.capabilities();
+		if args.instances >= 0 && !caps.instancing {
+			return Err(crate::GfxError::Unsupported("instancing"));
+		}
+
 		let this = &*self;
 		let Some(vb) = this.vertices.get(args.vertices) else { return Err(crate::GfxError::InvalidVertexBufferHandle) };
 		let Some(ub) = self.uniforms.get(args.uniforms) else { return Err(crate::GfxError::InvalidUniformBufferHandle) };
@@ -359,20 +993,35 @@ impl crate::IGraphics for GlGraphics {
 			return Ok(());
 		}
 
+		let draw_index = self.draw_count;
+		let captured = match self.capture {
+			Some((index, max_vertices)) if index == draw_index => Some(crate::DrawCapture {
+				draw_index,
+				shader: args.shader,
+				prim_type: args.prim_type,
+				uniforms: gl_capture_uniforms(ub, args.uniform_index, shader),
+				vertex_layout: vb._layout,
+				vertex_start: args.vertex_start,
+				vertex_data: gl_capture_vertices(vb, args.vertex_start, max_vertices),
+			}),
+			_ => None,
+		};
+
 		gl_blend(args.blend_mode);
 		gl_depth_test(args.depth_test);
 		gl_cull_face(args.cull_mode);
+		gl_fill_mode(args.fill_mode);
+		gl_alpha_to_coverage(args.alpha_to_coverage);
+		gl_stencil(args.stencil);
 		gl_scissor(&args.scissor);
 		check(|| unsafe { gl::Viewport(args.viewport.mins.x, args.viewport.mins.y, args.viewport.width(), args.viewport.height()) });
 
 		check(|| unsafe { gl::BindVertexArray(vb.vao) });
 
-		gl_uniforms(ub, shader, args.uniform_index, &self.textures);
+		gl_uniforms(ub, shader, args.uniform_index, &self.blocks, &self.uniforms, &self.samplers(), &caps);
 
-		let mode = match args.prim_type {
-			crate::PrimType::Lines => gl::LINES,
-			crate::PrimType::Triangles => gl::TRIANGLES,
-		};
+		gl_prim_state(args.prim_type);
+		let mode = gl_prim_type(args.prim_type);
 		if args.instances >= 0 {
 			check(|| unsafe { gl::DrawArraysInstanced(mode, args.vertex_start as i32, (args.vertex_end - args.vertex_start) as i32, args.instances) });
 		}
@@ -380,6 +1029,10 @@ impl crate::IGraphics for GlGraphics {
 			check(|| unsafe { gl::DrawArrays(mode, args.vertex_start as i32, (args.vertex_end - args.vertex_start) as i32) });
 		}
 
+		self.draw_count += 1;
+		if captured.is_some() {
+			self.captured = captured;
+		}
 		Ok(())
 	}
 
@@ -388,6 +1041,11 @@ impl crate::IGraphics for GlGraphics {
 			return Err(crate::GfxError::InvalidDrawCallTime);
 		}
 
+		let caps = self.capabilities();
+		if args.instances >= 0 && !caps.instancing {
+			return Err(crate::GfxError::Unsupported("instancing"));
+		}
+
 		let this = &*self;
 		let Some(vb) = this.vertices.get(args.vertices) else { return Err(crate::GfxError::InvalidVertexBufferHandle) };
 		let Some(ib) = this.indices.get(args.indices) else { return Err(crate::GfxError::InvalidIndexBufferHandle) };
@@ -401,34 +1059,59 @@ impl crate::IGraphics for GlGraphics {
 			return Ok(());
 		}
 
+		let draw_index = self.draw_count;
+		let captured = match self.capture {
+			Some((index, max_vertices)) if index == draw_index => Some(crate::DrawCapture {
+				draw_index,
+				shader: args.shader,
+				prim_type: args.prim_type,
+				uniforms: gl_capture_uniforms(ub, args.uniform_index, shader),
+				vertex_layout: vb._layout,
+				vertex_start: args.vertex_start,
+				vertex_data: gl_capture_vertices(vb, args.vertex_start, max_vertices),
+			}),
+			_ => None,
+		};
+
 		gl_blend(args.blend_mode);
 		gl_depth_test(args.depth_test);
 		gl_cull_face(args.cull_mode);
+		gl_fill_mode(args.fill_mode);
+		gl_alpha_to_coverage(args.alpha_to_coverage);
+		gl_stencil(args.stencil);
 		gl_scissor(&args.scissor);
 		check(|| unsafe { gl::Viewport(args.viewport.mins.x, args.viewport.mins.y, args.viewport.width(), args.viewport.height()) });
 
 		check(|| unsafe { gl::BindVertexArray(vb.vao) });
 		check(|| unsafe { gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, ib.buffer) });
 
-		gl_uniforms(ub, shader, args.uniform_index, &self.textures);
+		gl_uniforms(ub, shader, args.uniform_index, &self.blocks, &self.uniforms, &self.samplers(), &caps);
 
-		let mode = match args.prim_type {
-			crate::PrimType::Lines => gl::LINES,
-			crate::PrimType::Triangles => gl::TRIANGLES,
-		};
+		gl_prim_state(args.prim_type);
+		let mode = gl_prim_type(args.prim_type);
 		let count = args.index_end - args.index_start;
 		let offset = match ib.ty {
 			gl::UNSIGNED_INT => args.index_start * 4,
 			gl::UNSIGNED_SHORT => args.index_start * 2,
 			_ => args.index_start,
 		};
-		if args.instances >= 0 {
+		if args.instances >= 0 && args.base_vertex != 0 {
+			check(|| unsafe { gl::DrawElementsInstancedBaseVertex(mode, count as i32, ib.ty, offset as *const _, args.instances, args.base_vertex) });
+		}
+		else if args.instances >= 0 {
 			check(|| unsafe { gl::DrawElementsInstanced(mode, count as i32, ib.ty, offset as *const _, args.instances) });
 		}
+		else if args.base_vertex != 0 {
+			check(|| unsafe { gl::DrawElementsBaseVertex(mode, count as i32, ib.ty, offset as *const _, args.base_vertex) });
+		}
 		else {
 			check(|| unsafe { gl::DrawElements(mode, count as i32, ib.ty, offset as *const _) });
 		}
 
+		self.draw_count += 1;
+		if captured.is_some() {
+			self.captured = captured;
+		}
 		Ok(())
 	}
 
@@ -437,7 +1120,16 @@ impl crate::IGraphics for GlGraphics {
 		Ok(())
 	}
 
+	fn debug_capture(&mut self, draw_index: Option<u32>, max_vertices: usize) {
+		self.capture = draw_index.map(|index| (index, max_vertices));
+	}
+
+	fn debug_capture_take(&mut self) -> Option<crate::DrawCapture> {
+		self.captured.take()
+	}
+
 	fn vertex_buffer_create(&mut self, name: Option<&str>, _layout: &'static crate::VertexLayout, _count: usize) -> Result<crate::VertexBuffer, crate::GfxError> {
+		let caps = self.capabilities();
 		let mut buffer = 0;
 		let mut vao = 0;
 		check(|| unsafe { gl::GenBuffers(1, &mut buffer) });
@@ -461,20 +1153,32 @@ impl crate::IGraphics for GlGraphics {
 				crate::VertexAttributeFormat::I16Norm => (gl::SHORT, true),
 				crate::VertexAttributeFormat::U16Norm => (gl::UNSIGNED_SHORT, true),
 			};
-			check(|| unsafe { gl::VertexAttribPointer(i as u32, attr.len as i32, type_, normalized as u8, _layout.size as i32, attr.offset as usize as *const _) });
+			if type_ == gl::DOUBLE && caps.f64 {
+				check(|| unsafe { gl::VertexAttribLPointer(i as u32, attr.len as i32, type_, _layout.size as i32, attr.offset as usize as *const _) });
+			}
+			else {
+				if type_ == gl::DOUBLE {
+					static WARNED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+					warn_once(&WARNED, crate::LogCategory::Buffer, "double precision vertex attributes are not supported, falling back to single precision");
+				}
+				check(|| unsafe { gl::VertexAttribPointer(i as u32, attr.len as i32, type_, normalized as u8, _layout.size as i32, attr.offset as usize as *const _) });
+			}
 			check(|| unsafe { gl::EnableVertexAttribArray(i as u32) });
+			if _layout.instance_divisor != 0 {
+				check(|| unsafe { gl::VertexAttribDivisor(i as u32, _layout.instance_divisor) });
+			}
 		}
 
 		check(|| unsafe { gl::BindBuffer(gl::ARRAY_BUFFER, 0) });
 		check(|| unsafe { gl::BindVertexArray(0) });
 
 		let id = self.vertices.insert(name, GlVertexBuffer { buffer, vao, _layout, _count });
-		return Ok(id);
+		Ok(id)
 	}
 
 	fn vertex_buffer_find(&mut self, name: &str) -> Result<crate::VertexBuffer, crate::GfxError> {
 		let Some(id) = self.vertices.find_id(name) else { return Err(crate::GfxError::NameNotFound) };
-		return Ok(id);
+		Ok(id)
 	}
 
 	fn vertex_buffer_set_data(&mut self, id: crate::VertexBuffer, data: &[u8], usage: crate::BufferUsage) -> Result<(), crate::GfxError> {
@@ -503,12 +1207,12 @@ impl crate::IGraphics for GlGraphics {
 		check(|| unsafe { gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, buffer) });
 		check(|| unsafe { gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, 0) });
 		let id = self.indices.insert(name, GlIndexBuffer { buffer, ty: gl::UNSIGNED_INT, _count: count });
-		return Ok(id);
+		Ok(id)
 	}
 
 	fn index_buffer_find(&mut self, name: &str) -> Result<crate::IndexBuffer, crate::GfxError> {
 		let Some(id) = self.indices.find_id(name) else { return Err(crate::GfxError::NameNotFound) };
-		return Ok(id);
+		Ok(id)
 	}
 
 	fn index_buffer_set_data(&mut self, id: crate::IndexBuffer, data: &[u32], usage: crate::BufferUsage) -> Result<(), crate::GfxError> {
@@ -534,32 +1238,67 @@ impl crate::IGraphics for GlGraphics {
 	fn uniform_buffer_create(&mut self, name: Option<&str>, layout: &'static crate::UniformLayout, count: usize) -> Result<crate::UniformBuffer, crate::GfxError> {
 		let data = Vec::with_capacity(layout.size as usize * count);
 		let id = self.uniforms.insert(name, GlUniformBuffer { data, layout });
-		return Ok(id);
+		Ok(id)
 	}
 	fn uniform_buffer_find(&mut self, name: &str) -> Result<crate::UniformBuffer, crate::GfxError> {
 		let Some(id) = self.uniforms.find_id(name) else { return Err(crate::GfxError::NameNotFound) };
-		return Ok(id);
+		Ok(id)
 	}
 	fn uniform_buffer_set_data(&mut self, id: crate::UniformBuffer, data: &[u8]) -> Result<(), crate::GfxError> {
 		let Some(ub) = self.uniforms.get_mut(id) else { return Err(crate::GfxError::InvalidUniformBufferHandle) };
 		ub.data.clear();
 		ub.data.extend_from_slice(data);
+		for block in self.blocks.iter().filter(|block| block.uniforms == id) {
+			block.upload(ub);
+		}
 		Ok(())
 	}
 	fn uniform_buffer_delete(&mut self, id: crate::UniformBuffer, free_handle: bool) -> Result<(), crate::GfxError> {
 		let Some(_) = self.uniforms.remove(id, free_handle) else { return Err(crate::GfxError::InvalidUniformBufferHandle) };
+		self.blocks.retain(|block| {
+			if block.uniforms == id {
+				check(|| unsafe { gl::DeleteBuffers(1, &block.buffer) });
+			}
+			block.uniforms != id
+		});
+		Ok(())
+	}
+	fn uniform_block_bind(&mut self, block: &str, id: crate::UniformBuffer, uniform_index: u32) -> Result<(), crate::GfxError> {
+		let Some(ub) = self.uniforms.get(id) else { return Err(crate::GfxError::InvalidUniformBufferHandle) };
+		if ub.data.len() < ub.layout.size as usize * (uniform_index as usize + 1) {
+			return Err(crate::GfxError::IndexOutOfBounds);
+		}
+		let index = match self.blocks.iter().position(|b| b.name == block) {
+			Some(index) => index,
+			None => {
+				let mut buffer = 0;
+				check(|| unsafe { gl::GenBuffers(1, &mut buffer) });
+				self.blocks.push(GlUniformBlock { name: block.to_string(), uniforms: id, uniform_index, buffer });
+				self.blocks.len() - 1
+			}
+		};
+		let block = &mut self.blocks[index];
+		block.uniforms = id;
+		block.uniform_index = uniform_index;
+		block.upload(ub);
+		Ok(())
+	}
+	fn uniform_block_unbind(&mut self, block: &str) -> Result<(), crate::GfxError> {
+		let Some(index) = self.blocks.iter().position(|b| b.name == block) else { return Err(crate::GfxError::NameNotFound) };
+		let block = self.blocks.remove(index);
+		check(|| unsafe { gl::DeleteBuffers(1, &block.buffer) });
 		Ok(())
 	}
 
 	fn shader_create(&mut self, name: Option<&str>) -> Result<crate::Shader, crate::GfxError> {
 		let program = check(|| unsafe { gl::CreateProgram() });
-		let id = self.shaders.insert(name, GlShader { program, compile_log: String::new(), active_uniforms: Vec::new() });
-		return Ok(id);
+		let id = self.shaders.insert(name, GlShader { program, compile_log: String::new(), defaults: crate::ParamBlock::new(), active_uniforms: Vec::new(), active_blocks: Vec::new(), missing_uniforms: Default::default() });
+		Ok(id)
 	}
 
 	fn shader_find(&mut self, name: &str) -> Result<crate::Shader, crate::GfxError> {
 		let Some(id) = self.shaders.find_id(name) else { return Err(crate::GfxError::NameNotFound) };
-		return Ok(id);
+		Ok(id)
 	}
 
 	fn shader_compile(&mut self, id: crate::Shader, vertex_source: &str, fragment_source: &str) -> Result<(), crate::GfxError> {
@@ -568,6 +1307,10 @@ impl crate::IGraphics for GlGraphics {
 		let mut status = 0;
 
 		shader.active_uniforms.clear();
+		shader.active_blocks.clear();
+		shader.missing_uniforms.get_mut().clear();
+		shader.defaults.merge(&crate::ParamBlock::parse(vertex_source));
+		shader.defaults.merge(&crate::ParamBlock::parse(fragment_source));
 
 		let vertex_shader = check(|| unsafe { gl::CreateShader(gl::VERTEX_SHADER) });
 		check(|| unsafe { gl::ShaderSource(vertex_shader, 1, &(vertex_source.as_ptr() as *const _), &(vertex_source.len() as gl::types::GLint)) });
@@ -621,25 +1364,51 @@ impl crate::IGraphics for GlGraphics {
 					let mut ty = 0;
 					let mut name = [0; 64];
 					check(|| unsafe { gl::GetActiveUniform(shader.program, i as u32, 64, &mut name_len, &mut size, &mut ty, name.as_mut_ptr() as *mut _) });
+					// Members of uniform blocks have no location
+					let location = check(|| unsafe { gl::GetUniformLocation(shader.program, name.as_ptr() as *const _) });
+					if location < 0 {
+						continue;
+					}
 					shader.active_uniforms.push(GlShaderActiveUniform {
-						location: i,
+						location,
 						namelen: name_len as u8,
 						namebuf: name,
 						_size: size,
 						_ty: ty,
 					});
 				}
+				let mut count = 0;
+				check(|| unsafe { gl::GetProgramiv(shader.program, gl::ACTIVE_UNIFORM_BLOCKS, &mut count) });
+				for i in 0..count {
+					let mut name_len = 0;
+					let mut name = [0; 64];
+					check(|| unsafe { gl::GetActiveUniformBlockName(shader.program, i as u32, 64, &mut name_len, name.as_mut_ptr() as *mut _) });
+					shader.active_blocks.push(GlShaderActiveBlock {
+						index: i as u32,
+						namelen: name_len as u8,
+						namebuf: name,
+					});
+				}
 			}
 		}
 
 		check(|| unsafe { gl::DeleteShader(vertex_shader) });
 		check(|| unsafe { gl::DeleteShader(fragment_shader) });
-		return if success { Ok(()) } else { Err(crate::GfxError::ShaderCompileError) };
+		if !success {
+			gfx_log!(Error, Shader, "Shader compile error:\n{}", shader.compile_log);
+		}
+		if success { Ok(()) } else { Err(crate::GfxError::ShaderCompileError) }
 	}
 
 	fn shader_compile_log(&mut self, id: crate::Shader) -> Result<String, crate::GfxError> {
 		let Some(shader) = self.shaders.get(id) else { return Err(crate::GfxError::InvalidShaderHandle) };
-		return Ok(shader.compile_log.clone());
+		Ok(shader.compile_log.clone())
+	}
+
+	fn shader_set_defaults(&mut self, id: crate::Shader, params: &crate::ParamBlock) -> Result<(), crate::GfxError> {
+		let Some(shader) = self.shaders.get_mut(id) else { return Err(crate::GfxError::InvalidShaderHandle) };
+		shader.defaults.merge(params);
+		Ok(())
 	}
 
 	fn shader_delete(&mut self, id: crate::Shader, free_handle: bool) -> Result<(), crate::GfxError> {
@@ -654,29 +1423,96 @@ impl crate::IGraphics for GlGraphics {
 		// check(|| unsafe { gl::BindTexture(gl::TEXTURE_2D, texture) });
 		// check(|| unsafe { gl::BindTexture(gl::TEXTURE_2D, 0) });
 		let id = self.textures.insert(name, GlTexture2D { texture, info: *info });
-		return Ok(id);
+		Ok(id)
 	}
 
 	fn texture2d_find(&mut self, name: &str) -> Result<crate::Texture2D, crate::GfxError> {
 		let Some(id) = self.textures.find_id(name) else { return Err(crate::GfxError::NameNotFound) };
-		return Ok(id);
+		Ok(id)
 	}
 
 	fn texture2d_set_data(&mut self, id: crate::Texture2D, data: &[u8]) -> Result<(), crate::GfxError> {
+		let max_anisotropy = self.capabilities().max_anisotropy;
 		let Some(texture) = self.textures.get(id) else { return Err(crate::GfxError::InvalidTexture2DHandle) };
+		let info = &texture.info;
+		let Some((internal, format, ty)) = gl_texture_format(info.format) else { return Err(crate::GfxError::Unsupported("texture2d_set_data with a compressed format")) };
+		if data.len() < info.format.data_size(info.width, info.height) {
+			return Err(crate::GfxError::IndexOutOfBounds);
+		}
 		check(|| unsafe { gl::BindTexture(gl::TEXTURE_2D, texture.texture) });
-		check(|| unsafe { gl::TexImage2D(gl::TEXTURE_2D, 0, gl::RGBA as i32, texture.info.width, texture.info.height, 0, gl::RGBA, gl::UNSIGNED_BYTE, data.as_ptr() as *const _) });
-		check(|| unsafe { gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl_texture_wrap(texture.info.wrap_u) as gl::types::GLint) });
-		check(|| unsafe { gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl_texture_wrap(texture.info.wrap_v) as gl::types::GLint) });
-		check(|| unsafe { gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl_texture_filter(texture.info.filter_mag) as gl::types::GLint) });
-		check(|| unsafe { gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl_texture_filter(texture.info.filter_min) as gl::types::GLint) });
+		check(|| unsafe { gl::TexImage2D(gl::TEXTURE_2D, 0, internal as i32, info.width, info.height, 0, format, ty, data.as_ptr() as *const _) });
+		gl_texture_props(&texture.info.props(), max_anisotropy);
+		gl_texture_levels(&texture.info);
+		check(|| unsafe { gl::BindTexture(gl::TEXTURE_2D, 0) });
+		Ok(())
+	}
+
+	fn texture2d_set_compressed_data(&mut self, id: crate::Texture2D, level: u32, data: &[u8]) -> Result<(), crate::GfxError> {
+		let max_anisotropy = self.capabilities().max_anisotropy;
+		let Some(texture) = self.textures.get(id) else { return Err(crate::GfxError::InvalidTexture2DHandle) };
+		let info = &texture.info;
+		let Some(internal_format) = gl_compressed_format(info.format) else { return Err(crate::GfxError::Unsupported("texture2d_set_compressed_data with an uncompressed format")) };
+		if level >= info.mip_levels.count(info.width, info.height) {
+			return Err(crate::GfxError::IndexOutOfBounds);
+		}
+		let width = i32::max(1, info.width >> level);
+		let height = i32::max(1, info.height >> level);
+		let size = info.format.data_size(width, height);
+		if data.len() < size {
+			return Err(crate::GfxError::IndexOutOfBounds);
+		}
+		check(|| unsafe { gl::BindTexture(gl::TEXTURE_2D, texture.texture) });
+		check(|| unsafe { gl::CompressedTexImage2D(gl::TEXTURE_2D, level as i32, internal_format, width, height, 0, size as i32, data.as_ptr() as *const _) });
+		if level == 0 {
+			gl_texture_props(&info.props(), max_anisotropy);
+			gl_texture_levels(info);
+		}
+		check(|| unsafe { gl::BindTexture(gl::TEXTURE_2D, 0) });
+		Ok(())
+	}
+
+	fn texture2d_generate_mips(&mut self, id: crate::Texture2D) -> Result<(), crate::GfxError> {
+		let Some(texture) = self.textures.get(id) else { return Err(crate::GfxError::InvalidTexture2DHandle) };
+		check(|| unsafe { gl::BindTexture(gl::TEXTURE_2D, texture.texture) });
+		gl_texture_levels(&texture.info);
+		check(|| unsafe { gl::BindTexture(gl::TEXTURE_2D, 0) });
+		Ok(())
+	}
+
+	fn texture2d_set_props(&mut self, id: crate::Texture2D, props: &crate::TextureProps) -> Result<(), crate::GfxError> {
+		let max_anisotropy = self.capabilities().max_anisotropy;
+		let Some(texture) = self.textures.get_mut(id) else { return Err(crate::GfxError::InvalidTexture2DHandle) };
+		texture.info.set_props(props);
+		check(|| unsafe { gl::BindTexture(gl::TEXTURE_2D, texture.texture) });
+		gl_texture_props(props, max_anisotropy);
+		gl_texture_levels(&texture.info);
 		check(|| unsafe { gl::BindTexture(gl::TEXTURE_2D, 0) });
 		Ok(())
 	}
 
 	fn texture2d_get_info(&mut self, id: crate::Texture2D) -> Result<crate::Texture2DInfo, crate::GfxError> {
 		let Some(texture) = self.textures.get(id) else { return Err(crate::GfxError::InvalidTexture2DHandle) };
-		return Ok(texture.info);
+		Ok(texture.info)
+	}
+
+	fn texture2d_copy(&mut self, src: crate::Texture2D, dst: crate::Texture2D, regions: &[crate::TextureCopy]) -> Result<(), crate::GfxError> {
+		let Some(src) = self.textures.get(src) else { return Err(crate::GfxError::InvalidTexture2DHandle) };
+		let Some(dst) = self.textures.get(dst) else { return Err(crate::GfxError::InvalidTexture2DHandle) };
+
+		// Read from the source texture through a temporary framebuffer
+		let mut frame_buf = 0;
+		check(|| unsafe { gl::GenFramebuffers(1, &mut frame_buf) });
+		check(|| unsafe { gl::BindFramebuffer(gl::READ_FRAMEBUFFER, frame_buf) });
+		check(|| unsafe { gl::FramebufferTexture2D(gl::READ_FRAMEBUFFER, gl::COLOR_ATTACHMENT0, gl::TEXTURE_2D, src.texture, 0) });
+		check(|| unsafe { gl::BindTexture(gl::TEXTURE_2D, dst.texture) });
+		for region in regions {
+			let rc = region.src_rect;
+			check(|| unsafe { gl::CopyTexSubImage2D(gl::TEXTURE_2D, 0, region.dst_pos.x, region.dst_pos.y, rc.mins.x, rc.mins.y, rc.width(), rc.height()) });
+		}
+		check(|| unsafe { gl::BindTexture(gl::TEXTURE_2D, 0) });
+		check(|| unsafe { gl::BindFramebuffer(gl::READ_FRAMEBUFFER, 0) });
+		check(|| unsafe { gl::DeleteFramebuffers(1, &frame_buf) });
+		Ok(())
 	}
 
 	fn texture2d_delete(&mut self, id: crate::Texture2D, free_handle: bool) -> Result<(), crate::GfxError> {
@@ -685,6 +1521,144 @@ impl crate::IGraphics for GlGraphics {
 		Ok(())
 	}
 
+	fn texturecube_create(&mut self, name: Option<&str>, info: &crate::TextureCubeInfo) -> Result<crate::TextureCube, crate::GfxError> {
+		let Some((internal, format, ty)) = gl_texture_format(info.format) else { return Err(crate::GfxError::Unsupported("texturecube_create with a compressed format")) };
+		let mut texture = 0;
+		check(|| unsafe { gl::GenTextures(1, &mut texture) });
+		check(|| unsafe { gl::BindTexture(gl::TEXTURE_CUBE_MAP, texture) });
+		for face in crate::CubeFace::ALL {
+			check(|| unsafe { gl::TexImage2D(gl::TEXTURE_CUBE_MAP_POSITIVE_X + face as u32, 0, internal as i32, info.size, info.size, 0, format, ty, std::ptr::null()) });
+		}
+		check(|| unsafe { gl::TexParameteri(gl::TEXTURE_CUBE_MAP, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as gl::types::GLint) });
+		check(|| unsafe { gl::TexParameteri(gl::TEXTURE_CUBE_MAP, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as gl::types::GLint) });
+		check(|| unsafe { gl::TexParameteri(gl::TEXTURE_CUBE_MAP, gl::TEXTURE_WRAP_R, gl::CLAMP_TO_EDGE as gl::types::GLint) });
+		check(|| unsafe { gl::TexParameteri(gl::TEXTURE_CUBE_MAP, gl::TEXTURE_MAG_FILTER, gl_texture_filter(info.filter_mag.base()) as gl::types::GLint) });
+		check(|| unsafe { gl::TexParameteri(gl::TEXTURE_CUBE_MAP, gl::TEXTURE_MIN_FILTER, gl_texture_filter(info.filter_min.base()) as gl::types::GLint) });
+		check(|| unsafe { gl::BindTexture(gl::TEXTURE_CUBE_MAP, 0) });
+		// Filter across the face edges
+		check(|| unsafe { gl::Enable(gl::TEXTURE_CUBE_MAP_SEAMLESS) });
+		let id = self.cubes.insert(name, GlTextureCube { texture, info: *info });
+		Ok(id)
+	}
+
+	fn texturecube_find(&mut self, name: &str) -> Result<crate::TextureCube, crate::GfxError> {
+		let Some(id) = self.cubes.find_id(name) else { return Err(crate::GfxError::NameNotFound) };
+		Ok(id)
+	}
+
+	fn texturecube_set_face_data(&mut self, id: crate::TextureCube, face: crate::CubeFace, data: &[u8]) -> Result<(), crate::GfxError> {
+		let Some(cube) = self.cubes.get(id) else { return Err(crate::GfxError::InvalidTextureCubeHandle) };
+		let Some((_, format, ty)) = gl_texture_format(cube.info.format) else { return Err(crate::GfxError::Unsupported("texturecube_set_face_data with a compressed format")) };
+		if data.len() < cube.info.format.data_size(cube.info.size, cube.info.size) {
+			return Err(crate::GfxError::IndexOutOfBounds);
+		}
+		check(|| unsafe { gl::BindTexture(gl::TEXTURE_CUBE_MAP, cube.texture) });
+		check(|| unsafe { gl::TexSubImage2D(gl::TEXTURE_CUBE_MAP_POSITIVE_X + face as u32, 0, 0, 0, cube.info.size, cube.info.size, format, ty, data.as_ptr() as *const _) });
+		check(|| unsafe { gl::BindTexture(gl::TEXTURE_CUBE_MAP, 0) });
+		Ok(())
+	}
+
+	fn texturecube_get_info(&mut self, id: crate::TextureCube) -> Result<crate::TextureCubeInfo, crate::GfxError> {
+		let Some(cube) = self.cubes.get(id) else { return Err(crate::GfxError::InvalidTextureCubeHandle) };
+		Ok(cube.info)
+	}
+
+	fn texturecube_delete(&mut self, id: crate::TextureCube, free_handle: bool) -> Result<(), crate::GfxError> {
+		let Some(cube) = self.cubes.remove(id, free_handle) else { return Err(crate::GfxError::InvalidTextureCubeHandle) };
+		check(|| unsafe { gl::DeleteTextures(1, &cube.texture) });
+		Ok(())
+	}
+
+	fn texture3d_create(&mut self, name: Option<&str>, info: &crate::Texture3DInfo) -> Result<crate::Texture3D, crate::GfxError> {
+		let Some((internal, format, ty)) = gl_texture_format(info.format) else { return Err(crate::GfxError::Unsupported("texture3d_create with a compressed format")) };
+		let mut texture = 0;
+		check(|| unsafe { gl::GenTextures(1, &mut texture) });
+		check(|| unsafe { gl::BindTexture(gl::TEXTURE_3D, texture) });
+		check(|| unsafe { gl::TexImage3D(gl::TEXTURE_3D, 0, internal as i32, info.width, info.height, info.depth, 0, format, ty, std::ptr::null()) });
+		check(|| unsafe { gl::TexParameteri(gl::TEXTURE_3D, gl::TEXTURE_WRAP_S, gl_texture_wrap(info.wrap_u) as gl::types::GLint) });
+		check(|| unsafe { gl::TexParameteri(gl::TEXTURE_3D, gl::TEXTURE_WRAP_T, gl_texture_wrap(info.wrap_v) as gl::types::GLint) });
+		check(|| unsafe { gl::TexParameteri(gl::TEXTURE_3D, gl::TEXTURE_WRAP_R, gl_texture_wrap(info.wrap_w) as gl::types::GLint) });
+		check(|| unsafe { gl::TexParameteri(gl::TEXTURE_3D, gl::TEXTURE_MAG_FILTER, gl_texture_filter(info.filter_mag.base()) as gl::types::GLint) });
+		check(|| unsafe { gl::TexParameteri(gl::TEXTURE_3D, gl::TEXTURE_MIN_FILTER, gl_texture_filter(info.filter_min.base()) as gl::types::GLint) });
+		check(|| unsafe { gl::BindTexture(gl::TEXTURE_3D, 0) });
+		let id = self.volumes.insert(name, GlTexture3D { texture, info: *info });
+		Ok(id)
+	}
+
+	fn texture3d_find(&mut self, name: &str) -> Result<crate::Texture3D, crate::GfxError> {
+		let Some(id) = self.volumes.find_id(name) else { return Err(crate::GfxError::NameNotFound) };
+		Ok(id)
+	}
+
+	fn texture3d_set_data(&mut self, id: crate::Texture3D, data: &[u8]) -> Result<(), crate::GfxError> {
+		let Some(volume) = self.volumes.get(id) else { return Err(crate::GfxError::InvalidTexture3DHandle) };
+		let info = &volume.info;
+		let Some((_, format, ty)) = gl_texture_format(info.format) else { return Err(crate::GfxError::Unsupported("texture3d_set_data with a compressed format")) };
+		if data.len() < info.format.data_size(info.width, info.height) * i32::max(0, info.depth) as usize {
+			return Err(crate::GfxError::IndexOutOfBounds);
+		}
+		check(|| unsafe { gl::BindTexture(gl::TEXTURE_3D, volume.texture) });
+		check(|| unsafe { gl::TexSubImage3D(gl::TEXTURE_3D, 0, 0, 0, 0, info.width, info.height, info.depth, format, ty, data.as_ptr() as *const _) });
+		check(|| unsafe { gl::BindTexture(gl::TEXTURE_3D, 0) });
+		Ok(())
+	}
+
+	fn texture3d_get_info(&mut self, id: crate::Texture3D) -> Result<crate::Texture3DInfo, crate::GfxError> {
+		let Some(volume) = self.volumes.get(id) else { return Err(crate::GfxError::InvalidTexture3DHandle) };
+		Ok(volume.info)
+	}
+
+	fn texture3d_delete(&mut self, id: crate::Texture3D, free_handle: bool) -> Result<(), crate::GfxError> {
+		let Some(volume) = self.volumes.remove(id, free_handle) else { return Err(crate::GfxError::InvalidTexture3DHandle) };
+		check(|| unsafe { gl::DeleteTextures(1, &volume.texture) });
+		Ok(())
+	}
+
+	fn texture2darray_create(&mut self, name: Option<&str>, info: &crate::Texture2DArrayInfo) -> Result<crate::Texture2DArray, crate::GfxError> {
+		let Some((internal, format, ty)) = gl_texture_format(info.format) else { return Err(crate::GfxError::Unsupported("texture2darray_create with a compressed format")) };
+		let filter_min = if info.mipmaps { gl_texture_filter_mipmap(info.filter_min) } else { gl_texture_filter(info.filter_min.base()) };
+		let mut texture = 0;
+		check(|| unsafe { gl::GenTextures(1, &mut texture) });
+		check(|| unsafe { gl::BindTexture(gl::TEXTURE_2D_ARRAY, texture) });
+		check(|| unsafe { gl::TexImage3D(gl::TEXTURE_2D_ARRAY, 0, internal as i32, info.width, info.height, info.layers, 0, format, ty, std::ptr::null()) });
+		check(|| unsafe { gl::TexParameteri(gl::TEXTURE_2D_ARRAY, gl::TEXTURE_WRAP_S, gl_texture_wrap(info.wrap_u) as gl::types::GLint) });
+		check(|| unsafe { gl::TexParameteri(gl::TEXTURE_2D_ARRAY, gl::TEXTURE_WRAP_T, gl_texture_wrap(info.wrap_v) as gl::types::GLint) });
+		check(|| unsafe { gl::TexParameteri(gl::TEXTURE_2D_ARRAY, gl::TEXTURE_MAG_FILTER, gl_texture_filter(info.filter_mag.base()) as gl::types::GLint) });
+		check(|| unsafe { gl::TexParameteri(gl::TEXTURE_2D_ARRAY, gl::TEXTURE_MIN_FILTER, filter_min as gl::types::GLint) });
+		check(|| unsafe { gl::BindTexture(gl::TEXTURE_2D_ARRAY, 0) });
+		let id = self.arrays.insert(name, GlTexture2DArray { texture, info: *info });
+		Ok(id)
+	}
+
+	fn texture2darray_find(&mut self, name: &str) -> Result<crate::Texture2DArray, crate::GfxError> {
+		let Some(id) = self.arrays.find_id(name) else { return Err(crate::GfxError::NameNotFound) };
+		Ok(id)
+	}
+
+	fn texture2darray_set_data(&mut self, id: crate::Texture2DArray, data: &[u8]) -> Result<(), crate::GfxError> {
+		let Some(array) = self.arrays.get(id) else { return Err(crate::GfxError::InvalidTexture2DArrayHandle) };
+		gl_texture2darray_upload(array, 0, array.info.layers, data)
+	}
+
+	fn texture2darray_set_layer(&mut self, id: crate::Texture2DArray, layer: i32, data: &[u8]) -> Result<(), crate::GfxError> {
+		let Some(array) = self.arrays.get(id) else { return Err(crate::GfxError::InvalidTexture2DArrayHandle) };
+		if layer < 0 || layer >= array.info.layers {
+			return Err(crate::GfxError::IndexOutOfBounds);
+		}
+		gl_texture2darray_upload(array, layer, 1, data)
+	}
+
+	fn texture2darray_get_info(&mut self, id: crate::Texture2DArray) -> Result<crate::Texture2DArrayInfo, crate::GfxError> {
+		let Some(array) = self.arrays.get(id) else { return Err(crate::GfxError::InvalidTexture2DArrayHandle) };
+		Ok(array.info)
+	}
+
+	fn texture2darray_delete(&mut self, id: crate::Texture2DArray, free_handle: bool) -> Result<(), crate::GfxError> {
+		let Some(array) = self.arrays.remove(id, free_handle) else { return Err(crate::GfxError::InvalidTexture2DArrayHandle) };
+		check(|| unsafe { gl::DeleteTextures(1, &array.texture) });
+		Ok(())
+	}
+
 	fn surface_create(&mut self, name: Option<&str>, info: &crate::SurfaceInfo) -> Result<crate::Surface, crate::GfxError> {
 		let texture = Handle::create(0);
 
@@ -692,18 +1666,22 @@ impl crate::IGraphics for GlGraphics {
 		let mut depth_buf = 0;
 		let mut tex_buf = 0;
 		check(|| unsafe { gl::GenFramebuffers(1, &mut frame_buf) });
-		check(|| unsafe { gl::GenRenderbuffers(1, &mut depth_buf) });
 		check(|| unsafe { gl::GenTextures(1, &mut tex_buf) });
 
 		check(|| unsafe { gl::BindFramebuffer(gl::FRAMEBUFFER, frame_buf) });
 
-		check(|| unsafe { gl::BindRenderbuffer(gl::RENDERBUFFER, depth_buf) });
-		check(|| unsafe { gl::RenderbufferStorage(gl::RENDERBUFFER, gl::DEPTH_COMPONENT, info.width, info.height) });
-		check(|| unsafe { gl::FramebufferRenderbuffer(gl::FRAMEBUFFER, gl::DEPTH_ATTACHMENT, gl::RENDERBUFFER, depth_buf) });
+		if info.has_depth {
+			let (depth_internal, depth_attachment) = gl_depth_format(info.depth_format);
+			check(|| unsafe { gl::GenRenderbuffers(1, &mut depth_buf) });
+			check(|| unsafe { gl::BindRenderbuffer(gl::RENDERBUFFER, depth_buf) });
+			check(|| unsafe { gl::RenderbufferStorage(gl::RENDERBUFFER, depth_internal, info.width, info.height) });
+			check(|| unsafe { gl::FramebufferRenderbuffer(gl::FRAMEBUFFER, depth_attachment, gl::RENDERBUFFER, depth_buf) });
+		}
 
 		check(|| unsafe { gl::BindTexture(gl::TEXTURE_2D, tex_buf) });
 
-		check(|| unsafe { gl::TexImage2D(gl::TEXTURE_2D, 0, gl::RGBA as i32, info.width, info.height, 0, gl::RGBA, gl::UNSIGNED_BYTE, std::ptr::null()) });
+		let (internal, format, ty) = gl_surface_format(info.format);
+		check(|| unsafe { gl::TexImage2D(gl::TEXTURE_2D, 0, internal as i32, info.width, info.height, 0, format, ty, std::ptr::null()) });
 		check(|| unsafe { gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as i32) });
 		check(|| unsafe { gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as i32) });
 		check(|| unsafe { gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32) });
@@ -720,34 +1698,48 @@ impl crate::IGraphics for GlGraphics {
 		// 	panic!("Framebuffer is not complete: {}", status);
 		// }
 
-		let id = self.surfaces.insert(name, GlSurface { texture, frame_buf, depth_buf, tex_buf, format: info.format, width: info.width, height: info.height });
-		return Ok(id);
+		let id = self.surfaces.insert(name, GlSurface { texture, frame_buf, depth_buf, tex_buf, format: info.format, depth_format: info.depth_format, width: info.width, height: info.height });
+		Ok(id)
 	}
 
 	fn surface_find(&mut self, name: &str) -> Result<crate::Surface, crate::GfxError> {
 		let Some(id) = self.surfaces.find_id(name) else { return Err(crate::GfxError::NameNotFound) };
-		return Ok(id);
+		Ok(id)
 	}
 
 	fn surface_get_info(&mut self, id: crate::Surface) -> Result<crate::SurfaceInfo, crate::GfxError> {
 		let Some(surface) = self.surfaces.get(id) else { return Err(crate::GfxError::InvalidSurfaceHandle) };
-		return Ok(crate::SurfaceInfo {
+		Ok(crate::SurfaceInfo {
 			offscreen: true,
 			has_depth: surface.depth_buf != 0,
 			has_texture: surface.texture.id() != 0,
 			format: surface.format,
+			depth_format: surface.depth_format,
 			width: surface.width,
 			height: surface.height,
-		});
+		})
 	}
 
 	fn surface_set_info(&mut self, _id: crate::Surface, _info: &crate::SurfaceInfo) -> Result<(), crate::GfxError> {
-		Err(crate::GfxError::InternalError)
+		Err(crate::GfxError::Unsupported("surface_set_info"))
 	}
 
 	fn surface_get_texture(&mut self, id: crate::Surface) -> Result<crate::Texture2D, crate::GfxError> {
 		let Some(surface) = self.surfaces.get(id) else { return Err(crate::GfxError::InvalidSurfaceHandle) };
-		return Ok(surface.texture);
+		Ok(surface.texture)
+	}
+
+	fn blit(&mut self, src: crate::Surface, src_rect: &cvmath::Rect<i32>, dst: crate::Surface, dst_rect: &cvmath::Rect<i32>, filter: crate::TextureFilter) -> Result<(), crate::GfxError> {
+		let src_buf = self.surface_frame_buf(src)?;
+		let dst_buf = self.surface_frame_buf(dst)?;
+		check(|| unsafe { gl::BindFramebuffer(gl::READ_FRAMEBUFFER, src_buf) });
+		check(|| unsafe { gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, dst_buf) });
+		check(|| unsafe { gl::BlitFramebuffer(
+			src_rect.mins.x, src_rect.mins.y, src_rect.maxs.x, src_rect.maxs.y,
+			dst_rect.mins.x, dst_rect.mins.y, dst_rect.maxs.x, dst_rect.maxs.y,
+			gl::COLOR_BUFFER_BIT, gl_texture_filter(filter.base())) });
+		check(|| unsafe { gl::BindFramebuffer(gl::FRAMEBUFFER, 0) });
+		Ok(())
 	}
 
 	fn surface_delete(&mut self, id: crate::Surface, free_handle: bool) -> Result<(), crate::GfxError> {
@@ -755,6 +1747,31 @@ impl crate::IGraphics for GlGraphics {
 		self.texture2d_delete(surface.texture, free_handle)?;
 		Ok(())
 	}
+
+	fn frame_fence(&mut self) -> Result<crate::Fence, crate::GfxError> {
+		if !gl::FenceSync::is_loaded() {
+			return Err(crate::GfxError::Unsupported("fences"));
+		}
+		let sync = check(|| unsafe { gl::FenceSync(gl::SYNC_GPU_COMMANDS_COMPLETE, 0) });
+		let id = self.fences.insert(None, GlFence { sync });
+		Ok(id)
+	}
+
+	fn fence_wait(&mut self, id: crate::Fence, timeout_ns: u64) -> Result<bool, crate::GfxError> {
+		let Some(fence) = self.fences.get(id) else { return Err(crate::GfxError::InvalidFenceHandle) };
+		let status = check(|| unsafe { gl::ClientWaitSync(fence.sync, gl::SYNC_FLUSH_COMMANDS_BIT, timeout_ns) });
+		match status {
+			gl::ALREADY_SIGNALED | gl::CONDITION_SATISFIED => Ok(true),
+			gl::TIMEOUT_EXPIRED => Ok(false),
+			_ => Err(crate::GfxError::InternalError),
+		}
+	}
+
+	fn fence_delete(&mut self, id: crate::Fence) -> Result<(), crate::GfxError> {
+		let Some(fence) = self.fences.remove(id, true) else { return Err(crate::GfxError::InvalidFenceHandle) };
+		check(|| unsafe { gl::DeleteSync(fence.sync) });
+		Ok(())
+	}
 }
 
 impl ops::Deref for GlGraphics {
@@ -772,22 +1789,34 @@ impl ops::DerefMut for GlGraphics {
 	}
 }
 
-#[cfg(debug_assertions)]
+static CHECK_ERRORS: AtomicBool = AtomicBool::new(cfg!(debug_assertions));
+static LAST_ERROR: AtomicU32 = AtomicU32::new(gl::NO_ERROR);
+static BREAK_ON_ERROR: AtomicBool = AtomicBool::new(false);
+static DEBUG_BREAK: AtomicBool = AtomicBool::new(false);
+
 #[inline]
 #[track_caller]
 fn check<T, F: FnOnce() -> T>(f: F) -> T {
 	let result = f();
-	{
-		let error = unsafe { gl::GetError() };
-		if error != gl::NO_ERROR {
-			panic!("OpenGL error: {}", error);
-		}
+	if cfg!(debug_assertions) || CHECK_ERRORS.load(Ordering::Relaxed) {
+		check_error();
+	}
+	#[cfg(debug_assertions)]
+	if DEBUG_BREAK.swap(false, Ordering::Relaxed) {
+		panic!("OpenGL high severity debug message");
 	}
 	result
 }
 
-#[cfg(not(debug_assertions))]
-#[inline]
-fn check<T, F: FnOnce() -> T>(f: F) -> T {
-	f()
+#[inline(never)]
+#[track_caller]
+fn check_error() {
+	let error = unsafe { gl::GetError() };
+	if error != gl::NO_ERROR {
+		LAST_ERROR.store(error, Ordering::Relaxed);
+		#[cfg(debug_assertions)]
+		panic!("OpenGL error: {}", error);
+		#[cfg(not(debug_assertions))]
+		gfx_log!(Error, General, "OpenGL error: {} at {}", error, std::panic::Location::caller());
+	}
 }
@@ -0,0 +1,13 @@
+#version 330 core
+out vec4 FragColor;
+
+in vec2 v_texcoord;
+in vec4 v_color;
+in vec4 v_outline;
+
+uniform sampler2D u_texture;
+
+void main() {
+	vec4 color = texture(u_texture, v_texcoord);
+	FragColor = vec4(color.rgb, color.a * v_color.a);
+}
@@ -0,0 +1,23 @@
+// Equirectangular sampling helpers, paste into a shader after the #version line.
+// Matches the conventions of shade::equirect_coords.
+
+const float EQUIRECT_PI = 3.14159265359;
+
+vec2 equirect_uv(vec3 dir) {
+	dir = normalize(dir);
+	float u = atan(dir.z, dir.x) / (2.0 * EQUIRECT_PI) + 0.5;
+	float v = acos(clamp(dir.y, -1.0, 1.0)) / EQUIRECT_PI;
+	return vec2(u, v);
+}
+
+// Samples an equirectangular texture without the seam where the longitude wraps around.
+// The texture must use the repeat wrap mode horizontally.
+vec4 texture_equirect(sampler2D tex, vec3 dir) {
+	vec2 uv = equirect_uv(dir);
+	vec2 dx = dFdx(uv);
+	vec2 dy = dFdy(uv);
+	// Unwrap the derivatives across the seam so the seam does not select the smallest mip level
+	dx.x -= round(dx.x);
+	dy.x -= round(dy.x);
+	return textureGrad(tex, uv, dx, dy);
+}
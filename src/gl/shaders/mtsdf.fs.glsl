@@ -13,6 +13,7 @@ uniform float u_out_bias;
 uniform float u_outline_width_absolute;
 uniform float u_outline_width_relative;
 uniform float u_gamma;
+uniform int u_layer;
 
 float median(vec3 distances) {
 	return max(min(distances.r, distances.g), min(max(distances.r, distances.g), distances.b));
@@ -34,6 +35,18 @@ void main() {
 	inner = clamp(inner, 0.0, 1.0);
 	outer = clamp(outer, 0.0, 1.0);
 
-	vec4 color = v_color * inner + v_outline * (outer - inner);
+	vec4 color;
+	if (u_layer == 1) {
+		color = v_color * inner;
+	}
+	else if (u_layer == 2) {
+		color = v_outline * outer;
+	}
+	else if (u_layer == 3) {
+		color = v_outline * (outer - inner);
+	}
+	else {
+		color = v_color * inner + v_outline * (outer - inner);
+	}
 	FragColor = pow(color, vec4(1.0 / u_gamma));
 }
@@ -0,0 +1,10 @@
+#version 330 core
+out vec4 FragColor;
+
+flat in int v_index;
+
+uniform sampler2D u_palette;
+
+void main() {
+	FragColor = texelFetch(u_palette, ivec2(v_index, 0), 0);
+}
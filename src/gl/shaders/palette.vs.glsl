@@ -0,0 +1,12 @@
+#version 330 core
+layout (location = 0) in vec2 a_pos;
+layout (location = 1) in float a_index;
+
+flat out int v_index;
+
+uniform mat3x2 u_transform;
+
+void main() {
+	v_index = int(a_index + 0.5);
+	gl_Position = vec4(u_transform * vec3(a_pos, 1.0), 0.0, 1.0);
+}
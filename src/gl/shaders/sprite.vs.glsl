@@ -0,0 +1,18 @@
+#version 330 core
+layout (location = 0) in vec2 a_pos;
+layout (location = 1) in vec2 a_size;
+layout (location = 2) in vec4 a_uv;
+layout (location = 3) in vec4 a_color;
+
+out vec2 v_texcoord;
+out vec4 v_color;
+
+uniform mat3x2 u_transform;
+
+void main() {
+	// Quad corner from the vertex index, drawn as a triangle strip
+	vec2 corner = vec2(gl_VertexID & 1, gl_VertexID >> 1);
+	v_texcoord = mix(a_uv.xy, a_uv.zw, corner);
+	v_color = a_color;
+	gl_Position = vec4(u_transform * vec3(a_pos + a_size * corner, 1.0), 0.0, 1.0);
+}
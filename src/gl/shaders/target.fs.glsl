@@ -0,0 +1,34 @@
+#version 330 core
+out vec4 FragColor;
+
+in vec2 v_texcoord;
+
+uniform sampler2D u_texture;
+uniform int u_mode;
+uniform float u_near;
+uniform float u_far;
+
+void main() {
+	vec4 color = texture(u_texture, v_texcoord);
+
+	// Color
+	if (u_mode == 0) {
+		FragColor = vec4(color.rgb, 1.0);
+	}
+	// Single channel
+	else if (u_mode >= 1 && u_mode <= 4) {
+		float value = color[u_mode - 1];
+		FragColor = vec4(vec3(value), 1.0);
+	}
+	// Normals in [-1, 1]
+	else if (u_mode == 5) {
+		FragColor = vec4(color.rgb * 0.5 + 0.5, 1.0);
+	}
+	// Linearized depth
+	else {
+		float z = color.r * 2.0 - 1.0;
+		float linear = 2.0 * u_near * u_far / (u_far + u_near - z * (u_far - u_near));
+		float value = (linear - u_near) / (u_far - u_near);
+		FragColor = vec4(vec3(clamp(value, 0.0, 1.0)), 1.0);
+	}
+}
@@ -0,0 +1,58 @@
+#version 330 core
+out vec4 FragColor;
+
+in vec2 v_texcoord;
+
+uniform sampler2D u_from;
+uniform sampler2D u_to;
+uniform int u_mode;
+uniform float u_progress;
+uniform vec2 u_direction;
+uniform float u_count;
+uniform float u_softness;
+uniform vec2 u_resolution;
+
+float hash(vec2 p) {
+	return fract(sin(dot(p, vec2(12.9898, 78.233))) * 43758.5453);
+}
+
+// Mix factor for a threshold which sweeps from 0 to 1 with soft edges
+float sweep(float value) {
+	float edge = u_progress * (1.0 + u_softness);
+	return 1.0 - smoothstep(edge - u_softness, edge, value);
+}
+
+void main() {
+	vec4 from = texture(u_from, v_texcoord);
+	vec4 to = texture(u_to, v_texcoord);
+	float t = u_progress;
+
+	// Crossfade
+	if (u_mode == 0) {
+		FragColor = mix(from, to, t);
+	}
+	// Wipe
+	else if (u_mode == 1) {
+		vec2 dir = normalize(u_direction);
+		float value = dot(v_texcoord - 0.5, dir) / (abs(dir.x) + abs(dir.y)) + 0.5;
+		FragColor = mix(from, to, sweep(value));
+	}
+	// Shutters
+	else if (u_mode == 2) {
+		vec2 dir = normalize(u_direction);
+		float value = fract(dot(v_texcoord, dir) * u_count);
+		FragColor = mix(from, to, sweep(value));
+	}
+	// Pixelate
+	else if (u_mode == 3) {
+		float size = max(1.0, u_count * (1.0 - abs(t * 2.0 - 1.0)));
+		vec2 cell = size / u_resolution;
+		vec2 uv = (floor(v_texcoord / cell) + 0.5) * cell;
+		FragColor = t < 0.5 ? texture(u_from, uv) : texture(u_to, uv);
+	}
+	// Dissolve
+	else {
+		float value = hash(floor(v_texcoord * u_resolution / max(u_count, 1.0)));
+		FragColor = mix(from, to, sweep(value));
+	}
+}
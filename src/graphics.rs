@@ -7,8 +7,16 @@ pub struct ClearArgs {
 	pub surface: Surface,
 	/// Scissor rectangle.
 	pub scissor: Option<cvmath::Rect<i32>>,
+	/// Color attachment to clear.
+	///
+	/// If `None` the color is applied to all color attachments.
+	pub attachment: Option<u32>,
 	/// Color to clear with.
 	pub color: Option<cvmath::Vec4<f32>>,
+	/// Color to clear signed integer attachments with.
+	pub color_int: Option<cvmath::Vec4<i32>>,
+	/// Color to clear unsigned integer attachments with.
+	pub color_uint: Option<cvmath::Vec4<u32>>,
 	/// Depth to clear with.
 	pub depth: Option<f32>,
 	/// Stencil to clear with.
@@ -29,6 +37,16 @@ pub struct DrawArgs {
 	pub depth_test: Option<DepthTest>,
 	/// Triangle culling mode.
 	pub cull_mode: Option<CullMode>,
+	/// Polygon fill mode.
+	pub fill_mode: FillMode,
+	/// Use the alpha as multisample coverage mask.
+	///
+	/// Smooths the edges of alpha tested geometry without sorting, requires a multisampled surface.
+	pub alpha_to_coverage: bool,
+	/// Stencil mode.
+	///
+	/// Requires a surface with a stencil buffer.
+	pub stencil: Option<StencilMode>,
 	/// Primitive type.
 	pub prim_type: PrimType,
 	/// Shader used.
@@ -63,6 +81,16 @@ pub struct DrawIndexedArgs {
 	pub depth_test: Option<DepthTest>,
 	/// Triangle culling mode.
 	pub cull_mode: Option<CullMode>,
+	/// Polygon fill mode.
+	pub fill_mode: FillMode,
+	/// Use the alpha as multisample coverage mask.
+	///
+	/// Smooths the edges of alpha tested geometry without sorting, requires a multisampled surface.
+	pub alpha_to_coverage: bool,
+	/// Stencil mode.
+	///
+	/// Requires a surface with a stencil buffer.
+	pub stencil: Option<StencilMode>,
 	/// Primitive type.
 	pub prim_type: PrimType,
 	/// Shader used.
@@ -81,6 +109,10 @@ pub struct DrawIndexedArgs {
 	pub index_start: u32,
 	/// Index of one past the last index.
 	pub index_end: u32,
+	/// Value added to every index before fetching the vertex.
+	///
+	/// Allows packed index buffers to reference meshes in a shared vertex pool.
+	pub base_vertex: i32,
 	/// Index of the uniform to use.
 	pub uniform_index: u32,
 	/// Number of instances to draw.
@@ -97,18 +129,38 @@ pub enum GfxError {
 	InvalidUniformBufferHandle,
 	InvalidShaderHandle,
 	InvalidTexture2DHandle,
+	InvalidTextureCubeHandle,
+	InvalidTexture3DHandle,
+	InvalidTexture2DArrayHandle,
 	InvalidSurfaceHandle,
+	InvalidFenceHandle,
 	IndexOutOfBounds,
 	InvalidDrawCallTime,
 	ShaderCompileError,
 	NameNotFound,
 	InternalError,
+	/// The feature is not supported by the graphics backend.
+	Unsupported(&'static str),
+	/// Error code reported by the graphics backend.
+	BackendError(u32),
 }
 
 /// Graphics interface.
 ///
 /// See [`Graphics`](struct.Graphics.html) for a type-erased version.
 pub trait IGraphics {
+	/// Query the capabilities of the graphics backend.
+	fn capabilities(&mut self) -> Caps;
+	/// Returns and clears the last error reported by the graphics backend.
+	fn last_error(&mut self) -> Option<GfxError>;
+
+	/// Notify the backend the size or scale of the display changed.
+	///
+	/// Call when the window is resized or moved to a monitor with a different DPI.
+	fn display_update(&mut self, size: cvmath::Vec2<i32>, scale: f32);
+	/// Returns the size and scale of the display.
+	fn display_info(&self) -> DisplayInfo;
+
 	/// Begin drawing.
 	fn begin(&mut self) -> Result<(), GfxError>;
 	/// Clear the surface.
@@ -120,6 +172,13 @@ pub trait IGraphics {
 	/// End drawing.
 	fn end(&mut self) -> Result<(), GfxError>;
 
+	/// Capture the resolved uniforms and up to `max_vertices` vertices of a draw call for debugging.
+	///
+	/// The draw index counts the draw calls since [begin](IGraphics::begin), `None` stops capturing.
+	fn debug_capture(&mut self, draw_index: Option<u32>, max_vertices: usize);
+	/// Take the last captured draw call.
+	fn debug_capture_take(&mut self) -> Option<DrawCapture>;
+
 	/// Create a vertex buffer.
 	fn vertex_buffer_create(&mut self, name: Option<&str>, layout: &'static VertexLayout, count: usize) -> Result<VertexBuffer, GfxError>;
 	/// Find a vertex buffer by name.
@@ -146,6 +205,13 @@ pub trait IGraphics {
 	fn uniform_buffer_set_data(&mut self, id: UniformBuffer, data: &[u8]) -> Result<(), GfxError>;
 	/// Release the resources of a uniform buffer.
 	fn uniform_buffer_delete(&mut self, id: UniformBuffer, free_handle: bool) -> Result<(), GfxError>;
+	/// Bind an element of a uniform buffer to the named uniform block for the following draw calls.
+	///
+	/// The element is uploaded once with the std140 layout and shared by every shader declaring the block.
+	/// Shaders without the block receive its attributes as loose uniforms.
+	fn uniform_block_bind(&mut self, block: &str, id: UniformBuffer, uniform_index: u32) -> Result<(), GfxError>;
+	/// Unbind the named uniform block.
+	fn uniform_block_unbind(&mut self, block: &str) -> Result<(), GfxError>;
 
 	/// Create a shader.
 	fn shader_create(&mut self, name: Option<&str>) -> Result<Shader, GfxError>;
@@ -155,6 +221,10 @@ pub trait IGraphics {
 	fn shader_compile(&mut self, id: Shader, vertex_source: &str, fragment_source: &str) -> Result<(), GfxError>;
 	/// Get the compile log of a shader.
 	fn shader_compile_log(&mut self, id: Shader) -> Result<String, GfxError>;
+	/// Set default uniform values of a shader.
+	///
+	/// Merged with the defaults annotated in the shader source, see [`ParamBlock::parse`].
+	fn shader_set_defaults(&mut self, id: Shader, params: &ParamBlock) -> Result<(), GfxError>;
 	/// Release the resources of a shader.
 	fn shader_delete(&mut self, id: Shader, free_handle: bool) -> Result<(), GfxError>;
 
@@ -163,12 +233,70 @@ pub trait IGraphics {
 	/// Find a 2D texture by name.
 	fn texture2d_find(&mut self, name: &str) -> Result<Texture2D, GfxError>;
 	/// Set the data of a 2D texture.
+	///
+	/// Generates the mip levels of the texture.
 	fn texture2d_set_data(&mut self, id: Texture2D, data: &[u8]) -> Result<(), GfxError>;
+	/// Set the compressed data of a mip level of a 2D texture.
+	///
+	/// The texture must be created with a compressed format, see [`TextureFormat::data_size`] for the size of the data.
+	/// Mip levels are not generated for compressed textures, the [`mip_levels`](Texture2DInfo::mip_levels) of the texture are uploaded one by one.
+	fn texture2d_set_compressed_data(&mut self, id: Texture2D, level: u32, data: &[u8]) -> Result<(), GfxError>;
+	/// Generate the mip levels of a 2D texture from its base level.
+	///
+	/// Call after the base level is changed by [`texture2d_copy`](Self::texture2d_copy) or by drawing to a surface.
+	fn texture2d_generate_mips(&mut self, id: Texture2D) -> Result<(), GfxError>;
 	/// Get the info of a 2D texture.
 	fn texture2d_get_info(&mut self, id: Texture2D) -> Result<Texture2DInfo, GfxError>;
+	/// Set the sampling properties of a 2D texture.
+	fn texture2d_set_props(&mut self, id: Texture2D, props: &TextureProps) -> Result<(), GfxError>;
+	/// Copy regions of a 2D texture to another 2D texture.
+	fn texture2d_copy(&mut self, src: Texture2D, dst: Texture2D, regions: &[TextureCopy]) -> Result<(), GfxError>;
 	/// Release the resources of a 2D texture.
 	fn texture2d_delete(&mut self, id: Texture2D, free_handle: bool) -> Result<(), GfxError>;
 
+	/// Create a cube map texture.
+	fn texturecube_create(&mut self, name: Option<&str>, info: &TextureCubeInfo) -> Result<TextureCube, GfxError>;
+	/// Find a cube map texture by name.
+	fn texturecube_find(&mut self, name: &str) -> Result<TextureCube, GfxError>;
+	/// Set the data of a face of a cube map texture.
+	///
+	/// See [`equirect_to_cube`](crate::equirect_to_cube) to convert panoramas to faces.
+	fn texturecube_set_face_data(&mut self, id: TextureCube, face: CubeFace, data: &[u8]) -> Result<(), GfxError>;
+	/// Get the info of a cube map texture.
+	fn texturecube_get_info(&mut self, id: TextureCube) -> Result<TextureCubeInfo, GfxError>;
+	/// Release the resources of a cube map texture.
+	fn texturecube_delete(&mut self, id: TextureCube, free_handle: bool) -> Result<(), GfxError>;
+
+	/// Create a 3D texture.
+	fn texture3d_create(&mut self, name: Option<&str>, info: &Texture3DInfo) -> Result<Texture3D, GfxError>;
+	/// Find a 3D texture by name.
+	fn texture3d_find(&mut self, name: &str) -> Result<Texture3D, GfxError>;
+	/// Set the data of a 3D texture.
+	///
+	/// The data is stored as `depth` consecutive slices of `width` by `height` texels.
+	fn texture3d_set_data(&mut self, id: Texture3D, data: &[u8]) -> Result<(), GfxError>;
+	/// Get the info of a 3D texture.
+	fn texture3d_get_info(&mut self, id: Texture3D) -> Result<Texture3DInfo, GfxError>;
+	/// Release the resources of a 3D texture.
+	fn texture3d_delete(&mut self, id: Texture3D, free_handle: bool) -> Result<(), GfxError>;
+
+	/// Create a 2D texture array.
+	fn texture2darray_create(&mut self, name: Option<&str>, info: &Texture2DArrayInfo) -> Result<Texture2DArray, GfxError>;
+	/// Find a 2D texture array by name.
+	fn texture2darray_find(&mut self, name: &str) -> Result<Texture2DArray, GfxError>;
+	/// Set the data of all the layers of a 2D texture array.
+	///
+	/// The data is stored as `layers` consecutive images of `width` by `height` texels.
+	fn texture2darray_set_data(&mut self, id: Texture2DArray, data: &[u8]) -> Result<(), GfxError>;
+	/// Set the data of a single layer of a 2D texture array.
+	///
+	/// The mipmaps are generated again for every call, prefer [`texture2darray_set_data`](Self::texture2darray_set_data) to upload all the layers at once.
+	fn texture2darray_set_layer(&mut self, id: Texture2DArray, layer: i32, data: &[u8]) -> Result<(), GfxError>;
+	/// Get the info of a 2D texture array.
+	fn texture2darray_get_info(&mut self, id: Texture2DArray) -> Result<Texture2DArrayInfo, GfxError>;
+	/// Release the resources of a 2D texture array.
+	fn texture2darray_delete(&mut self, id: Texture2DArray, free_handle: bool) -> Result<(), GfxError>;
+
 	/// Create a surface.
 	fn surface_create(&mut self, name: Option<&str>, info: &SurfaceInfo) -> Result<Surface, GfxError>;
 	/// Find a surface by name.
@@ -179,8 +307,21 @@ pub trait IGraphics {
 	fn surface_set_info(&mut self, id: Surface, info: &SurfaceInfo) -> Result<(), GfxError>;
 	/// Get the texture of a surface.
 	fn surface_get_texture(&mut self, id: Surface) -> Result<Texture2D, GfxError>;
+	/// Copy a rectangle of a surface to a rectangle of another surface.
+	///
+	/// The rectangle is scaled with the filter if the sizes differ, resolves multisampled surfaces.
+	fn blit(&mut self, src: Surface, src_rect: &cvmath::Rect<i32>, dst: Surface, dst_rect: &cvmath::Rect<i32>, filter: TextureFilter) -> Result<(), GfxError>;
 	/// Release the resources of a surface.
 	fn surface_delete(&mut self, id: Surface, free_handle: bool) -> Result<(), GfxError>;
+
+	/// Insert a fence which is signaled when the GPU completes all commands submitted so far.
+	fn frame_fence(&mut self) -> Result<Fence, GfxError>;
+	/// Wait for a fence to be signaled, returns false if the timeout expired.
+	///
+	/// A timeout of zero polls the fence without blocking.
+	fn fence_wait(&mut self, id: Fence, timeout_ns: u64) -> Result<bool, GfxError>;
+	/// Release a fence.
+	fn fence_delete(&mut self, id: Fence) -> Result<(), GfxError>;
 }
 
 /// Graphics interface.
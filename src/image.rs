@@ -0,0 +1,241 @@
+/*!
+Utility to load compressed DDS and KTX2 files to texture.
+
+The compressed data goes straight to the GPU, the mip levels are taken from the file.
+*/
+
+use std::{fmt, fs, io};
+
+#[derive(Debug)]
+pub enum LoadError {
+	Gfx(crate::GfxError),
+	Io(io::Error),
+	/// The file is not a valid DDS or KTX2 file.
+	InvalidFile,
+	/// The file is not a 2D texture in a supported compressed format.
+	UnsupportedFormat,
+}
+
+impl From<crate::GfxError> for LoadError {
+	#[inline]
+	fn from(e: crate::GfxError) -> Self {
+		LoadError::Gfx(e)
+	}
+}
+impl From<io::Error> for LoadError {
+	#[inline]
+	fn from(e: io::Error) -> Self {
+		LoadError::Io(e)
+	}
+}
+
+impl fmt::Display for LoadError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			LoadError::Gfx(e) => write!(f, "{:?}", e),
+			LoadError::Io(e) => e.fmt(f),
+			LoadError::InvalidFile => f.write_str("invalid image file"),
+			LoadError::UnsupportedFormat => f.write_str("unsupported image format"),
+		}
+	}
+}
+
+impl std::error::Error for LoadError {}
+
+/// Compressed image with its mip levels.
+#[derive(Clone, Debug)]
+pub struct CompressedImage<'a> {
+	pub format: crate::TextureFormat,
+	pub width: i32,
+	pub height: i32,
+	/// Data of the mip levels, starting with the base level.
+	pub levels: Vec<&'a [u8]>,
+}
+
+const DDS_MAGIC: [u8; 4] = *b"DDS ";
+const KTX2_MAGIC: [u8; 12] = [0xAB, b'K', b'T', b'X', b' ', b'2', b'0', 0xBB, b'\r', b'\n', 0x1A, b'\n'];
+
+/// Parses a DDS or KTX2 file, detected by its magic.
+pub fn parse(bytes: &[u8]) -> Result<CompressedImage<'_>, LoadError> {
+	if bytes.starts_with(&DDS_MAGIC) {
+		parse_dds(bytes)
+	}
+	else if bytes.starts_with(&KTX2_MAGIC) {
+		parse_ktx2(bytes)
+	}
+	else {
+		Err(LoadError::InvalidFile)
+	}
+}
+
+/// Parses a DDS file.
+///
+/// Supports 2D textures in the `DXT1`, `DXT3` and `DXT5` formats, also with the `DX10` header extension.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut file = vec![0u8; 128];
+/// file[0..4].copy_from_slice(b"DDS ");
+/// file[4..8].copy_from_slice(&124u32.to_le_bytes());
+/// file[12..16].copy_from_slice(&4u32.to_le_bytes()); // height
+/// file[16..20].copy_from_slice(&8u32.to_le_bytes()); // width
+/// file[80..84].copy_from_slice(&4u32.to_le_bytes()); // DDPF_FOURCC
+/// file[84..88].copy_from_slice(b"DXT1");
+/// file.extend_from_slice(&[0xff; 16]);
+///
+/// let image = shade::image::parse_dds(&file).unwrap();
+/// assert_eq!(image.format, shade::TextureFormat::BC1);
+/// assert_eq!((image.width, image.height), (8, 4));
+/// assert_eq!(image.levels, [&[0xff; 16][..]]);
+/// ```
+pub fn parse_dds(bytes: &[u8]) -> Result<CompressedImage<'_>, LoadError> {
+	if bytes.len() < 128 || bytes[0..4] != DDS_MAGIC || read_u32(bytes, 4) != 124 {
+		return Err(LoadError::InvalidFile);
+	}
+	const DDPF_FOURCC: u32 = 0x4;
+	const DDSCAPS2_CUBEMAP: u32 = 0x200;
+	const DDSCAPS2_VOLUME: u32 = 0x200000;
+	let height = read_u32(bytes, 12);
+	let width = read_u32(bytes, 16);
+	let mip_count = read_u32(bytes, 28);
+	let pf_flags = read_u32(bytes, 80);
+	let caps2 = read_u32(bytes, 112);
+	if pf_flags & DDPF_FOURCC == 0 || caps2 & (DDSCAPS2_CUBEMAP | DDSCAPS2_VOLUME) != 0 {
+		return Err(LoadError::UnsupportedFormat);
+	}
+
+	let (format, offset) = match &bytes[84..88] {
+		b"DXT1" => (crate::TextureFormat::BC1, 128),
+		b"DXT3" => (crate::TextureFormat::BC2, 128),
+		b"DXT5" => (crate::TextureFormat::BC3, 128),
+		b"DX10" => {
+			if bytes.len() < 148 {
+				return Err(LoadError::InvalidFile);
+			}
+			// Only single 2D textures
+			const DDS_DIMENSION_TEXTURE2D: u32 = 3;
+			if read_u32(bytes, 132) != DDS_DIMENSION_TEXTURE2D || read_u32(bytes, 140) > 1 {
+				return Err(LoadError::UnsupportedFormat);
+			}
+			let format = match read_u32(bytes, 128) {
+				70..=72 => crate::TextureFormat::BC1,
+				73..=75 => crate::TextureFormat::BC2,
+				76..=78 => crate::TextureFormat::BC3,
+				_ => return Err(LoadError::UnsupportedFormat),
+			};
+			(format, 148)
+		}
+		_ => return Err(LoadError::UnsupportedFormat),
+	};
+
+	let (width, height) = image_size(width, height)?;
+	let mut levels = Vec::new();
+	let mut offset = offset;
+	for level in 0..level_count(width, height, mip_count) {
+		let size = level_size(format, width, height, level);
+		let Some(data) = bytes.get(offset..offset + size) else { return Err(LoadError::InvalidFile) };
+		levels.push(data);
+		offset += size;
+	}
+	Ok(CompressedImage { format, width, height, levels })
+}
+
+/// Parses a KTX2 file.
+///
+/// Supports 2D textures in the BC1, BC2, BC3, ETC2 and ASTC 4x4 formats without supercompression.
+pub fn parse_ktx2(bytes: &[u8]) -> Result<CompressedImage<'_>, LoadError> {
+	if bytes.len() < 80 || bytes[0..12] != KTX2_MAGIC {
+		return Err(LoadError::InvalidFile);
+	}
+	let vk_format = read_u32(bytes, 12);
+	let width = read_u32(bytes, 20);
+	let height = read_u32(bytes, 24);
+	let depth = read_u32(bytes, 28);
+	let layer_count = read_u32(bytes, 32);
+	let face_count = read_u32(bytes, 36);
+	let num_levels = read_u32(bytes, 40);
+	let supercompression = read_u32(bytes, 44);
+	if depth != 0 || layer_count > 1 || face_count != 1 || supercompression != 0 {
+		return Err(LoadError::UnsupportedFormat);
+	}
+
+	// The sRGB variants are uploaded as their linear format
+	let format = match vk_format {
+		131..=134 => crate::TextureFormat::BC1,
+		135 | 136 => crate::TextureFormat::BC2,
+		137 | 138 => crate::TextureFormat::BC3,
+		147 | 148 => crate::TextureFormat::Etc2Rgb8,
+		151 | 152 => crate::TextureFormat::Etc2Rgba8,
+		157 | 158 => crate::TextureFormat::Astc4x4,
+		_ => return Err(LoadError::UnsupportedFormat),
+	};
+
+	let (width, height) = image_size(width, height)?;
+	let mut levels = Vec::new();
+	for level in 0..level_count(width, height, num_levels) {
+		let index = 80 + level as usize * 24;
+		let Some(entry) = bytes.get(index..index + 16) else { return Err(LoadError::InvalidFile) };
+		let offset = usize::try_from(read_u64(entry, 0)).map_err(|_| LoadError::InvalidFile)?;
+		let size = level_size(format, width, height, level);
+		let Some(data) = bytes.get(offset..).and_then(|data| data.get(..size)) else { return Err(LoadError::InvalidFile) };
+		levels.push(data);
+	}
+	Ok(CompressedImage { format, width, height, levels })
+}
+
+/// Creates a texture and uploads the compressed image.
+///
+/// The top mip levels are dropped according to the [`texture_quality`](crate::texture_quality), as long as a level remains.
+/// The [`mip_levels`](crate::TextureProps::mip_levels) of the properties are ignored, the texture has the levels of the image.
+pub fn upload(g: &mut crate::Graphics, name: Option<&str>, image: &CompressedImage, props: &crate::TextureProps) -> Result<crate::Texture2D, LoadError> {
+	let bias = usize::min(crate::texture_quality().mip_bias() as usize, image.levels.len().saturating_sub(1));
+	let levels = &image.levels[bias..];
+
+	let mut info = crate::Texture2DInfo {
+		format: image.format,
+		width: i32::max(1, image.width >> bias),
+		height: i32::max(1, image.height >> bias),
+		..Default::default()
+	};
+	info.set_props(props);
+	info.mip_levels = crate::MipLevels::Max(levels.len() as u8);
+
+	let tx = g.texture2d_create(name, &info)?;
+	for (level, data) in levels.iter().enumerate() {
+		g.texture2d_set_compressed_data(tx, level as u32, data)?;
+	}
+	Ok(tx)
+}
+
+/// Loads a DDS or KTX2 file to texture.
+pub fn load(g: &mut crate::Graphics, name: Option<&str>, path: &str, props: &crate::TextureProps) -> Result<crate::Texture2D, LoadError> {
+	let bytes = fs::read(path)?;
+	let image = parse(&bytes)?;
+	upload(g, name, &image, props)
+}
+
+fn image_size(width: u32, height: u32) -> Result<(i32, i32), LoadError> {
+	match (i32::try_from(width), i32::try_from(height)) {
+		(Ok(width), Ok(height)) if width > 0 && height > 0 => Ok((width, height)),
+		_ => Err(LoadError::InvalidFile),
+	}
+}
+
+/// Returns the number of levels in the file, ignoring levels past the full mip chain.
+fn level_count(width: i32, height: i32, count: u32) -> u32 {
+	u32::clamp(count, 1, crate::MipLevels::Full.count(width, height))
+}
+
+fn level_size(format: crate::TextureFormat, width: i32, height: i32, level: u32) -> usize {
+	format.data_size(i32::max(1, width >> level), i32::max(1, height >> level))
+}
+
+#[inline]
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+	u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+#[inline]
+fn read_u64(bytes: &[u8], offset: usize) -> u64 {
+	u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap())
+}
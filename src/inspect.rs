@@ -0,0 +1,159 @@
+use std::fmt;
+use super::*;
+
+/// Scalar value decoded from captured data.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum CaptureValue {
+	F64(f64),
+	F32(f32),
+	I32(i32),
+	U32(u32),
+}
+
+impl fmt::Display for CaptureValue {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			CaptureValue::F64(v) => write!(f, "{}", v),
+			CaptureValue::F32(v) => write!(f, "{}", v),
+			CaptureValue::I32(v) => write!(f, "{}", v),
+			CaptureValue::U32(v) => write!(f, "{}", v),
+		}
+	}
+}
+
+/// Captured uniform value.
+#[derive(Clone, Debug)]
+pub struct UniformCapture {
+	pub name: &'static str,
+	pub ty: UniformType,
+	pub len: u16,
+	/// The shader has an active uniform with this name.
+	pub active: bool,
+	/// Raw bytes of the uniform.
+	pub data: Vec<u8>,
+}
+
+impl UniformCapture {
+	/// Decodes the uniform data.
+	pub fn values(&self) -> Vec<CaptureValue> {
+		let (count, size, read): ValueShape = match self.ty {
+			UniformType::D1 => (1, 8, read_f64),
+			UniformType::D2 => (2, 8, read_f64),
+			UniformType::D3 => (3, 8, read_f64),
+			UniformType::D4 => (4, 8, read_f64),
+			UniformType::F1 => (1, 4, read_f32),
+			UniformType::F2 => (2, 4, read_f32),
+			UniformType::F3 => (3, 4, read_f32),
+			UniformType::F4 => (4, 4, read_f32),
+			UniformType::I1 | UniformType::B1 => (1, 4, read_i32),
+			UniformType::I2 | UniformType::B2 => (2, 4, read_i32),
+			UniformType::I3 | UniformType::B3 => (3, 4, read_i32),
+			UniformType::I4 | UniformType::B4 => (4, 4, read_i32),
+			UniformType::U1 | UniformType::Sampler2D(_) | UniformType::SamplerCube(_) | UniformType::Sampler3D(_) | UniformType::Sampler2DArray(_) => (1, 4, read_u32),
+			UniformType::U2 => (2, 4, read_u32),
+			UniformType::U3 => (3, 4, read_u32),
+			UniformType::U4 => (4, 4, read_u32),
+			UniformType::Mat2x2 { .. } => (4, 4, read_f32),
+			UniformType::Mat2x3 { .. } | UniformType::Mat3x2 { .. } => (6, 4, read_f32),
+			UniformType::Mat2x4 { .. } | UniformType::Mat4x2 { .. } => (8, 4, read_f32),
+			UniformType::Mat3x3 { .. } => (9, 4, read_f32),
+			UniformType::Mat3x4 { .. } | UniformType::Mat4x3 { .. } => (12, 4, read_f32),
+			UniformType::Mat4x4 { .. } => (16, 4, read_f32),
+		};
+		self.data.chunks_exact(size).take(count * self.len as usize).map(read).collect()
+	}
+}
+
+/// Captured draw call.
+///
+/// Request a capture with [debug_capture](IGraphics::debug_capture) and retrieve it after the draw call was made.
+#[derive(Clone, Debug)]
+pub struct DrawCapture {
+	/// Index of the draw call since the beginning of the frame.
+	pub draw_index: u32,
+	pub shader: Shader,
+	pub prim_type: PrimType,
+	/// Uniforms resolved for the draw call.
+	pub uniforms: Vec<UniformCapture>,
+	pub vertex_layout: &'static VertexLayout,
+	/// Index of the first captured vertex.
+	pub vertex_start: u32,
+	/// Raw bytes of the captured vertices.
+	pub vertex_data: Vec<u8>,
+}
+
+impl DrawCapture {
+	/// Returns the number of captured vertices.
+	#[inline]
+	pub fn vertex_count(&self) -> usize {
+		self.vertex_data.len() / cmp::max(1, self.vertex_layout.size as usize)
+	}
+
+	/// Decodes the attributes of a captured vertex.
+	pub fn vertex(&self, index: usize) -> Vec<Vec<CaptureValue>> {
+		let size = self.vertex_layout.size as usize;
+		let Some(vertex) = self.vertex_data.get(index * size..index * size + size) else { return Vec::new() };
+		self.vertex_layout.attributes.iter().map(|attr| {
+			let (elsize, read): (usize, fn(&[u8]) -> CaptureValue) = match attr.format {
+				VertexAttributeFormat::F32 => (4, read_f32),
+				VertexAttributeFormat::F64 => (8, read_f64),
+				VertexAttributeFormat::I32 => (4, read_i32),
+				VertexAttributeFormat::U32 => (4, read_u32),
+				VertexAttributeFormat::I16 => (2, |b| CaptureValue::I32(i16::from_ne_bytes([b[0], b[1]]) as i32)),
+				VertexAttributeFormat::U16 => (2, |b| CaptureValue::U32(u16::from_ne_bytes([b[0], b[1]]) as u32)),
+				VertexAttributeFormat::I8 => (1, |b| CaptureValue::I32(b[0] as i8 as i32)),
+				VertexAttributeFormat::U8 => (1, |b| CaptureValue::U32(b[0] as u32)),
+				VertexAttributeFormat::I16Norm => (2, |b| CaptureValue::F32(f32::max(-1.0, i16::from_ne_bytes([b[0], b[1]]) as f32 / 32767.0))),
+				VertexAttributeFormat::U16Norm => (2, |b| CaptureValue::F32(u16::from_ne_bytes([b[0], b[1]]) as f32 / 65535.0)),
+				VertexAttributeFormat::I8Norm => (1, |b| CaptureValue::F32(f32::max(-1.0, b[0] as i8 as f32 / 127.0))),
+				VertexAttributeFormat::U8Norm => (1, |b| CaptureValue::F32(b[0] as f32 / 255.0)),
+			};
+			let start = attr.offset as usize;
+			let end = cmp::min(vertex.len(), start + elsize * attr.len as usize);
+			vertex.get(start..end).unwrap_or(&[]).chunks_exact(elsize).map(read).collect()
+		}).collect()
+	}
+}
+
+impl fmt::Display for DrawCapture {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		writeln!(f, "Draw call {} ({:?}, {:?}):", self.draw_index, self.shader, self.prim_type)?;
+		for uniform in &self.uniforms {
+			let inactive = if uniform.active { "" } else { " (inactive)" };
+			write!(f, "  uniform {}: {:?}{} =", uniform.name, uniform.ty, inactive)?;
+			for value in uniform.values() {
+				write!(f, " {}", value)?;
+			}
+			writeln!(f)?;
+		}
+		for index in 0..self.vertex_count() {
+			write!(f, "  vertex {}:", self.vertex_start as usize + index)?;
+			for attr in self.vertex(index) {
+				write!(f, " (")?;
+				for (i, value) in attr.iter().enumerate() {
+					let sep = if i == 0 { "" } else { ", " };
+					write!(f, "{}{}", sep, value)?;
+				}
+				write!(f, ")")?;
+			}
+			writeln!(f)?;
+		}
+		Ok(())
+	}
+}
+
+/// Number of values, size of each value in bytes and the decoder of a value.
+type ValueShape = (usize, usize, fn(&[u8]) -> CaptureValue);
+
+fn read_f64(b: &[u8]) -> CaptureValue {
+	CaptureValue::F64(f64::from_ne_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]))
+}
+fn read_f32(b: &[u8]) -> CaptureValue {
+	CaptureValue::F32(f32::from_ne_bytes([b[0], b[1], b[2], b[3]]))
+}
+fn read_i32(b: &[u8]) -> CaptureValue {
+	CaptureValue::I32(i32::from_ne_bytes([b[0], b[1], b[2], b[3]]))
+}
+fn read_u32(b: &[u8]) -> CaptureValue {
+	CaptureValue::U32(u32::from_ne_bytes([b[0], b[1], b[2], b[3]]))
+}
@@ -1,10 +1,12 @@
-use std::{mem, ops};
+use std::{cmp, mem, ops};
 
 /// Re-export of compatible `cvmath` crate.
 pub use cvmath;
 
 #[macro_use]
 mod handle;
+#[macro_use]
+mod log;
 
 mod common;
 mod graphics;
@@ -14,18 +16,54 @@ mod texture;
 mod surface;
 mod uniform;
 mod shader;
+mod material;
 mod resources;
+mod arena;
+mod caps;
+mod envmap;
+mod cull;
+mod meshopt;
+mod meshfile;
+mod timestep;
+mod damp;
+mod conventions;
+mod fence;
+mod display;
+mod queue;
+mod cmdlist;
+mod inspect;
 
-pub use self::common::{PrimType, BlendMode, DepthTest, CullMode, BufferUsage};
+pub use self::common::{PrimType, BlendMode, DepthTest, StencilMode, CullMode, FillMode, BufferUsage};
 pub use self::graphics::{IGraphics, Graphics, GfxError, ClearArgs, DrawArgs, DrawIndexedArgs};
 pub use self::buffer::{VertexBuffer, IndexBuffer};
-pub use self::vertex::{TVertex, VertexAttributeFormat, VertexAttribute, VertexLayout};
-pub use self::texture::{Texture2D, TextureFormat, TextureWrap, TextureFilter, Texture2DInfo};
-pub use self::surface::{Surface, SurfaceFormat, SurfaceInfo};
+pub use self::vertex::{TVertex, VertexAttributeFormat, VertexAttribute, VertexLayout, split_f64};
+pub use self::texture::{Texture2D, TextureCube, Texture3D, Texture2DArray, TextureFormat, TextureWrap, TextureFilter, MipLevels, Texture2DInfo, TextureCubeInfo, Texture3DInfo, Texture2DArrayInfo, TextureProps, TextureCopy, TextureQuality, set_texture_quality, texture_quality};
+pub use self::surface::{Surface, SurfaceFormat, DepthFormat, SurfaceInfo};
 pub use self::uniform::{UniformBuffer, TUniform, UniformLayout, UniformAttribute, UniformMatOrder, UniformType};
 pub use self::shader::Shader;
+pub use self::material::{UniformValue, ParamBlock};
+pub use self::arena::{BufferArena, ArenaRange};
+pub use self::caps::{Caps, Profile};
+pub use self::envmap::{CubeFace, equirect_direction, equirect_coords, equirect_to_cube, cube_to_equirect};
+pub use self::cull::{box_visible, InstanceCuller};
+pub use self::meshopt::{cache_miss_ratio, optimize_vertex_cache, optimize_overdraw, optimize_vertex_fetch, simplify};
+pub use self::meshfile::{MeshFileError, MESH_FILE_MAGIC, MESH_FILE_HEADER_SIZE, AlignedBytes, layout_hash, write_vertices, read_vertices, vertices_from_bytes, vertices_from_raw_bytes};
+pub use self::timestep::{FixedTimestep, lerp};
+pub use self::damp::{SmoothDamp, Shake};
+pub use self::conventions::{Conventions, UpAxis, set_conventions, conventions, relative_to_eye};
+pub use self::fence::{Fence, FramePacer};
+pub use self::display::DisplayInfo;
+pub use self::queue::{RenderBucket, RenderQueue};
+pub use self::cmdlist::{CommandList, UniformRef};
+pub use self::inspect::{CaptureValue, UniformCapture, DrawCapture};
+pub use self::log::{LogLevel, LogCategory, LogRecord, ILogger, StderrLogger, set_logger, set_log_level, set_log_rate_limit};
 
 pub mod d2;
+pub mod fx;
+pub mod perf;
+pub mod tasks;
+pub mod streaming;
+pub mod image;
 
 #[cfg(feature = "gl")]
 pub mod gl;
@@ -35,3 +73,9 @@ pub mod png;
 
 #[cfg(feature = "msdfgen")]
 pub mod msdfgen;
+
+#[cfg(feature = "soft")]
+pub mod soft;
+
+#[cfg(test)]
+mod tests;
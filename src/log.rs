@@ -0,0 +1,122 @@
+use std::fmt;
+use std::sync::RwLock;
+use std::sync::atomic::{AtomicU8, AtomicU32, Ordering};
+
+/// Log level.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[repr(u8)]
+pub enum LogLevel {
+	/// Errors which cause an operation to fail.
+	Error = 1,
+	/// Problems which were recovered from.
+	Warn = 2,
+	/// Informational messages.
+	Info = 3,
+	/// Verbose diagnostics.
+	Debug = 4,
+}
+
+/// Log category.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum LogCategory {
+	/// Backend initialization and capabilities.
+	General,
+	/// Shader compilation and uniforms.
+	Shader,
+	/// Vertex, index and uniform buffers.
+	Buffer,
+}
+
+/// Log record.
+pub struct LogRecord<'a> {
+	pub level: LogLevel,
+	pub category: LogCategory,
+	pub args: fmt::Arguments<'a>,
+}
+
+/// Logger interface.
+///
+/// Install a logger with [`set_logger`] to route graphics diagnostics.
+pub trait ILogger: Send + Sync {
+	/// Returns true if records with this level and category should be logged.
+	#[inline]
+	fn enabled(&self, _level: LogLevel, _category: LogCategory) -> bool {
+		true
+	}
+	/// Logs the record.
+	fn log(&self, record: &LogRecord);
+}
+
+/// Logger which prints to stderr.
+pub struct StderrLogger;
+
+impl ILogger for StderrLogger {
+	fn log(&self, record: &LogRecord) {
+		eprintln!("shade [{:?}] {:?}: {}", record.level, record.category, record.args);
+	}
+}
+
+static LOGGER: RwLock<&'static dyn ILogger> = RwLock::new(&StderrLogger);
+static MAX_LEVEL: AtomicU8 = AtomicU8::new(LogLevel::Warn as u8);
+static RATE_LIMIT: AtomicU32 = AtomicU32::new(u32::MAX);
+static FRAME_COUNT: AtomicU32 = AtomicU32::new(0);
+
+/// Installs the logger.
+///
+/// The default logger prints to stderr.
+pub fn set_logger(logger: &'static dyn ILogger) {
+	let mut guard = match LOGGER.write() {
+		Ok(guard) => guard,
+		Err(err) => err.into_inner(),
+	};
+	*guard = logger;
+}
+
+/// Sets the maximum level to log, `None` disables logging.
+///
+/// The default level is [`LogLevel::Warn`].
+pub fn set_log_level(level: Option<LogLevel>) {
+	MAX_LEVEL.store(level.map(|level| level as u8).unwrap_or(0), Ordering::Relaxed);
+}
+
+/// Sets the maximum number of records logged per frame.
+///
+/// Records over the limit are dropped until the next call to [`IGraphics::begin`](crate::IGraphics::begin).
+pub fn set_log_rate_limit(max_per_frame: u32) {
+	RATE_LIMIT.store(max_per_frame, Ordering::Relaxed);
+}
+
+/// Resets the per frame rate limit.
+#[cfg(any(feature = "gl", feature = "soft"))]
+pub(crate) fn frame_reset() {
+	FRAME_COUNT.store(0, Ordering::Relaxed);
+}
+
+#[inline]
+pub(crate) fn log_enabled(level: LogLevel) -> bool {
+	level as u8 <= MAX_LEVEL.load(Ordering::Relaxed)
+}
+
+#[inline(never)]
+pub(crate) fn log(level: LogLevel, category: LogCategory, args: fmt::Arguments) {
+	let logger = match LOGGER.read() {
+		Ok(guard) => *guard,
+		Err(err) => *err.into_inner(),
+	};
+	// Records filtered out by the logger do not count towards the rate limit
+	if !logger.enabled(level, category) {
+		return;
+	}
+	if FRAME_COUNT.fetch_add(1, Ordering::Relaxed) >= RATE_LIMIT.load(Ordering::Relaxed) {
+		return;
+	}
+	logger.log(&LogRecord { level, category, args });
+}
+
+macro_rules! gfx_log {
+	($level:ident, $category:ident, $($args:tt)*) => {
+		if crate::log::log_enabled(crate::LogLevel::$level) {
+			crate::log::log(crate::LogLevel::$level, crate::LogCategory::$category, format_args!($($args)*));
+		}
+	};
+}
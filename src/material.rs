@@ -0,0 +1,141 @@
+/// Default value of a uniform.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum UniformValue {
+	F1(f32), F2([f32; 2]), F3([f32; 3]), F4([f32; 4]),
+	I1(i32), I2([i32; 2]), I3([i32; 3]), I4([i32; 4]),
+	U1(u32), U2([u32; 2]), U3([u32; 3]), U4([u32; 4]),
+}
+
+impl UniformValue {
+	/// Parses a value for the GLSL type from whitespace or comma separated components.
+	///
+	/// A single component is repeated for vector types.
+	pub fn parse(ty: &str, text: &str) -> Option<UniformValue> {
+		let parts: Vec<&str> = text.split(|c: char| c == ',' || c.is_whitespace()).filter(|s| !s.is_empty()).collect();
+		fn comps<T: Copy + std::str::FromStr, const N: usize>(parts: &[&str]) -> Option<[T; N]> {
+			let values = parts.iter().map(|s| s.parse().ok()).collect::<Option<Vec<T>>>()?;
+			match values.len() {
+				1 => Some([values[0]; N]),
+				len if len == N => values.try_into().ok(),
+				_ => None,
+			}
+		}
+		let parse_bool = |parts: &[&str]| match parts {
+			["true"] | ["1"] => Some(1),
+			["false"] | ["0"] => Some(0),
+			_ => None,
+		};
+		let value = match ty {
+			"float" => UniformValue::F1(comps::<f32, 1>(&parts)?[0]),
+			"vec2" => UniformValue::F2(comps(&parts)?),
+			"vec3" => UniformValue::F3(comps(&parts)?),
+			"vec4" => UniformValue::F4(comps(&parts)?),
+			"int" => UniformValue::I1(comps::<i32, 1>(&parts)?[0]),
+			"ivec2" => UniformValue::I2(comps(&parts)?),
+			"ivec3" => UniformValue::I3(comps(&parts)?),
+			"ivec4" => UniformValue::I4(comps(&parts)?),
+			"uint" => UniformValue::U1(comps::<u32, 1>(&parts)?[0]),
+			"uvec2" => UniformValue::U2(comps(&parts)?),
+			"uvec3" => UniformValue::U3(comps(&parts)?),
+			"uvec4" => UniformValue::U4(comps(&parts)?),
+			"bool" => UniformValue::I1(parse_bool(&parts)?),
+			_ => return None,
+		};
+		Some(value)
+	}
+}
+
+/// Material parameter block.
+///
+/// Named set of default uniform values attached to a shader with [`shader_set_defaults`](crate::IGraphics::shader_set_defaults).
+/// The defaults are applied to every draw with the shader, uniforms set by the draw override them.
+/// This avoids black renders when a caller forgets to set a parameter.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ParamBlock {
+	params: Vec<(String, UniformValue)>,
+}
+
+impl ParamBlock {
+	/// Creates a new empty parameter block.
+	pub fn new() -> ParamBlock {
+		ParamBlock::default()
+	}
+
+	/// Parses the defaults annotated in the shader source.
+	///
+	/// Annotate a uniform declaration with a `// default:` comment.
+	///
+	/// ```rust
+	/// use shade::{ParamBlock, UniformValue};
+	///
+	/// let params = ParamBlock::parse("
+	///     uniform float u_heightScale; // default: 0.05
+	///     uniform vec3 u_tint; // default: 1.0, 0.5, 0.5
+	///     uniform sampler2D u_texture;
+	/// ");
+	/// assert_eq!(params.get("u_heightScale"), Some(UniformValue::F1(0.05)));
+	/// assert_eq!(params.get("u_tint"), Some(UniformValue::F3([1.0, 0.5, 0.5])));
+	/// assert_eq!(params.len(), 2);
+	/// ```
+	pub fn parse(source: &str) -> ParamBlock {
+		let mut params = ParamBlock::new();
+		for line in source.lines() {
+			let Some((decl, comment)) = line.split_once("//") else { continue };
+			let Some(value) = comment.trim().strip_prefix("default:") else { continue };
+			let mut tokens = decl.split_whitespace().filter(|&t| t != "uniform" && t != "highp" && t != "mediump" && t != "lowp");
+			let (Some(ty), Some(name)) = (tokens.next(), tokens.next()) else { continue };
+			let name = name.trim_end_matches(';');
+			if let Some(value) = UniformValue::parse(ty, value) {
+				params.set(name, value);
+			}
+			else {
+				gfx_log!(Warn, Shader, "Invalid default for uniform {}: {}", name, value.trim());
+			}
+		}
+		params
+	}
+
+	/// Sets the default value of a uniform.
+	pub fn set(&mut self, name: &str, value: UniformValue) {
+		if let Some(param) = self.params.iter_mut().find(|(n, _)| n == name) {
+			param.1 = value;
+		}
+		else {
+			self.params.push((name.to_string(), value));
+		}
+	}
+
+	/// Gets the default value of a uniform.
+	pub fn get(&self, name: &str) -> Option<UniformValue> {
+		self.params.iter().find(|(n, _)| n == name).map(|&(_, value)| value)
+	}
+
+	/// Removes the default value of a uniform.
+	pub fn remove(&mut self, name: &str) {
+		self.params.retain(|(n, _)| n != name);
+	}
+
+	/// Sets the values of another parameter block, overriding existing values.
+	pub fn merge(&mut self, other: &ParamBlock) {
+		for (name, value) in other.iter() {
+			self.set(name, value);
+		}
+	}
+
+	/// Returns the number of parameters.
+	#[inline]
+	pub fn len(&self) -> usize {
+		self.params.len()
+	}
+
+	/// Returns true if there are no parameters.
+	#[inline]
+	pub fn is_empty(&self) -> bool {
+		self.params.is_empty()
+	}
+
+	/// Iterates over the names and values of the parameters.
+	pub fn iter(&self) -> impl Iterator<Item = (&str, UniformValue)> {
+		self.params.iter().map(|(name, value)| (name.as_str(), *value))
+	}
+}
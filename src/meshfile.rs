@@ -0,0 +1,201 @@
+use std::{fmt, io, mem, slice};
+use super::*;
+
+/// Magic of a mesh file.
+pub const MESH_FILE_MAGIC: [u8; 4] = *b"SHDV";
+
+/// Size of the mesh file header in bytes.
+///
+/// A multiple of the 16 byte alignment of [`include_aligned_bytes!`](crate::include_aligned_bytes),
+/// the vertex data stays aligned for vertex types up to 16 byte alignment.
+pub const MESH_FILE_HEADER_SIZE: usize = 32;
+
+/// Number of bytes read at a time, bounds the allocation ahead of the data actually read.
+const READ_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Mesh file error.
+#[derive(Debug)]
+pub enum MeshFileError {
+	Io(io::Error),
+	/// The file does not start with the mesh file magic.
+	InvalidMagic,
+	/// The file was written for a different vertex type.
+	LayoutMismatch,
+	/// The data is not a whole number of vertices.
+	InvalidSize,
+	/// The data is not aligned for the vertex type.
+	Misaligned,
+}
+
+impl From<io::Error> for MeshFileError {
+	#[inline]
+	fn from(e: io::Error) -> Self {
+		MeshFileError::Io(e)
+	}
+}
+
+impl fmt::Display for MeshFileError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			MeshFileError::Io(e) => e.fmt(f),
+			MeshFileError::InvalidMagic => f.write_str("invalid mesh file magic"),
+			MeshFileError::LayoutMismatch => f.write_str("mesh file vertex layout mismatch"),
+			MeshFileError::InvalidSize => f.write_str("mesh file size is not a whole number of vertices"),
+			MeshFileError::Misaligned => f.write_str("mesh file data is misaligned"),
+		}
+	}
+}
+
+impl std::error::Error for MeshFileError {}
+
+/// Returns a stable hash of the vertex layout.
+///
+/// The hash does not depend on the compiler or the platform, it can be stored in files.
+pub fn layout_hash(layout: &VertexLayout) -> u64 {
+	// FNV-1a
+	let mut hash = 0xcbf29ce484222325u64;
+	let mut write = |bytes: &[u8]| {
+		for &byte in bytes {
+			hash = (hash ^ byte as u64).wrapping_mul(0x100000001b3);
+		}
+	};
+	write(&layout.size.to_le_bytes());
+	write(&layout.alignment.to_le_bytes());
+	write(&layout.instance_divisor.to_le_bytes());
+	for attr in layout.attributes {
+		write(&[attr.format as u8]);
+		write(&attr.len.to_le_bytes());
+		write(&attr.offset.to_le_bytes());
+	}
+	hash
+}
+
+/// Writes the vertices as a mesh file.
+///
+/// A mesh file is a header followed by the raw vertex data in the memory layout of the vertex type.
+/// The header stores the magic `SHDV`, the vertex size, the vertex count and the [`layout_hash`]
+/// so files written for a different vertex type are rejected instead of being reinterpreted.
+/// The header fields are little-endian, the header is padded to 32 bytes with zeroes so the vertex data
+/// is 16 byte aligned when the file is loaded at a 16 byte aligned address.
+pub fn write_vertices<V: TVertex, W: io::Write>(writer: &mut W, vertices: &[V]) -> io::Result<()> {
+	writer.write_all(&header::<V>(vertices.len()))?;
+	writer.write_all(dataview::bytes(vertices))
+}
+
+/// Reads the vertices of a mesh file.
+///
+/// The vertices are read straight into their final allocation in bounded chunks,
+/// a corrupt vertex count fails at the end of the data instead of allocating the whole count up front.
+///
+/// # Examples
+///
+/// ```rust
+/// #[derive(Copy, Clone, Debug, Default, PartialEq, dataview::Pod)]
+/// #[repr(C)]
+/// struct Vertex {
+///     position: [f32; 3],
+/// }
+///
+/// unsafe impl shade::TVertex for Vertex {
+///     const VERTEX_LAYOUT: &'static shade::VertexLayout = &shade::VertexLayout {
+///         size: std::mem::size_of::<Vertex>() as u16,
+///         alignment: std::mem::align_of::<Vertex>() as u16,
+///         instance_divisor: 0,
+///         attributes: &[shade::VertexAttribute { format: shade::VertexAttributeFormat::F32, len: 3, offset: 0 }],
+///     };
+/// }
+///
+/// let vertices = [Vertex { position: [1.0, 2.0, 3.0] }, Vertex { position: [4.0, 5.0, 6.0] }];
+/// let mut file = Vec::new();
+/// shade::write_vertices(&mut file, &vertices).unwrap();
+///
+/// let loaded: Vec<Vertex> = shade::read_vertices(&mut &file[..]).unwrap();
+/// assert_eq!(loaded, vertices);
+/// ```
+pub fn read_vertices<V: TVertex, R: io::Read>(reader: &mut R) -> Result<Vec<V>, MeshFileError> {
+	let mut bytes = [0u8; MESH_FILE_HEADER_SIZE];
+	reader.read_exact(&mut bytes)?;
+	let count = parse_header::<V>(&bytes)?;
+	let chunk = cmp::max(1, READ_CHUNK_SIZE / cmp::max(1, mem::size_of::<V>()));
+	let mut vertices = Vec::new();
+	while vertices.len() < count {
+		let start = vertices.len();
+		vertices.resize(start + cmp::min(chunk, count - start), V::default());
+		reader.read_exact(dataview::bytes_mut(&mut vertices[start..]))?;
+	}
+	Ok(vertices)
+}
+
+/// Borrows the vertices of a mesh file without copying.
+///
+/// The bytes must be aligned for the vertex type, see [`include_aligned_bytes!`](crate::include_aligned_bytes).
+/// A memory mapped file is page aligned and can be passed directly, this crate does not map files itself.
+pub fn vertices_from_bytes<V: TVertex>(bytes: &[u8]) -> Result<&[V], MeshFileError> {
+	let Some(header) = bytes.get(..MESH_FILE_HEADER_SIZE) else { return Err(MeshFileError::InvalidSize) };
+	let count = parse_header::<V>(header)?;
+	let data = &bytes[MESH_FILE_HEADER_SIZE..];
+	if count.checked_mul(mem::size_of::<V>()) != Some(data.len()) {
+		return Err(MeshFileError::InvalidSize);
+	}
+	vertices_from_raw_bytes(data)
+}
+
+/// Borrows raw vertex data without a header.
+///
+/// Validates the size and alignment of the bytes for the vertex type.
+pub fn vertices_from_raw_bytes<V: TVertex>(bytes: &[u8]) -> Result<&[V], MeshFileError> {
+	let size = mem::size_of::<V>();
+	if size == 0 || !bytes.len().is_multiple_of(size) {
+		return Err(MeshFileError::InvalidSize);
+	}
+	if bytes.is_empty() {
+		return Ok(&[]);
+	}
+	if !(bytes.as_ptr() as usize).is_multiple_of(mem::align_of::<V>()) {
+		return Err(MeshFileError::Misaligned);
+	}
+	// Safety: the vertex type is Pod, the size and alignment are checked above
+	Ok(unsafe { slice::from_raw_parts(bytes.as_ptr() as *const V, bytes.len() / size) })
+}
+
+fn header<V: TVertex>(count: usize) -> [u8; MESH_FILE_HEADER_SIZE] {
+	let mut bytes = [0u8; MESH_FILE_HEADER_SIZE];
+	bytes[0..4].copy_from_slice(&MESH_FILE_MAGIC);
+	bytes[4..8].copy_from_slice(&(mem::size_of::<V>() as u32).to_le_bytes());
+	bytes[8..16].copy_from_slice(&(count as u64).to_le_bytes());
+	bytes[16..24].copy_from_slice(&layout_hash(V::VERTEX_LAYOUT).to_le_bytes());
+	// The remaining bytes are reserved padding
+	bytes
+}
+
+/// Validates the header and returns the vertex count.
+fn parse_header<V: TVertex>(bytes: &[u8]) -> Result<usize, MeshFileError> {
+	if bytes[0..4] != MESH_FILE_MAGIC {
+		return Err(MeshFileError::InvalidMagic);
+	}
+	let size = u32::from_le_bytes(bytes[4..8].try_into().unwrap());
+	let count = u64::from_le_bytes(bytes[8..16].try_into().unwrap());
+	let hash = u64::from_le_bytes(bytes[16..24].try_into().unwrap());
+	if size as usize != mem::size_of::<V>() || hash != layout_hash(V::VERTEX_LAYOUT) {
+		return Err(MeshFileError::LayoutMismatch);
+	}
+	usize::try_from(count).map_err(|_| MeshFileError::InvalidSize)
+}
+
+/// Bytes aligned to 16 bytes.
+///
+/// Used by [`include_aligned_bytes!`](crate::include_aligned_bytes).
+#[doc(hidden)]
+#[repr(C, align(16))]
+pub struct AlignedBytes<T: ?Sized>(pub T);
+
+/// Includes a file as a byte slice aligned to 16 bytes.
+///
+/// Like `include_bytes!` but the bytes can be borrowed as vertices with [`vertices_from_bytes`](crate::vertices_from_bytes).
+#[macro_export]
+macro_rules! include_aligned_bytes {
+	($path:expr) => {{
+		static BYTES: &$crate::AlignedBytes<[u8]> = &$crate::AlignedBytes(*include_bytes!($path));
+		&BYTES.0
+	}};
+}
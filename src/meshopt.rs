@@ -0,0 +1,430 @@
+use std::{cmp, mem};
+use cvmath::Vec3;
+
+/// Size of the simulated vertex cache.
+const CACHE_SIZE: usize = 32;
+
+/// Returns the average number of vertex cache misses per triangle.
+///
+/// Simulates a FIFO post-transform cache of the given size, lower is better.
+/// The ratio is between `0.5` for an ideal ordering and `3.0` when every vertex is transformed again.
+pub fn cache_miss_ratio(indices: &[u32], cache_size: usize) -> f32 {
+	let tri_count = indices.len() / 3;
+	if tri_count == 0 {
+		return 0.0;
+	}
+	let mut cache = std::collections::VecDeque::with_capacity(cache_size);
+	let mut misses = 0;
+	for &index in &indices[..tri_count * 3] {
+		if !cache.contains(&index) {
+			misses += 1;
+			if cache.len() >= cache_size {
+				cache.pop_front();
+			}
+			cache.push_back(index);
+		}
+	}
+	misses as f32 / tri_count as f32
+}
+
+/// Reorders the triangles to improve the post-transform vertex cache hit rate.
+///
+/// Implements Tom Forsyth's linear-speed vertex cache optimisation.
+/// Triangles are greedily emitted by scoring their vertices on their position in a simulated cache and the number of triangles still using them.
+/// The winding of the triangles is preserved, trailing indices not forming a whole triangle are left alone.
+///
+/// # Examples
+///
+/// ```rust
+/// // Grid of quads wider than the cache, in row-major order
+/// let mut indices = Vec::new();
+/// for y in 0..32 {
+///     for x in 0..32 {
+///         let i = y * 33 + x;
+///         indices.extend_from_slice(&[i, i + 1, i + 33, i + 1, i + 34, i + 33]);
+///     }
+/// }
+///
+/// let before = shade::cache_miss_ratio(&indices, 16);
+/// shade::optimize_vertex_cache(&mut indices);
+/// let after = shade::cache_miss_ratio(&indices, 16);
+/// assert!(after < before);
+/// ```
+pub fn optimize_vertex_cache(indices: &mut [u32]) {
+	let tri_count = indices.len() / 3;
+	if tri_count == 0 {
+		return;
+	}
+	let indices = &mut indices[..tri_count * 3];
+	let vertex_count = indices.iter().max().map_or(0, |&index| index as usize + 1);
+
+	// Triangles using each vertex, the live triangles are at the front of each vertex's range
+	let mut live = vec![0u32; vertex_count];
+	for &index in indices.iter() {
+		live[index as usize] += 1;
+	}
+	let mut total = 0;
+	let offsets: Vec<usize> = live.iter().map(|&count| { let offset = total; total += count as usize; offset }).collect();
+	let mut adjacency = vec![0u32; total];
+	let mut fill = offsets.clone();
+	for (tri, corners) in indices.chunks_exact(3).enumerate() {
+		for &index in corners {
+			adjacency[fill[index as usize]] = tri as u32;
+			fill[index as usize] += 1;
+		}
+	}
+
+	let mut vertex_scores: Vec<f32> = live.iter().map(|&count| vertex_score(None, count)).collect();
+	let mut tri_scores: Vec<f32> = indices.chunks_exact(3).map(|corners| corners.iter().map(|&index| vertex_scores[index as usize]).sum()).collect();
+	let mut emitted = vec![false; tri_count];
+	let mut output = Vec::with_capacity(indices.len());
+	let mut cache: Vec<u32> = Vec::with_capacity(CACHE_SIZE + 3);
+	let mut best = None;
+	let mut cursor = 0;
+
+	while output.len() < indices.len() {
+		// Fall back to the next triangle in the input when nothing in the cache is left
+		let tri = match best {
+			Some(tri) => tri,
+			None => {
+				while emitted[cursor] {
+					cursor += 1;
+				}
+				cursor
+			}
+		};
+		emitted[tri] = true;
+		let corners = [indices[tri * 3], indices[tri * 3 + 1], indices[tri * 3 + 2]];
+		output.extend_from_slice(&corners);
+
+		for &index in &corners {
+			let v = index as usize;
+			let list = &mut adjacency[offsets[v]..offsets[v] + live[v] as usize];
+			if let Some(pos) = list.iter().position(|&t| t as usize == tri) {
+				list.swap(pos, list.len() - 1);
+				live[v] -= 1;
+			}
+		}
+
+		// Move the vertices of the triangle to the front of the cache
+		let mut next = Vec::with_capacity(CACHE_SIZE + 3);
+		for &index in corners.iter().chain(cache.iter()) {
+			if !next.contains(&index) {
+				next.push(index);
+			}
+		}
+
+		// Rescore the vertices whose cache position changed, including the ones pushed out
+		for (pos, &index) in next.iter().enumerate() {
+			let v = index as usize;
+			let score = vertex_score(if pos < CACHE_SIZE { Some(pos) } else { None }, live[v]);
+			let delta = score - vertex_scores[v];
+			vertex_scores[v] = score;
+			for &t in &adjacency[offsets[v]..offsets[v] + live[v] as usize] {
+				tri_scores[t as usize] += delta;
+			}
+		}
+		next.truncate(CACHE_SIZE);
+
+		best = None;
+		let mut best_score = f32::NEG_INFINITY;
+		for &index in &next {
+			let v = index as usize;
+			for &t in &adjacency[offsets[v]..offsets[v] + live[v] as usize] {
+				if tri_scores[t as usize] > best_score {
+					best_score = tri_scores[t as usize];
+					best = Some(t as usize);
+				}
+			}
+		}
+		cache = next;
+	}
+
+	indices.copy_from_slice(&output);
+}
+
+fn vertex_score(cache_pos: Option<usize>, live: u32) -> f32 {
+	if live == 0 {
+		return -1.0;
+	}
+	let cache = match cache_pos {
+		None => 0.0,
+		// The vertices of the last triangle score lower to avoid strip-like orderings
+		Some(pos) if pos < 3 => 0.75,
+		Some(pos) => (1.0 - (pos - 3) as f32 / (CACHE_SIZE - 3) as f32).powf(1.5),
+	};
+	// Boost vertices with few triangles left to get rid of them
+	cache + 2.0 / (live as f32).sqrt()
+}
+
+/// Reorders clusters of triangles to reduce overdraw.
+///
+/// Call after [`optimize_vertex_cache`], the triangles are split into clusters where the vertex cache is flushed and the clusters facing away from the center of the mesh are drawn first.
+/// Those are the most likely to occlude the rest of the mesh, the cache efficiency within the clusters is kept.
+///
+/// The positions are indexed by the indices.
+pub fn optimize_overdraw(indices: &mut [u32], positions: &[Vec3<f32>]) {
+	let tri_count = indices.len() / 3;
+	if tri_count == 0 {
+		return;
+	}
+	let indices = &mut indices[..tri_count * 3];
+
+	// Start a new cluster on every triangle missing the cache entirely
+	let mut clusters = Vec::new();
+	let mut cache = std::collections::VecDeque::with_capacity(16);
+	for (tri, corners) in indices.chunks_exact(3).enumerate() {
+		let mut misses = 0;
+		for index in corners {
+			if !cache.contains(index) {
+				misses += 1;
+				if cache.len() >= 16 {
+					cache.pop_front();
+				}
+				cache.push_back(*index);
+			}
+		}
+		if misses == 3 || tri == 0 {
+			clusters.push(tri);
+		}
+	}
+
+	let mut center: Vec3<f32> = Vec3::ZERO;
+	for corners in indices.chunks_exact(3) {
+		center += positions[corners[0] as usize] + positions[corners[1] as usize] + positions[corners[2] as usize];
+	}
+	center /= (tri_count * 3) as f32;
+
+	// Sort the clusters by how much they face away from the center
+	let mut sorted: Vec<(f32, usize, usize)> = clusters.iter().enumerate().map(|(i, &start)| {
+		let end = clusters.get(i + 1).map_or(tri_count, |&end| end);
+		let mut centroid: Vec3<f32> = Vec3::ZERO;
+		let mut normal: Vec3<f32> = Vec3::ZERO;
+		let mut area = 0.0;
+		for corners in indices[start * 3..end * 3].chunks_exact(3) {
+			let (a, b, c) = (positions[corners[0] as usize], positions[corners[1] as usize], positions[corners[2] as usize]);
+			let n = (b - a).cross(c - a);
+			let len = n.len();
+			centroid += (a + b + c) * (len / 3.0);
+			normal += n;
+			area += len;
+		}
+		let score = if area > 0.0 { (centroid / area - center).dot(normal.normalize()) } else { 0.0 };
+		(score, start, end)
+	}).collect();
+	sorted.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+	let mut output = Vec::with_capacity(indices.len());
+	for &(_, start, end) in &sorted {
+		output.extend_from_slice(&indices[start * 3..end * 3]);
+	}
+	indices.copy_from_slice(&output);
+}
+
+/// Reorders the vertices in the order they are first used by the indices.
+///
+/// Improves the locality of the vertex fetches, call after the triangles are reordered.
+/// The indices are remapped and the reordered vertices are returned, vertices not used by any index are dropped.
+///
+/// # Examples
+///
+/// ```rust
+/// let vertices = ['a', 'b', 'c', 'd'];
+/// let mut indices = [3, 1, 2, 2, 1, 3];
+/// let vertices = shade::optimize_vertex_fetch(&mut indices, &vertices);
+/// assert_eq!(vertices, ['d', 'b', 'c']);
+/// assert_eq!(indices, [0, 1, 2, 2, 1, 0]);
+/// ```
+pub fn optimize_vertex_fetch<V: Copy>(indices: &mut [u32], vertices: &[V]) -> Vec<V> {
+	let mut remap = vec![u32::MAX; vertices.len()];
+	let mut output = Vec::with_capacity(cmp::min(vertices.len(), indices.len()));
+	for index in indices.iter_mut() {
+		let new = &mut remap[*index as usize];
+		if *new == u32::MAX {
+			*new = output.len() as u32;
+			output.push(vertices[*index as usize]);
+		}
+		*index = *new;
+	}
+	output
+}
+
+/// Simplifies the triangles to about the ratio of the triangle count.
+///
+/// Collapses edges in the order of their quadric error, the error of moving a vertex away from the planes of its triangles.
+/// A vertex is collapsed onto one of its neighbours, the simplified indices reference the original vertices and their attributes.
+/// Vertices on a border are locked, this includes the uv and normal seams where the vertices are split, so the attributes stay continuous.
+/// Collapses flipping the normal of a triangle are rejected.
+///
+/// Returns the simplified indices, the triangle count may stay above the target when no more edges can be collapsed.
+/// Trailing indices not forming a whole triangle are dropped.
+///
+/// # Examples
+///
+/// ```rust
+/// use shade::cvmath::Vec3;
+///
+/// // Flat grid of 8x8 quads
+/// let mut positions = Vec::new();
+/// for y in 0..9 {
+///     for x in 0..9 {
+///         positions.push(Vec3(x as f32, y as f32, 0.0));
+///     }
+/// }
+/// let mut indices = Vec::new();
+/// for y in 0..8 {
+///     for x in 0..8 {
+///         let i = y * 9 + x;
+///         indices.extend_from_slice(&[i, i + 1, i + 10, i, i + 10, i + 9]);
+///     }
+/// }
+///
+/// let simplified = shade::simplify(&indices, &positions, 0.25);
+/// assert!(simplified.len() <= indices.len() / 4);
+/// ```
+pub fn simplify(indices: &[u32], positions: &[Vec3<f32>], target_ratio: f32) -> Vec<u32> {
+	let tri_count = indices.len() / 3;
+	let mut tris: Vec<[u32; 3]> = indices.chunks_exact(3).map(|corners| [corners[0], corners[1], corners[2]]).collect();
+	let target = (tri_count as f32 * target_ratio.clamp(0.0, 1.0)).ceil() as usize;
+
+	let mut quadrics = vec![Quadric::default(); positions.len()];
+	let mut vertex_tris = vec![Vec::new(); positions.len()];
+	let mut edges = std::collections::HashMap::new();
+	for (tri, corners) in tris.iter().enumerate() {
+		let quadric = Quadric::plane(corners.map(|index| positions[index as usize]));
+		for i in 0..3 {
+			let (a, b) = (corners[i], corners[(i + 1) % 3]);
+			quadrics[a as usize].add(&quadric);
+			vertex_tris[a as usize].push(tri);
+			*edges.entry((cmp::min(a, b), cmp::max(a, b))).or_insert(0u32) += 1;
+		}
+	}
+
+	// Lock the vertices of the edges not shared by exactly two triangles
+	let mut locked = vec![false; positions.len()];
+	for (&(a, b), &count) in &edges {
+		if count != 2 {
+			locked[a as usize] = true;
+			locked[b as usize] = true;
+		}
+	}
+
+	let mut live = vec![true; tri_count];
+	let mut live_count = tri_count;
+	while live_count > target {
+		// Every pass collapses the cheapest edges not touching the vertices of an earlier collapse in the pass
+		let mut collapses = Vec::new();
+		for (tri, corners) in tris.iter().enumerate() {
+			if !live[tri] {
+				continue;
+			}
+			for i in 0..3 {
+				let (from, to) = (corners[i], corners[(i + 1) % 3]);
+				for (from, to) in [(from, to), (to, from)] {
+					if !locked[from as usize] {
+						let mut quadric = quadrics[from as usize];
+						quadric.add(&quadrics[to as usize]);
+						collapses.push((quadric.error(positions[to as usize]), from, to));
+					}
+				}
+			}
+		}
+		collapses.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+		let mut touched = vec![false; positions.len()];
+		let mut collapsed = false;
+		for &(_, from, to) in &collapses {
+			if live_count <= target {
+				break;
+			}
+			if touched[from as usize] || touched[to as usize] || flips(&tris, &live, &vertex_tris[from as usize], from, to, positions) {
+				continue;
+			}
+			touched[from as usize] = true;
+			touched[to as usize] = true;
+			collapsed = true;
+
+			let from_tris = mem::take(&mut vertex_tris[from as usize]);
+			for &tri in &from_tris {
+				if !live[tri] {
+					continue;
+				}
+				if tris[tri].contains(&to) {
+					live[tri] = false;
+					live_count -= 1;
+				}
+				else {
+					for index in &mut tris[tri] {
+						if *index == from {
+							*index = to;
+						}
+					}
+					vertex_tris[to as usize].push(tri);
+				}
+			}
+			let quadric = quadrics[from as usize];
+			quadrics[to as usize].add(&quadric);
+		}
+		if !collapsed {
+			break;
+		}
+	}
+
+	tris.iter().zip(&live).filter(|&(_, &live)| live).flat_map(|(corners, _)| *corners).collect()
+}
+
+/// Returns true if moving the vertex onto its neighbour flips or degenerates any of its remaining triangles.
+fn flips(tris: &[[u32; 3]], live: &[bool], vertex_tris: &[usize], from: u32, to: u32, positions: &[Vec3<f32>]) -> bool {
+	vertex_tris.iter().any(|&tri| {
+		let corners = tris[tri];
+		if !live[tri] || corners.contains(&to) {
+			return false;
+		}
+		let normal = |corners: [u32; 3]| {
+			let [a, b, c] = corners.map(|index| positions[index as usize]);
+			(b - a).cross(c - a)
+		};
+		let moved = corners.map(|index| if index == from { to } else { index });
+		normal(corners).dot(normal(moved)) <= 0.0
+	})
+}
+
+/// Symmetric 4x4 matrix measuring the squared distance to a set of planes.
+#[derive(Copy, Clone, Debug, Default)]
+struct Quadric([f64; 10]);
+
+impl Quadric {
+	/// Quadric of the plane of the triangle weighted by its area.
+	fn plane([a, b, c]: [Vec3<f32>; 3]) -> Quadric {
+		let n = (b - a).cross(c - a);
+		let len = n.len();
+		if len.is_nan() || len <= 0.0 {
+			return Quadric::default();
+		}
+		let (x, y, z) = ((n.x / len) as f64, (n.y / len) as f64, (n.z / len) as f64);
+		let d = -(x * a.x as f64 + y * a.y as f64 + z * a.z as f64);
+		let w = len as f64 * 0.5;
+		Quadric([
+			w * x * x, w * x * y, w * x * z, w * x * d,
+			w * y * y, w * y * z, w * y * d,
+			w * z * z, w * z * d,
+			w * d * d,
+		])
+	}
+
+	fn add(&mut self, rhs: &Quadric) {
+		for (a, b) in self.0.iter_mut().zip(&rhs.0) {
+			*a += b;
+		}
+	}
+
+	/// Returns the weighted sum of the squared distances of the point to the planes.
+	fn error(&self, p: Vec3<f32>) -> f64 {
+		let q = &self.0;
+		let (x, y, z) = (p.x as f64, p.y as f64, p.z as f64);
+		q[0] * x * x + 2.0 * q[1] * x * y + 2.0 * q[2] * x * z + 2.0 * q[3] * x
+			+ q[4] * y * y + 2.0 * q[5] * y * z + 2.0 * q[6] * y
+			+ q[7] * z * z + 2.0 * q[8] * z
+			+ q[9]
+	}
+}
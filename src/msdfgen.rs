@@ -104,6 +104,11 @@ use super::*;
 use cvmath::Vec2;
 
 impl d2::IFont for Font {
+	#[inline]
+	fn has_glyph(&self, chr: char) -> bool {
+		self.glyphs.contains_key(&(chr as u32))
+	}
+
 	fn write_span(&self, mut cv: Option<&mut d2::TextBuffer>, scribe: &mut d2::Scribe, cursor: &mut Vec2<f32>, text: &str) {
 		let font = self;
 		let mut chars = text.chars();
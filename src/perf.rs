@@ -0,0 +1,118 @@
+/*!
+Adaptive performance scaling.
+*/
+
+/// Performance governor.
+///
+/// Monitors the frame time and lowers the quality level when frames take too long, raises it again when there is headroom.
+/// Bind the level to quality knobs such as the render scale, particle counts or optional effects.
+///
+/// Hysteresis between the downgrade and upgrade thresholds and a cooldown after each change prevent oscillation.
+///
+/// # Example
+///
+/// ```rust
+/// use shade::perf::Governor;
+///
+/// let mut governor = Governor::new(1.0 / 60.0, 4);
+/// assert_eq!(governor.level(), 3);
+///
+/// // Frames consistently take too long
+/// let mut changed = None;
+/// for _ in 0..120 {
+///     changed = changed.or(governor.frame(1.0 / 60.0, Some(1.0 / 30.0)));
+/// }
+/// assert_eq!(changed, Some(2));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Governor {
+	/// Target frame time in seconds.
+	pub target: f32,
+	/// Lower the level when the average frame time exceeds the target by this factor.
+	pub downgrade: f32,
+	/// Raise the level when the average frame time is below the target by this factor.
+	pub upgrade: f32,
+	/// Minimum time in seconds between level changes.
+	pub cooldown: f32,
+	/// Smoothing factor of the average frame time, between `0.0` (frozen) and `1.0` (no smoothing).
+	pub smoothing: f32,
+	levels: u32,
+	level: u32,
+	average: f32,
+	elapsed: f32,
+}
+
+impl Governor {
+	/// Creates a new governor for the target frame time in seconds and the number of quality levels.
+	///
+	/// Starts at the highest level.
+	pub fn new(target: f32, levels: u32) -> Governor {
+		let levels = u32::max(1, levels);
+		Governor {
+			target,
+			downgrade: 1.1,
+			upgrade: 0.75,
+			cooldown: 1.0,
+			smoothing: 0.1,
+			levels,
+			level: levels - 1,
+			average: target,
+			elapsed: 0.0,
+		}
+	}
+
+	/// Returns the current level, from `0` (lowest quality) to `levels - 1` (highest quality).
+	#[inline]
+	pub fn level(&self) -> u32 {
+		self.level
+	}
+
+	/// Returns the current level as a fraction between `0.0` and `1.0`.
+	#[inline]
+	pub fn quality(&self) -> f32 {
+		if self.levels > 1 { self.level as f32 / (self.levels - 1) as f32 } else { 1.0 }
+	}
+
+	/// Forces a level and restarts the cooldown.
+	#[inline]
+	pub fn set_level(&mut self, level: u32) {
+		self.level = u32::min(level, self.levels - 1);
+		self.elapsed = 0.0;
+	}
+
+	/// Returns the smoothed frame time in seconds.
+	#[inline]
+	pub fn average(&self) -> f32 {
+		self.average
+	}
+
+	/// Records a frame.
+	///
+	/// The CPU time is the wall clock time of the frame, the GPU time is measured with timer queries when available.
+	/// Returns the new level if it was changed.
+	pub fn frame(&mut self, cpu_time: f32, gpu_time: Option<f32>) -> Option<u32> {
+		let frame_time = f32::max(cpu_time, gpu_time.unwrap_or(0.0));
+		if frame_time.is_nan() || frame_time < 0.0 {
+			return None;
+		}
+
+		self.average += (frame_time - self.average) * self.smoothing;
+		self.elapsed += cpu_time;
+		if self.elapsed < self.cooldown {
+			return None;
+		}
+
+		if self.average > self.target * self.downgrade && self.level > 0 {
+			self.set_level(self.level - 1);
+			// Assume the lower level meets the target until measured otherwise
+			self.average = self.target;
+			return Some(self.level);
+		}
+		if self.average < self.target * self.upgrade && self.level + 1 < self.levels {
+			self.set_level(self.level + 1);
+			self.average = self.target;
+			return Some(self.level);
+		}
+		None
+	}
+}
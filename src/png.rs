@@ -8,6 +8,8 @@ use std::fs;
 pub enum LoadError {
 	Gfx(crate::GfxError),
 	PNG(png::DecodingError),
+	/// The images of a texture array are not the same size.
+	SizeMismatch,
 }
 
 impl From<crate::GfxError> for LoadError {
@@ -23,12 +25,35 @@ impl From<png::DecodingError> for LoadError {
 	}
 }
 
+/// Transform applied to the decoded pixels before uploading.
+pub type Transform<'a> = Option<&'a mut dyn FnMut(&mut Vec<u8>, &mut png::OutputInfo)>;
+
 /// Texture properties.
 pub struct TextureProps {
 	pub filter_min: crate::TextureFilter,
 	pub filter_mag: crate::TextureFilter,
 	pub wrap_u: crate::TextureWrap,
 	pub wrap_v: crate::TextureWrap,
+	/// Mip levels generated after loading.
+	pub mip_levels: crate::MipLevels,
+	/// Maximum anisotropy of the filtering, `1.0` disables anisotropic filtering.
+	pub anisotropy: f32,
+	/// Premultiply the color by the alpha before the transform, draw with [`BlendMode::PremultipliedAlpha`](crate::BlendMode::PremultipliedAlpha).
+	pub premultiply: bool,
+}
+
+/// Premultiplies the color of 8-bit Rgba pixels by their alpha.
+///
+/// Filtering premultiplied pixels does not bleed the color of transparent pixels into their neighbours,
+/// which removes the dark fringes around cutout sprites.
+/// Premultiply before adding a [`gutter`] so the gutter pixels are premultiplied as well.
+pub fn premultiply(image: &mut [u8]) {
+	for pixel in image.chunks_exact_mut(4) {
+		let alpha = pixel[3] as u32;
+		for channel in &mut pixel[..3] {
+			*channel = ((*channel as u32 * alpha + 127) / 255) as u8;
+		}
+	}
 }
 
 /// With a texture sprite sheet tightly packed, add a 1px gutter around each sprite.
@@ -78,8 +103,68 @@ pub fn load(
 	name: Option<&str>,
 	path: &str,
 	props: &TextureProps,
-	transform: Option<&mut dyn FnMut(&mut Vec<u8>, &mut png::OutputInfo)>,
+	transform: Transform,
 ) -> Result<crate::Texture2D, LoadError> {
+	let (pixels, info) = decode(path, props, transform)?;
+
+	let tx = g.texture2d_create(name, &crate::Texture2DInfo {
+		width: info.width as i32,
+		height: info.height as i32,
+		format: crate::TextureFormat::R8G8B8A8,
+		filter_min: props.filter_min,
+		filter_mag: props.filter_mag,
+		wrap_u: props.wrap_u,
+		wrap_v: props.wrap_v,
+		border_color: [0, 0, 0, 0],
+		mip_levels: props.mip_levels,
+		anisotropy: props.anisotropy,
+	})?;
+	g.texture2d_set_data(tx, &pixels)?;
+	Ok(tx)
+}
+
+/// Loads same-size PNG files as the layers of a texture array.
+///
+/// The layers are in the order of the paths.
+pub fn load_array(
+	g: &mut crate::Graphics,
+	name: Option<&str>,
+	paths: &[&str],
+	props: &TextureProps,
+	mipmaps: bool,
+) -> Result<crate::Texture2DArray, LoadError> {
+	let mut pixels = Vec::new();
+	let mut size = None;
+	for path in paths {
+		let (layer, info) = decode(path, props, None)?;
+		if *size.get_or_insert((info.width, info.height)) != (info.width, info.height) {
+			return Err(LoadError::SizeMismatch);
+		}
+		pixels.extend_from_slice(&layer);
+	}
+	let (width, height) = size.unwrap_or((0, 0));
+
+	let tx = g.texture2darray_create(name, &crate::Texture2DArrayInfo {
+		width: width as i32,
+		height: height as i32,
+		layers: paths.len() as i32,
+		mipmaps,
+		format: crate::TextureFormat::R8G8B8A8,
+		filter_min: props.filter_min,
+		filter_mag: props.filter_mag,
+		wrap_u: props.wrap_u,
+		wrap_v: props.wrap_v,
+	})?;
+	g.texture2darray_set_data(tx, &pixels)?;
+	Ok(tx)
+}
+
+/// Decodes a PNG file to 8-bit Rgba pixels.
+fn decode(
+	path: &str,
+	props: &TextureProps,
+	transform: Transform,
+) -> Result<(Vec<u8>, png::OutputInfo), LoadError> {
 
 	// Read the PNG file
 	let file = fs::File::open(path).map_err(png::DecodingError::IoError)?;
@@ -89,24 +174,33 @@ pub fn load(
 	let mut pixels = vec![0; reader.output_buffer_size()];
 	let mut info = reader.next_frame(&mut pixels)?;
 
-	// Only support 8-bit Rgba images
-	assert_eq!(info.bit_depth, png::BitDepth::Eight);
-	assert_eq!(info.color_type, png::ColorType::Rgba);
+	// Expand the image to 8-bit Rgba
+	if info.bit_depth != png::BitDepth::Eight {
+		return Err(LoadError::Gfx(crate::GfxError::Unsupported("png bit depth")));
+	}
+	pixels.truncate(info.buffer_size());
+	match info.color_type {
+		png::ColorType::Rgba => (),
+		png::ColorType::Rgb => pixels = pixels.chunks_exact(3).flat_map(|p| [p[0], p[1], p[2], 255]).collect(),
+		png::ColorType::GrayscaleAlpha => pixels = pixels.chunks_exact(2).flat_map(|p| [p[0], p[0], p[0], p[1]]).collect(),
+		png::ColorType::Grayscale => pixels = pixels.iter().flat_map(|&v| [v, v, v, 255]).collect(),
+		png::ColorType::Indexed => return Err(LoadError::Gfx(crate::GfxError::Unsupported("png indexed color"))),
+	}
+	info.color_type = png::ColorType::Rgba;
+	info.line_size = info.width as usize * 4;
+
+	if props.premultiply {
+		premultiply(&mut pixels);
+	}
 
 	if let Some(transform) = transform {
 		transform(&mut pixels, &mut info);
 	}
 
-	let tx = g.texture2d_create(name, &crate::Texture2DInfo {
-		width: info.width as i32,
-		height: info.height as i32,
-		format: crate::TextureFormat::R8G8B8A8,
-		filter_min: props.filter_min,
-		filter_mag: props.filter_mag,
-		wrap_u: props.wrap_u,
-		wrap_v: props.wrap_v,
-		border_color: [0, 0, 0, 0],
-	})?;
-	g.texture2d_set_data(tx, &pixels)?;
-	Ok(tx)
+	// Drop the top mip levels for the texture quality setting
+	let (width, height) = crate::texture_quality().downscale(&mut pixels, info.width as usize, info.height as usize);
+	info.width = width as u32;
+	info.height = height as u32;
+
+	Ok((pixels, info))
 }
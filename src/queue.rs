@@ -0,0 +1,96 @@
+use super::*;
+
+/// Render queue bucket.
+///
+/// Buckets are drawn in order.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[repr(u8)]
+pub enum RenderBucket {
+	/// Opaque geometry, sorted by state then front to back.
+	Opaque = 0,
+	/// Alpha tested geometry, sorted by state then front to back.
+	AlphaTest = 1,
+	/// Transparent geometry, sorted back to front.
+	Transparent = 2,
+	/// Overlays drawn last in submission order.
+	Overlay = 3,
+}
+
+/// Render queue.
+///
+/// Collects draw calls and submits them sorted to minimize state changes and blend transparent geometry correctly.
+pub struct RenderQueue {
+	items: Vec<(u64, DrawIndexedArgs)>,
+}
+
+impl Default for RenderQueue {
+	#[inline]
+	fn default() -> Self {
+		RenderQueue::new()
+	}
+}
+
+impl RenderQueue {
+	/// Creates a new empty render queue.
+	pub fn new() -> RenderQueue {
+		RenderQueue { items: Vec::new() }
+	}
+
+	/// Returns the number of queued draw calls.
+	#[inline]
+	pub fn len(&self) -> usize {
+		self.items.len()
+	}
+
+	/// Returns true if there are no queued draw calls.
+	#[inline]
+	pub fn is_empty(&self) -> bool {
+		self.items.is_empty()
+	}
+
+	/// Queues a draw call.
+	///
+	/// The texture is the main texture bound by the uniforms, draw calls sharing a shader are grouped by texture.
+	/// The depth is the normalized view depth between `0.0` (near) and `1.0` (far).
+	#[inline]
+	pub fn push(&mut self, bucket: RenderBucket, texture: Texture2D, depth: f32, args: DrawIndexedArgs) {
+		let key = sort_key(bucket, args.shader, texture, depth);
+		self.items.push((key, args));
+	}
+
+	/// Sorts and submits all queued draw calls, then clears the queue.
+	///
+	/// Draw calls with equal sort keys are submitted in the order they were queued.
+	pub fn submit(&mut self, g: &mut Graphics) -> Result<(), GfxError> {
+		self.items.sort_by_key(|&(key, _)| key);
+		let result = self.items.iter().try_for_each(|(_, args)| g.draw_indexed(args));
+		self.items.clear();
+		result
+	}
+
+	/// Clears the queue without drawing.
+	#[inline]
+	pub fn clear(&mut self) {
+		self.items.clear();
+	}
+}
+
+/// Builds the sort key of a draw call.
+///
+/// ```text
+/// opaque:      [bucket:2][shader:16][texture:16][depth:24][0:6]
+/// transparent: [bucket:2][far depth:24][shader:16][texture:16][0:6]
+/// overlay:     [bucket:2][0:62]
+/// ```
+pub(crate) fn sort_key(bucket: RenderBucket, shader: Shader, texture: Texture2D, depth: f32) -> u64 {
+	use crate::handle::Handle;
+	let bucket_bits = (bucket as u64) << 62;
+	let shader_bits = (shader.id() & 0xffff) as u64;
+	let texture_bits = (texture.id() & 0xffff) as u64;
+	let depth_bits = (depth.clamp(0.0, 1.0) * 0xffffff as f32) as u64;
+	match bucket {
+		RenderBucket::Opaque | RenderBucket::AlphaTest => bucket_bits | shader_bits << 46 | texture_bits << 30 | depth_bits << 6,
+		RenderBucket::Transparent => bucket_bits | (0xffffff - depth_bits) << 38 | shader_bits << 22 | texture_bits << 6,
+		RenderBucket::Overlay => bucket_bits,
+	}
+}
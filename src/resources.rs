@@ -57,9 +57,13 @@ impl<T: Resource> ResourceMap<T> {
 	}
 
 	/// Removes a resource from the map and returns it.
+	///
+	/// Handles are never reused, if `free_handle` is false the name of the resource remains reserved.
 	pub fn remove(&mut self, id: T::Handle, free_handle: bool) -> Option<T> {
-		assert!(free_handle, "not yet implemented");
 		let raw = <T::Handle as Handle>::id(&id);
+		if free_handle {
+			self.names.retain(|_, &mut id| id != raw);
+		}
 		self.map.remove(&raw)
 	}
 }
@@ -0,0 +1,1040 @@
+/*!
+Software graphics backend.
+
+Rasterizes to CPU framebuffers without a GL context or window, use it to test drawing code and compare golden images in CI.
+
+Shaders are not executed, the sources passed to [`shader_compile`](crate::IGraphics::shader_compile) are ignored.
+Draws are interpreted with a fixed function pipeline instead:
+
+* The first vertex attribute is the position.
+* The first normalized four component attribute is the vertex color, white if there is none.
+* The first two component float attribute after the position is the texture coordinate.
+* The `u_transform` uniform of the draw call or a bound uniform block transforms the position, a `Mat3x2` for 2D or a `Mat4x4` for 3D. Without it the position is in clip space.
+* The first sampler uniform is multiplied with the vertex color.
+
+Primitives crossing the near plane are not clipped, stencil modes and instancing are not supported.
+Cube map, 3D and array textures are stored but not sampled, mipmaps are only generated for 2D textures.
+The mip level of detail is computed once per triangle.
+Pixels are stored as 8-bit Rgba with the rows from bottom to top like `glReadPixels`, regardless of the surface format.
+
+# Example
+
+```rust
+use shade::cvmath::{Rect, Vec4};
+
+#[derive(Copy, Clone, Default, dataview::Pod)]
+#[repr(C)]
+struct Vertex {
+    pos: [f32; 2],
+    color: [u8; 4],
+}
+
+unsafe impl shade::TVertex for Vertex {
+    const VERTEX_LAYOUT: &'static shade::VertexLayout = &shade::VertexLayout {
+        size: std::mem::size_of::<Vertex>() as u16,
+        alignment: std::mem::align_of::<Vertex>() as u16,
+        instance_divisor: 0,
+        attributes: &[
+            shade::VertexAttribute { format: shade::VertexAttributeFormat::F32, len: 2, offset: 0 },
+            shade::VertexAttribute { format: shade::VertexAttributeFormat::U8Norm, len: 4, offset: 8 },
+        ],
+    };
+}
+
+#[derive(Copy, Clone, Default, dataview::Pod)]
+#[repr(C)]
+struct Uniform {}
+
+unsafe impl shade::TUniform for Uniform {
+    const UNIFORM_LAYOUT: &'static shade::UniformLayout = &shade::UniformLayout { size: 0, alignment: 1, attributes: &[] };
+}
+
+let mut soft = shade::soft::SoftGraphics::new(8, 8);
+let g = &mut *soft;
+let shader = g.shader_create(None).unwrap();
+let red = [255, 0, 0, 255];
+let vertices = g.vertex_buffer(None, &[
+    Vertex { pos: [-1.0, -1.0], color: red },
+    Vertex { pos: [1.0, -1.0], color: red },
+    Vertex { pos: [-1.0, 1.0], color: red },
+], shade::BufferUsage::Static).unwrap();
+let uniforms = g.uniform_buffer(None, &[Uniform {}]).unwrap();
+
+g.begin().unwrap();
+g.clear(&shade::ClearArgs {
+    surface: shade::Surface::BACK_BUFFER,
+    color: Some(Vec4(0.0, 0.0, 1.0, 1.0)),
+    ..Default::default()
+}).unwrap();
+g.draw(&shade::DrawArgs {
+    surface: shade::Surface::BACK_BUFFER,
+    viewport: Rect::c(0, 0, 8, 8),
+    scissor: None,
+    blend_mode: shade::BlendMode::Solid,
+    depth_test: None,
+    cull_mode: None,
+    fill_mode: shade::FillMode::Solid,
+    alpha_to_coverage: false,
+    stencil: None,
+    prim_type: shade::PrimType::Triangles,
+    shader,
+    vertices,
+    uniforms,
+    vertex_start: 0,
+    vertex_end: 3,
+    uniform_index: 0,
+    instances: -1,
+}).unwrap();
+g.end().unwrap();
+
+assert_eq!(soft.read_pixel(shade::Surface::BACK_BUFFER, 1, 1), Some([255, 0, 0, 255]));
+assert_eq!(soft.read_pixel(shade::Surface::BACK_BUFFER, 6, 6), Some([0, 0, 255, 255]));
+```
+*/
+
+use std::{cmp, mem, ops};
+
+use crate::resources::{Resource, ResourceMap};
+use crate::handle::Handle;
+
+mod raster;
+
+#[cfg(test)]
+mod tests;
+
+use self::raster::{Pipeline, Target, Transform};
+
+struct SoftVertexBuffer {
+	data: Vec<u8>,
+	layout: &'static crate::VertexLayout,
+}
+
+impl Resource for SoftVertexBuffer {
+	type Handle = crate::VertexBuffer;
+}
+
+struct SoftIndexBuffer {
+	data: Vec<u32>,
+}
+
+impl Resource for SoftIndexBuffer {
+	type Handle = crate::IndexBuffer;
+}
+
+struct SoftUniformBuffer {
+	data: Vec<u8>,
+	layout: &'static crate::UniformLayout,
+}
+
+impl Resource for SoftUniformBuffer {
+	type Handle = crate::UniformBuffer;
+}
+
+impl SoftUniformBuffer {
+	/// Returns the data of the uniform attribute.
+	fn attribute(&self, index: u32, attr: &crate::UniformAttribute) -> &[u8] {
+		let start = self.layout.size as usize * index as usize + attr.offset as usize;
+		self.data.get(start..).unwrap_or(&[])
+	}
+
+	fn transform(&self, index: u32) -> Option<Transform> {
+		let attr = self.layout.attributes.iter().find(|attr| attr.name == "u_transform")?;
+		let data = self.attribute(index, attr);
+		let read = |count: usize| -> Option<Vec<f32>> {
+			let bytes = data.get(..count * 4)?;
+			Some(bytes.chunks_exact(4).map(|b| f32::from_ne_bytes([b[0], b[1], b[2], b[3]])).collect())
+		};
+		let transform = match attr.ty {
+			crate::UniformType::Mat3x2 { order } => {
+				let m = read(6)?;
+				match order {
+					crate::UniformMatOrder::RowMajor => Transform::Affine([m[0], m[1], m[2], m[3], m[4], m[5]]),
+					crate::UniformMatOrder::ColumnMajor => Transform::Affine([m[0], m[2], m[4], m[1], m[3], m[5]]),
+				}
+			}
+			crate::UniformType::Mat4x4 { order } => {
+				let m = read(16)?;
+				match order {
+					crate::UniformMatOrder::RowMajor => Transform::Projective(std::array::from_fn(|i| m[i])),
+					crate::UniformMatOrder::ColumnMajor => Transform::Projective(std::array::from_fn(|i| m[(i % 4) * 4 + i / 4])),
+				}
+			}
+			_ => return None,
+		};
+		Some(transform)
+	}
+
+	fn texture(&self, index: u32) -> Option<crate::Texture2D> {
+		let attr = self.layout.attributes.iter().find(|attr| matches!(attr.ty, crate::UniformType::Sampler2D(_)))?;
+		let b = self.attribute(index, attr).get(..4)?;
+		Some(crate::Texture2D::create(u32::from_ne_bytes([b[0], b[1], b[2], b[3]])))
+	}
+}
+
+/// Uniform buffer element bound to a named uniform block.
+struct SoftUniformBlock {
+	name: String,
+	uniforms: crate::UniformBuffer,
+	uniform_index: u32,
+}
+
+struct SoftShader {
+	defaults: crate::ParamBlock,
+}
+
+impl Resource for SoftShader {
+	type Handle = crate::Shader;
+}
+
+struct SoftTexture2D {
+	info: crate::Texture2DInfo,
+	pixels: Vec<u8>,
+	/// Mip levels below the base level.
+	mips: Vec<Vec<u8>>,
+}
+
+impl Resource for SoftTexture2D {
+	type Handle = crate::Texture2D;
+}
+
+struct SoftTextureCube {
+	info: crate::TextureCubeInfo,
+	faces: [Vec<u8>; 6],
+}
+
+impl Resource for SoftTextureCube {
+	type Handle = crate::TextureCube;
+}
+
+struct SoftTexture3D {
+	info: crate::Texture3DInfo,
+	pixels: Vec<u8>,
+}
+
+impl Resource for SoftTexture3D {
+	type Handle = crate::Texture3D;
+}
+
+struct SoftTexture2DArray {
+	info: crate::Texture2DArrayInfo,
+	pixels: Vec<u8>,
+}
+
+impl Resource for SoftTexture2DArray {
+	type Handle = crate::Texture2DArray;
+}
+
+struct SoftSurface {
+	texture: crate::Texture2D,
+	depth: Vec<f32>,
+	info: crate::SurfaceInfo,
+}
+
+impl Resource for SoftSurface {
+	type Handle = crate::Surface;
+}
+
+struct SoftFence;
+
+impl Resource for SoftFence {
+	type Handle = crate::Fence;
+}
+
+/// Color and depth buffers taken out of a surface while drawing.
+struct Framebuffer {
+	color: Vec<u8>,
+	depth: Vec<f32>,
+	width: i32,
+	height: i32,
+}
+
+/// Software graphics backend.
+pub struct SoftGraphics {
+	vertices: ResourceMap<SoftVertexBuffer>,
+	indices: ResourceMap<SoftIndexBuffer>,
+	uniforms: ResourceMap<SoftUniformBuffer>,
+	shaders: ResourceMap<SoftShader>,
+	textures: ResourceMap<SoftTexture2D>,
+	cubes: ResourceMap<SoftTextureCube>,
+	volumes: ResourceMap<SoftTexture3D>,
+	arrays: ResourceMap<SoftTexture2DArray>,
+	surfaces: ResourceMap<SoftSurface>,
+	fences: ResourceMap<SoftFence>,
+	back_buffer: Framebuffer,
+	drawing: bool,
+	display: crate::DisplayInfo,
+	draw_count: u32,
+	capture: Option<(u32, usize)>,
+	captured: Option<crate::DrawCapture>,
+	blocks: Vec<SoftUniformBlock>,
+}
+
+impl SoftGraphics {
+	/// Creates a new software backend with a back buffer of the size in pixels.
+	pub fn new(width: i32, height: i32) -> SoftGraphics {
+		SoftGraphics {
+			vertices: ResourceMap::new(),
+			indices: ResourceMap::new(),
+			uniforms: ResourceMap::new(),
+			shaders: ResourceMap::new(),
+			textures: ResourceMap::new(),
+			cubes: ResourceMap::new(),
+			volumes: ResourceMap::new(),
+			arrays: ResourceMap::new(),
+			surfaces: ResourceMap::new(),
+			fences: ResourceMap::new(),
+			back_buffer: Framebuffer::new(width, height),
+			drawing: false,
+			display: crate::DisplayInfo { size: cvmath::Vec2(width, height), ..Default::default() },
+			draw_count: 0,
+			capture: None,
+			captured: None,
+			blocks: Vec::new(),
+		}
+	}
+
+	/// Resizes the back buffer, the contents are cleared.
+	///
+	/// Updates the display size, keeping its scale.
+	pub fn resize(&mut self, width: i32, height: i32) {
+		self.back_buffer = Framebuffer::new(width, height);
+		self.display.update(cvmath::Vec2(width, height), self.display.scale);
+	}
+
+	/// Returns the size of the surface in pixels.
+	pub fn size(&self, surface: crate::Surface) -> Result<(i32, i32), crate::GfxError> {
+		if surface == crate::Surface::BACK_BUFFER {
+			return Ok((self.back_buffer.width, self.back_buffer.height));
+		}
+		let Some(surface) = self.surfaces.get(surface) else { return Err(crate::GfxError::InvalidSurfaceHandle) };
+		Ok((surface.info.width, surface.info.height))
+	}
+
+	/// Returns the 8-bit Rgba pixels of the surface, the rows are stored from bottom to top.
+	pub fn pixels(&self, surface: crate::Surface) -> Result<&[u8], crate::GfxError> {
+		if surface == crate::Surface::BACK_BUFFER {
+			return Ok(&self.back_buffer.color);
+		}
+		let Some(surface) = self.surfaces.get(surface) else { return Err(crate::GfxError::InvalidSurfaceHandle) };
+		let Some(texture) = self.textures.get(surface.texture) else { return Err(crate::GfxError::InvalidTexture2DHandle) };
+		Ok(&texture.pixels)
+	}
+
+	/// Returns the pixel of the surface, `y` is counted from the bottom.
+	pub fn read_pixel(&self, surface: crate::Surface, x: i32, y: i32) -> Option<[u8; 4]> {
+		let (width, height) = self.size(surface).ok()?;
+		if x < 0 || x >= width || y < 0 || y >= height {
+			return None;
+		}
+		let index = ((y * width + x) * 4) as usize;
+		let pixel = self.pixels(surface).ok()?.get(index..index + 4)?;
+		Some([pixel[0], pixel[1], pixel[2], pixel[3]])
+	}
+
+	/// Takes the buffers of the surface to draw to them while the textures are borrowed.
+	fn take_framebuffer(&mut self, id: crate::Surface) -> Result<Framebuffer, crate::GfxError> {
+		if id == crate::Surface::BACK_BUFFER {
+			return Ok(mem::replace(&mut self.back_buffer, Framebuffer::new(0, 0)));
+		}
+		let Some(surface) = self.surfaces.get_mut(id) else { return Err(crate::GfxError::InvalidSurfaceHandle) };
+		let depth = mem::take(&mut surface.depth);
+		let (width, height) = (surface.info.width, surface.info.height);
+		let texture = surface.texture;
+		let Some(texture) = self.textures.get_mut(texture) else { return Err(crate::GfxError::InvalidTexture2DHandle) };
+		let color = mem::take(&mut texture.pixels);
+		Ok(Framebuffer { color, depth, width, height })
+	}
+
+	/// Returns the buffers taken with [`take_framebuffer`](Self::take_framebuffer).
+	fn put_framebuffer(&mut self, id: crate::Surface, framebuffer: Framebuffer) {
+		if id == crate::Surface::BACK_BUFFER {
+			self.back_buffer = framebuffer;
+			return;
+		}
+		let Some(surface) = self.surfaces.get_mut(id) else { return };
+		surface.depth = framebuffer.depth;
+		let texture = surface.texture;
+		if let Some(texture) = self.textures.get_mut(texture) {
+			texture.pixels = framebuffer.color;
+		}
+	}
+
+	/// Draws the vertices at the indices, counts the draw call if it is not empty.
+	fn draw_primitives(&mut self, args: &DrawState, indices: &[usize]) -> Result<(), crate::GfxError> {
+		if args.instances >= 0 {
+			return Err(crate::GfxError::Unsupported("instancing"));
+		}
+		if args.stencil.is_some() {
+			return Err(crate::GfxError::Unsupported("stencil"));
+		}
+		if self.shaders.get(args.shader).is_none() {
+			return Err(crate::GfxError::InvalidShaderHandle);
+		}
+		if indices.is_empty() {
+			return Ok(());
+		}
+
+		let mut fb = self.take_framebuffer(args.surface)?;
+		let result = self.rasterize(args, indices, &mut fb);
+		self.put_framebuffer(args.surface, fb);
+		self.draw_count += 1;
+		result
+	}
+
+	fn rasterize(&mut self, args: &DrawState, indices: &[usize], fb: &mut Framebuffer) -> Result<(), crate::GfxError> {
+		let Some(vb) = self.vertices.get(args.vertices) else { return Err(crate::GfxError::InvalidVertexBufferHandle) };
+		let Some(ub) = self.uniforms.get(args.uniforms) else { return Err(crate::GfxError::InvalidUniformBufferHandle) };
+
+		let draw_index = self.draw_count;
+		if let Some((index, max_vertices)) = self.capture {
+			if index == draw_index {
+				self.captured = Some(soft_capture(vb, ub, args, draw_index, max_vertices));
+			}
+		}
+
+		let mut clip = [0, 0, fb.width, fb.height];
+		for rc in [Some(args.viewport), args.scissor].into_iter().flatten() {
+			clip = [cmp::max(clip[0], rc.mins.x), cmp::max(clip[1], rc.mins.y), cmp::min(clip[2], rc.maxs.x), cmp::min(clip[3], rc.maxs.y)];
+		}
+
+		// The uniforms of the draw call take precedence over the uniform blocks
+		let blocks = self.blocks.iter().filter_map(|block| Some((self.uniforms.get(block.uniforms)?, block.uniform_index)));
+		let sources: Vec<(&SoftUniformBuffer, u32)> = std::iter::once((ub, args.uniform_index)).chain(blocks).collect();
+
+		let pipeline = Pipeline {
+			vertices: &vb.data,
+			layout: vb.layout,
+			transform: sources.iter().find_map(|&(ub, index)| ub.transform(index)).unwrap_or(Transform::Identity),
+			texture: sources.iter().find_map(|&(ub, index)| ub.texture(index)).and_then(|id| self.textures.get(id)),
+			viewport: args.viewport,
+			clip,
+			blend_mode: args.blend_mode,
+			depth_test: args.depth_test,
+			cull_mode: args.cull_mode,
+			fill_mode: args.fill_mode,
+		};
+		let has_depth = fb.depth.len() == fb.color.len() / 4 && !fb.depth.is_empty();
+		let mut target = Target {
+			color: &mut fb.color,
+			depth: if has_depth { Some(&mut fb.depth) } else { None },
+			width: fb.width,
+			height: fb.height,
+		};
+		pipeline.draw(&mut target, args.prim_type, indices);
+		Ok(())
+	}
+}
+
+impl Framebuffer {
+	fn new(width: i32, height: i32) -> Framebuffer {
+		let (width, height) = (cmp::max(0, width), cmp::max(0, height));
+		let count = (width * height) as usize;
+		Framebuffer { color: vec![0; count * 4], depth: vec![1.0; count], width, height }
+	}
+}
+
+/// State shared by indexed and non-indexed draws.
+struct DrawState {
+	surface: crate::Surface,
+	viewport: cvmath::Rect<i32>,
+	scissor: Option<cvmath::Rect<i32>>,
+	blend_mode: crate::BlendMode,
+	depth_test: Option<crate::DepthTest>,
+	cull_mode: Option<crate::CullMode>,
+	fill_mode: crate::FillMode,
+	stencil: Option<crate::StencilMode>,
+	prim_type: crate::PrimType,
+	shader: crate::Shader,
+	vertices: crate::VertexBuffer,
+	uniforms: crate::UniformBuffer,
+	vertex_start: u32,
+	uniform_index: u32,
+	instances: i32,
+}
+
+fn soft_capture(vb: &SoftVertexBuffer, ub: &SoftUniformBuffer, args: &DrawState, draw_index: u32, max_vertices: usize) -> crate::DrawCapture {
+	let uniform_start = ub.layout.size as usize * args.uniform_index as usize;
+	let uniforms = ub.layout.attributes.iter().map(|uattr| {
+		let start = cmp::min(ub.data.len(), uniform_start + uattr.offset as usize);
+		let end = match ub.layout.attributes.iter().map(|a| a.offset).filter(|&offset| offset > uattr.offset).min() {
+			Some(next) => uniform_start + next as usize,
+			None => uniform_start + ub.layout.size as usize,
+		};
+		crate::UniformCapture {
+			name: uattr.name,
+			ty: uattr.ty,
+			len: uattr.len,
+			// Only the transform and the texture are used by the fixed function pipeline
+			active: uattr.name == "u_transform" || matches!(uattr.ty, crate::UniformType::Sampler2D(_)),
+			data: ub.data[start..cmp::max(start, cmp::min(ub.data.len(), end))].to_vec(),
+		}
+	}).collect();
+	let stride = vb.layout.size as usize;
+	let start = cmp::min(vb.data.len(), args.vertex_start as usize * stride);
+	let end = cmp::min(vb.data.len(), start + max_vertices * stride);
+	crate::DrawCapture {
+		draw_index,
+		shader: args.shader,
+		prim_type: args.prim_type,
+		uniforms,
+		vertex_layout: vb.layout,
+		vertex_start: args.vertex_start,
+		vertex_data: vb.data[start..end].to_vec(),
+	}
+}
+
+impl crate::IGraphics for SoftGraphics {
+	fn capabilities(&mut self) -> crate::Caps {
+		crate::Caps {
+			max_texture_size: 16384,
+			max_vertex_attributes: 16,
+			max_texture_units: 1,
+			max_color_attachments: 1,
+			max_anisotropy: 0,
+			instancing: false,
+			float_textures: false,
+			f64: false,
+			compute: false,
+			s3tc: false,
+			etc2: false,
+			astc: false,
+		}
+	}
+
+	fn last_error(&mut self) -> Option<crate::GfxError> {
+		None
+	}
+
+	fn display_update(&mut self, size: cvmath::Vec2<i32>, scale: f32) {
+		self.display.update(size, scale);
+	}
+
+	fn display_info(&self) -> crate::DisplayInfo {
+		self.display
+	}
+
+	fn begin(&mut self) -> Result<(), crate::GfxError> {
+		if self.drawing {
+			return Err(crate::GfxError::InvalidDrawCallTime);
+		}
+
+		crate::log::frame_reset();
+		self.drawing = true;
+		self.draw_count = 0;
+		Ok(())
+	}
+
+	fn clear(&mut self, args: &crate::ClearArgs) -> Result<(), crate::GfxError> {
+		if !self.drawing {
+			return Err(crate::GfxError::InvalidDrawCallTime);
+		}
+		if args.color_int.is_some() || args.color_uint.is_some() {
+			return Err(crate::GfxError::Unsupported("integer attachments"));
+		}
+
+		let mut fb = self.take_framebuffer(args.surface)?;
+		let mut rc = [0, 0, fb.width, fb.height];
+		if let Some(scissor) = args.scissor {
+			rc = [cmp::max(rc[0], scissor.mins.x), cmp::max(rc[1], scissor.mins.y), cmp::min(rc[2], scissor.maxs.x), cmp::min(rc[3], scissor.maxs.y)];
+		}
+		let color = args.color.map(|color| [color.x, color.y, color.z, color.w].map(raster::to_u8));
+		for y in rc[1]..rc[3] {
+			for x in rc[0]..rc[2] {
+				let index = (y * fb.width + x) as usize;
+				if let Some(color) = color {
+					fb.color[index * 4..index * 4 + 4].copy_from_slice(&color);
+				}
+				if let (Some(depth), Some(value)) = (args.depth, fb.depth.get_mut(index)) {
+					*value = depth;
+				}
+			}
+		}
+		self.put_framebuffer(args.surface, fb);
+		Ok(())
+	}
+
+	fn draw(&mut self, args: &crate::DrawArgs) -> Result<(), crate::GfxError> {
+		if !self.drawing {
+			return Err(crate::GfxError::InvalidDrawCallTime);
+		}
+		if args.vertex_end < args.vertex_start {
+			return Err(crate::GfxError::IndexOutOfBounds);
+		}
+
+		let indices: Vec<usize> = (args.vertex_start as usize..args.vertex_end as usize).collect();
+		self.draw_primitives(&DrawState {
+			surface: args.surface,
+			viewport: args.viewport,
+			scissor: args.scissor,
+			blend_mode: args.blend_mode,
+			depth_test: args.depth_test,
+			cull_mode: args.cull_mode,
+			fill_mode: args.fill_mode,
+			stencil: args.stencil,
+			prim_type: args.prim_type,
+			shader: args.shader,
+			vertices: args.vertices,
+			uniforms: args.uniforms,
+			vertex_start: args.vertex_start,
+			uniform_index: args.uniform_index,
+			instances: args.instances,
+		}, &indices)
+	}
+
+	fn draw_indexed(&mut self, args: &crate::DrawIndexedArgs) -> Result<(), crate::GfxError> {
+		if !self.drawing {
+			return Err(crate::GfxError::InvalidDrawCallTime);
+		}
+		if args.index_end < args.index_start || args.vertex_end < args.vertex_start {
+			return Err(crate::GfxError::IndexOutOfBounds);
+		}
+
+		let Some(ib) = self.indices.get(args.indices) else { return Err(crate::GfxError::InvalidIndexBufferHandle) };
+		let Some(indices) = ib.data.get(args.index_start as usize..args.index_end as usize) else { return Err(crate::GfxError::IndexOutOfBounds) };
+		let indices: Vec<usize> = indices.iter().map(|&index| match index {
+			u32::MAX => raster::RESTART,
+			_ => (index as i64 + args.base_vertex as i64).max(0) as usize,
+		}).collect();
+		self.draw_primitives(&DrawState {
+			surface: args.surface,
+			viewport: args.viewport,
+			scissor: args.scissor,
+			blend_mode: args.blend_mode,
+			depth_test: args.depth_test,
+			cull_mode: args.cull_mode,
+			fill_mode: args.fill_mode,
+			stencil: args.stencil,
+			prim_type: args.prim_type,
+			shader: args.shader,
+			vertices: args.vertices,
+			uniforms: args.uniforms,
+			vertex_start: args.vertex_start,
+			uniform_index: args.uniform_index,
+			instances: args.instances,
+		}, &indices)
+	}
+
+	fn end(&mut self) -> Result<(), crate::GfxError> {
+		self.drawing = false;
+		Ok(())
+	}
+
+	fn debug_capture(&mut self, draw_index: Option<u32>, max_vertices: usize) {
+		self.capture = draw_index.map(|index| (index, max_vertices));
+	}
+
+	fn debug_capture_take(&mut self) -> Option<crate::DrawCapture> {
+		self.captured.take()
+	}
+
+	fn vertex_buffer_create(&mut self, name: Option<&str>, layout: &'static crate::VertexLayout, count: usize) -> Result<crate::VertexBuffer, crate::GfxError> {
+		let data = Vec::with_capacity(layout.size as usize * count);
+		let id = self.vertices.insert(name, SoftVertexBuffer { data, layout });
+		Ok(id)
+	}
+
+	fn vertex_buffer_find(&mut self, name: &str) -> Result<crate::VertexBuffer, crate::GfxError> {
+		let Some(id) = self.vertices.find_id(name) else { return Err(crate::GfxError::NameNotFound) };
+		Ok(id)
+	}
+
+	fn vertex_buffer_set_data(&mut self, id: crate::VertexBuffer, data: &[u8], _usage: crate::BufferUsage) -> Result<(), crate::GfxError> {
+		let Some(vb) = self.vertices.get_mut(id) else { return Err(crate::GfxError::InvalidVertexBufferHandle) };
+		vb.data.clear();
+		vb.data.extend_from_slice(data);
+		Ok(())
+	}
+
+	fn vertex_buffer_delete(&mut self, id: crate::VertexBuffer, free_handle: bool) -> Result<(), crate::GfxError> {
+		let Some(_) = self.vertices.remove(id, free_handle) else { return Err(crate::GfxError::InvalidVertexBufferHandle) };
+		Ok(())
+	}
+
+	fn index_buffer_create(&mut self, name: Option<&str>, count: usize) -> Result<crate::IndexBuffer, crate::GfxError> {
+		let id = self.indices.insert(name, SoftIndexBuffer { data: Vec::with_capacity(count) });
+		Ok(id)
+	}
+
+	fn index_buffer_find(&mut self, name: &str) -> Result<crate::IndexBuffer, crate::GfxError> {
+		let Some(id) = self.indices.find_id(name) else { return Err(crate::GfxError::NameNotFound) };
+		Ok(id)
+	}
+
+	fn index_buffer_set_data(&mut self, id: crate::IndexBuffer, data: &[u32], _usage: crate::BufferUsage) -> Result<(), crate::GfxError> {
+		let Some(ib) = self.indices.get_mut(id) else { return Err(crate::GfxError::InvalidIndexBufferHandle) };
+		ib.data.clear();
+		ib.data.extend_from_slice(data);
+		Ok(())
+	}
+
+	fn index_buffer_delete(&mut self, id: crate::IndexBuffer, free_handle: bool) -> Result<(), crate::GfxError> {
+		let Some(_) = self.indices.remove(id, free_handle) else { return Err(crate::GfxError::InvalidIndexBufferHandle) };
+		Ok(())
+	}
+
+	fn uniform_buffer_create(&mut self, name: Option<&str>, layout: &'static crate::UniformLayout, count: usize) -> Result<crate::UniformBuffer, crate::GfxError> {
+		let data = Vec::with_capacity(layout.size as usize * count);
+		let id = self.uniforms.insert(name, SoftUniformBuffer { data, layout });
+		Ok(id)
+	}
+
+	fn uniform_buffer_find(&mut self, name: &str) -> Result<crate::UniformBuffer, crate::GfxError> {
+		let Some(id) = self.uniforms.find_id(name) else { return Err(crate::GfxError::NameNotFound) };
+		Ok(id)
+	}
+
+	fn uniform_buffer_set_data(&mut self, id: crate::UniformBuffer, data: &[u8]) -> Result<(), crate::GfxError> {
+		let Some(ub) = self.uniforms.get_mut(id) else { return Err(crate::GfxError::InvalidUniformBufferHandle) };
+		ub.data.clear();
+		ub.data.extend_from_slice(data);
+		Ok(())
+	}
+
+	fn uniform_buffer_delete(&mut self, id: crate::UniformBuffer, free_handle: bool) -> Result<(), crate::GfxError> {
+		let Some(_) = self.uniforms.remove(id, free_handle) else { return Err(crate::GfxError::InvalidUniformBufferHandle) };
+		self.blocks.retain(|block| block.uniforms != id);
+		Ok(())
+	}
+
+	fn uniform_block_bind(&mut self, block: &str, id: crate::UniformBuffer, uniform_index: u32) -> Result<(), crate::GfxError> {
+		let Some(ub) = self.uniforms.get(id) else { return Err(crate::GfxError::InvalidUniformBufferHandle) };
+		if ub.data.len() < ub.layout.size as usize * (uniform_index as usize + 1) {
+			return Err(crate::GfxError::IndexOutOfBounds);
+		}
+		match self.blocks.iter_mut().find(|b| b.name == block) {
+			Some(b) => {
+				b.uniforms = id;
+				b.uniform_index = uniform_index;
+			}
+			None => self.blocks.push(SoftUniformBlock { name: block.to_string(), uniforms: id, uniform_index }),
+		}
+		Ok(())
+	}
+
+	fn uniform_block_unbind(&mut self, block: &str) -> Result<(), crate::GfxError> {
+		let Some(index) = self.blocks.iter().position(|b| b.name == block) else { return Err(crate::GfxError::NameNotFound) };
+		self.blocks.remove(index);
+		Ok(())
+	}
+
+	fn shader_create(&mut self, name: Option<&str>) -> Result<crate::Shader, crate::GfxError> {
+		let id = self.shaders.insert(name, SoftShader { defaults: crate::ParamBlock::new() });
+		Ok(id)
+	}
+
+	fn shader_find(&mut self, name: &str) -> Result<crate::Shader, crate::GfxError> {
+		let Some(id) = self.shaders.find_id(name) else { return Err(crate::GfxError::NameNotFound) };
+		Ok(id)
+	}
+
+	fn shader_compile(&mut self, id: crate::Shader, vertex_source: &str, fragment_source: &str) -> Result<(), crate::GfxError> {
+		let Some(shader) = self.shaders.get_mut(id) else { return Err(crate::GfxError::InvalidShaderHandle) };
+		shader.defaults.merge(&crate::ParamBlock::parse(vertex_source));
+		shader.defaults.merge(&crate::ParamBlock::parse(fragment_source));
+		Ok(())
+	}
+
+	fn shader_compile_log(&mut self, id: crate::Shader) -> Result<String, crate::GfxError> {
+		let Some(_) = self.shaders.get(id) else { return Err(crate::GfxError::InvalidShaderHandle) };
+		Ok(String::new())
+	}
+
+	fn shader_set_defaults(&mut self, id: crate::Shader, params: &crate::ParamBlock) -> Result<(), crate::GfxError> {
+		let Some(shader) = self.shaders.get_mut(id) else { return Err(crate::GfxError::InvalidShaderHandle) };
+		shader.defaults.merge(params);
+		Ok(())
+	}
+
+	fn shader_delete(&mut self, id: crate::Shader, free_handle: bool) -> Result<(), crate::GfxError> {
+		let Some(_) = self.shaders.remove(id, free_handle) else { return Err(crate::GfxError::InvalidShaderHandle) };
+		Ok(())
+	}
+
+	fn texture2d_create(&mut self, name: Option<&str>, info: &crate::Texture2DInfo) -> Result<crate::Texture2D, crate::GfxError> {
+		let id = self.textures.insert(name, SoftTexture2D { info: *info, pixels: Vec::new(), mips: Vec::new() });
+		Ok(id)
+	}
+
+	fn texture2d_find(&mut self, name: &str) -> Result<crate::Texture2D, crate::GfxError> {
+		let Some(id) = self.textures.find_id(name) else { return Err(crate::GfxError::NameNotFound) };
+		Ok(id)
+	}
+
+	fn texture2d_set_data(&mut self, id: crate::Texture2D, data: &[u8]) -> Result<(), crate::GfxError> {
+		let Some(texture) = self.textures.get_mut(id) else { return Err(crate::GfxError::InvalidTexture2DHandle) };
+		if texture.info.format != crate::TextureFormat::R8G8B8A8 {
+			return Err(crate::GfxError::Unsupported("texture2d_set_data with a format other than R8G8B8A8"));
+		}
+		let size = (cmp::max(0, texture.info.width) * cmp::max(0, texture.info.height) * 4) as usize;
+		if data.len() < size {
+			return Err(crate::GfxError::IndexOutOfBounds);
+		}
+		texture.pixels.clear();
+		texture.pixels.extend_from_slice(&data[..size]);
+		texture.generate_mips();
+		Ok(())
+	}
+
+	fn texture2d_set_compressed_data(&mut self, id: crate::Texture2D, _level: u32, _data: &[u8]) -> Result<(), crate::GfxError> {
+		let Some(_) = self.textures.get(id) else { return Err(crate::GfxError::InvalidTexture2DHandle) };
+		Err(crate::GfxError::Unsupported("texture2d_set_compressed_data"))
+	}
+
+	fn texture2d_generate_mips(&mut self, id: crate::Texture2D) -> Result<(), crate::GfxError> {
+		let Some(texture) = self.textures.get_mut(id) else { return Err(crate::GfxError::InvalidTexture2DHandle) };
+		texture.generate_mips();
+		Ok(())
+	}
+
+	fn texture2d_get_info(&mut self, id: crate::Texture2D) -> Result<crate::Texture2DInfo, crate::GfxError> {
+		let Some(texture) = self.textures.get(id) else { return Err(crate::GfxError::InvalidTexture2DHandle) };
+		Ok(texture.info)
+	}
+
+	fn texture2d_set_props(&mut self, id: crate::Texture2D, props: &crate::TextureProps) -> Result<(), crate::GfxError> {
+		let Some(texture) = self.textures.get_mut(id) else { return Err(crate::GfxError::InvalidTexture2DHandle) };
+		texture.info.set_props(props);
+		Ok(())
+	}
+
+	fn texture2d_copy(&mut self, src: crate::Texture2D, dst: crate::Texture2D, regions: &[crate::TextureCopy]) -> Result<(), crate::GfxError> {
+		let Some(src) = self.textures.get(src) else { return Err(crate::GfxError::InvalidTexture2DHandle) };
+		let (src_width, src_height, src_pixels) = (src.info.width, src.info.height, src.pixels.clone());
+		let Some(dst) = self.textures.get_mut(dst) else { return Err(crate::GfxError::InvalidTexture2DHandle) };
+		let (dst_width, dst_height) = (dst.info.width, dst.info.height);
+		dst.pixels.resize((cmp::max(0, dst_width) * cmp::max(0, dst_height) * 4) as usize, 0);
+		for region in regions {
+			let rc = region.src_rect;
+			for y in 0..rc.height() {
+				for x in 0..rc.width() {
+					let (sx, sy) = (rc.mins.x + x, rc.mins.y + y);
+					let (dx, dy) = (region.dst_pos.x + x, region.dst_pos.y + y);
+					if sx < 0 || sy < 0 || sx >= src_width || sy >= src_height || dx < 0 || dy < 0 || dx >= dst_width || dy >= dst_height {
+						continue;
+					}
+					let s = ((sy * src_width + sx) * 4) as usize;
+					let d = ((dy * dst_width + dx) * 4) as usize;
+					if let Some(pixel) = src_pixels.get(s..s + 4) {
+						dst.pixels[d..d + 4].copy_from_slice(pixel);
+					}
+				}
+			}
+		}
+		Ok(())
+	}
+
+	fn texture2d_delete(&mut self, id: crate::Texture2D, free_handle: bool) -> Result<(), crate::GfxError> {
+		let Some(_) = self.textures.remove(id, free_handle) else { return Err(crate::GfxError::InvalidTexture2DHandle) };
+		Ok(())
+	}
+
+	fn texturecube_create(&mut self, name: Option<&str>, info: &crate::TextureCubeInfo) -> Result<crate::TextureCube, crate::GfxError> {
+		if info.format != crate::TextureFormat::R8G8B8A8 {
+			return Err(crate::GfxError::Unsupported("texturecube_create with a format other than R8G8B8A8"));
+		}
+		let size = (cmp::max(0, info.size) * cmp::max(0, info.size) * 4) as usize;
+		let id = self.cubes.insert(name, SoftTextureCube { info: *info, faces: std::array::from_fn(|_| vec![0; size]) });
+		Ok(id)
+	}
+
+	fn texturecube_find(&mut self, name: &str) -> Result<crate::TextureCube, crate::GfxError> {
+		let Some(id) = self.cubes.find_id(name) else { return Err(crate::GfxError::NameNotFound) };
+		Ok(id)
+	}
+
+	fn texturecube_set_face_data(&mut self, id: crate::TextureCube, face: crate::CubeFace, data: &[u8]) -> Result<(), crate::GfxError> {
+		let Some(cube) = self.cubes.get_mut(id) else { return Err(crate::GfxError::InvalidTextureCubeHandle) };
+		let pixels = &mut cube.faces[face as usize];
+		let Some(data) = data.get(..pixels.len()) else { return Err(crate::GfxError::IndexOutOfBounds) };
+		pixels.copy_from_slice(data);
+		Ok(())
+	}
+
+	fn texturecube_get_info(&mut self, id: crate::TextureCube) -> Result<crate::TextureCubeInfo, crate::GfxError> {
+		let Some(cube) = self.cubes.get(id) else { return Err(crate::GfxError::InvalidTextureCubeHandle) };
+		Ok(cube.info)
+	}
+
+	fn texturecube_delete(&mut self, id: crate::TextureCube, free_handle: bool) -> Result<(), crate::GfxError> {
+		let Some(_) = self.cubes.remove(id, free_handle) else { return Err(crate::GfxError::InvalidTextureCubeHandle) };
+		Ok(())
+	}
+
+	fn texture3d_create(&mut self, name: Option<&str>, info: &crate::Texture3DInfo) -> Result<crate::Texture3D, crate::GfxError> {
+		if info.format != crate::TextureFormat::R8G8B8A8 {
+			return Err(crate::GfxError::Unsupported("texture3d_create with a format other than R8G8B8A8"));
+		}
+		let size = (cmp::max(0, info.width) * cmp::max(0, info.height) * cmp::max(0, info.depth) * 4) as usize;
+		let id = self.volumes.insert(name, SoftTexture3D { info: *info, pixels: vec![0; size] });
+		Ok(id)
+	}
+
+	fn texture3d_find(&mut self, name: &str) -> Result<crate::Texture3D, crate::GfxError> {
+		let Some(id) = self.volumes.find_id(name) else { return Err(crate::GfxError::NameNotFound) };
+		Ok(id)
+	}
+
+	fn texture3d_set_data(&mut self, id: crate::Texture3D, data: &[u8]) -> Result<(), crate::GfxError> {
+		let Some(volume) = self.volumes.get_mut(id) else { return Err(crate::GfxError::InvalidTexture3DHandle) };
+		let Some(data) = data.get(..volume.pixels.len()) else { return Err(crate::GfxError::IndexOutOfBounds) };
+		volume.pixels.copy_from_slice(data);
+		Ok(())
+	}
+
+	fn texture3d_get_info(&mut self, id: crate::Texture3D) -> Result<crate::Texture3DInfo, crate::GfxError> {
+		let Some(volume) = self.volumes.get(id) else { return Err(crate::GfxError::InvalidTexture3DHandle) };
+		Ok(volume.info)
+	}
+
+	fn texture3d_delete(&mut self, id: crate::Texture3D, free_handle: bool) -> Result<(), crate::GfxError> {
+		let Some(_) = self.volumes.remove(id, free_handle) else { return Err(crate::GfxError::InvalidTexture3DHandle) };
+		Ok(())
+	}
+
+	fn texture2darray_create(&mut self, name: Option<&str>, info: &crate::Texture2DArrayInfo) -> Result<crate::Texture2DArray, crate::GfxError> {
+		if info.format != crate::TextureFormat::R8G8B8A8 {
+			return Err(crate::GfxError::Unsupported("texture2darray_create with a format other than R8G8B8A8"));
+		}
+		let size = (cmp::max(0, info.width) * cmp::max(0, info.height) * cmp::max(0, info.layers) * 4) as usize;
+		let id = self.arrays.insert(name, SoftTexture2DArray { info: *info, pixels: vec![0; size] });
+		Ok(id)
+	}
+
+	fn texture2darray_find(&mut self, name: &str) -> Result<crate::Texture2DArray, crate::GfxError> {
+		let Some(id) = self.arrays.find_id(name) else { return Err(crate::GfxError::NameNotFound) };
+		Ok(id)
+	}
+
+	fn texture2darray_set_data(&mut self, id: crate::Texture2DArray, data: &[u8]) -> Result<(), crate::GfxError> {
+		let Some(array) = self.arrays.get_mut(id) else { return Err(crate::GfxError::InvalidTexture2DArrayHandle) };
+		let Some(data) = data.get(..array.pixels.len()) else { return Err(crate::GfxError::IndexOutOfBounds) };
+		array.pixels.copy_from_slice(data);
+		Ok(())
+	}
+
+	fn texture2darray_set_layer(&mut self, id: crate::Texture2DArray, layer: i32, data: &[u8]) -> Result<(), crate::GfxError> {
+		let Some(array) = self.arrays.get_mut(id) else { return Err(crate::GfxError::InvalidTexture2DArrayHandle) };
+		if layer < 0 || layer >= array.info.layers {
+			return Err(crate::GfxError::IndexOutOfBounds);
+		}
+		let size = (array.info.width * array.info.height * 4) as usize;
+		let offset = layer as usize * size;
+		let Some(data) = data.get(..size) else { return Err(crate::GfxError::IndexOutOfBounds) };
+		array.pixels[offset..offset + size].copy_from_slice(data);
+		Ok(())
+	}
+
+	fn texture2darray_get_info(&mut self, id: crate::Texture2DArray) -> Result<crate::Texture2DArrayInfo, crate::GfxError> {
+		let Some(array) = self.arrays.get(id) else { return Err(crate::GfxError::InvalidTexture2DArrayHandle) };
+		Ok(array.info)
+	}
+
+	fn texture2darray_delete(&mut self, id: crate::Texture2DArray, free_handle: bool) -> Result<(), crate::GfxError> {
+		let Some(_) = self.arrays.remove(id, free_handle) else { return Err(crate::GfxError::InvalidTexture2DArrayHandle) };
+		Ok(())
+	}
+
+	fn surface_create(&mut self, name: Option<&str>, info: &crate::SurfaceInfo) -> Result<crate::Surface, crate::GfxError> {
+		let fb = Framebuffer::new(info.width, info.height);
+		let texture = self.textures.insert(None, SoftTexture2D {
+			info: crate::Texture2DInfo {
+				width: fb.width,
+				height: fb.height,
+				..Default::default()
+			},
+			pixels: fb.color,
+			mips: Vec::new(),
+		});
+		let depth = if info.has_depth { fb.depth } else { Vec::new() };
+		let id = self.surfaces.insert(name, SoftSurface { texture, depth, info: *info });
+		Ok(id)
+	}
+
+	fn surface_find(&mut self, name: &str) -> Result<crate::Surface, crate::GfxError> {
+		let Some(id) = self.surfaces.find_id(name) else { return Err(crate::GfxError::NameNotFound) };
+		Ok(id)
+	}
+
+	fn surface_get_info(&mut self, id: crate::Surface) -> Result<crate::SurfaceInfo, crate::GfxError> {
+		let Some(surface) = self.surfaces.get(id) else { return Err(crate::GfxError::InvalidSurfaceHandle) };
+		Ok(surface.info)
+	}
+
+	fn surface_set_info(&mut self, _id: crate::Surface, _info: &crate::SurfaceInfo) -> Result<(), crate::GfxError> {
+		Err(crate::GfxError::Unsupported("surface_set_info"))
+	}
+
+	fn surface_get_texture(&mut self, id: crate::Surface) -> Result<crate::Texture2D, crate::GfxError> {
+		let Some(surface) = self.surfaces.get(id) else { return Err(crate::GfxError::InvalidSurfaceHandle) };
+		Ok(surface.texture)
+	}
+
+	fn blit(&mut self, src: crate::Surface, src_rect: &cvmath::Rect<i32>, dst: crate::Surface, dst_rect: &cvmath::Rect<i32>, filter: crate::TextureFilter) -> Result<(), crate::GfxError> {
+		let (src_width, src_height) = self.size(src)?;
+		let source = SoftTexture2D {
+			info: crate::Texture2DInfo {
+				width: src_width,
+				height: src_height,
+				filter_mag: filter,
+				..Default::default()
+			},
+			pixels: self.pixels(src)?.to_vec(),
+			mips: Vec::new(),
+		};
+		let mut fb = self.take_framebuffer(dst)?;
+		let (dst_width, dst_height) = (dst_rect.width(), dst_rect.height());
+		for y in 0..dst_height {
+			for x in 0..dst_width {
+				let (dx, dy) = (dst_rect.mins.x + x, dst_rect.mins.y + y);
+				if dx < 0 || dy < 0 || dx >= fb.width || dy >= fb.height {
+					continue;
+				}
+				// Map the pixel center to the source rectangle
+				let u = (src_rect.mins.x as f32 + (x as f32 + 0.5) / dst_width as f32 * src_rect.width() as f32) / src_width as f32;
+				let v = (src_rect.mins.y as f32 + (y as f32 + 0.5) / dst_height as f32 * src_rect.height() as f32) / src_height as f32;
+				let color = source.sample([u, v], 0.0).map(raster::to_u8);
+				let index = ((dy * fb.width + dx) * 4) as usize;
+				fb.color[index..index + 4].copy_from_slice(&color);
+			}
+		}
+		self.put_framebuffer(dst, fb);
+		Ok(())
+	}
+
+	fn surface_delete(&mut self, id: crate::Surface, free_handle: bool) -> Result<(), crate::GfxError> {
+		let Some(surface) = self.surfaces.remove(id, free_handle) else { return Err(crate::GfxError::InvalidSurfaceHandle) };
+		self.texture2d_delete(surface.texture, free_handle)?;
+		Ok(())
+	}
+
+	fn frame_fence(&mut self) -> Result<crate::Fence, crate::GfxError> {
+		let id = self.fences.insert(None, SoftFence);
+		Ok(id)
+	}
+
+	fn fence_wait(&mut self, id: crate::Fence, _timeout_ns: u64) -> Result<bool, crate::GfxError> {
+		// Drawing is complete when the draw call returns
+		let Some(_) = self.fences.get(id) else { return Err(crate::GfxError::InvalidFenceHandle) };
+		Ok(true)
+	}
+
+	fn fence_delete(&mut self, id: crate::Fence) -> Result<(), crate::GfxError> {
+		let Some(_) = self.fences.remove(id, true) else { return Err(crate::GfxError::InvalidFenceHandle) };
+		Ok(())
+	}
+}
+
+impl ops::Deref for SoftGraphics {
+	type Target = crate::Graphics;
+
+	#[inline]
+	fn deref(&self) -> &crate::Graphics {
+		unsafe { mem::transmute(self as &dyn crate::IGraphics) }
+	}
+}
+impl ops::DerefMut for SoftGraphics {
+	#[inline]
+	fn deref_mut(&mut self) -> &mut crate::Graphics {
+		crate::Graphics(self)
+	}
+}
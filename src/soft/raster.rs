@@ -0,0 +1,497 @@
+use super::*;
+
+/// Interpolated vertex outputs in window coordinates.
+#[derive(Copy, Clone, Debug)]
+pub(super) struct Varying {
+	x: f32,
+	y: f32,
+	z: f32,
+	inv_w: f32,
+	color: [f32; 4],
+	uv: [f32; 2],
+}
+
+/// Vertex transform read from the `u_transform` uniform.
+#[derive(Copy, Clone, Debug)]
+pub(super) enum Transform {
+	Identity,
+	/// Row-major 3x2 affine matrix.
+	Affine([f32; 6]),
+	/// Row-major 4x4 matrix.
+	Projective([f32; 16]),
+}
+
+/// Index of the vertex which restarts strip, loop and fan primitives.
+pub(super) const RESTART: usize = usize::MAX;
+
+/// Color buffer and depth buffer being drawn to.
+pub(super) struct Target<'a> {
+	pub color: &'a mut [u8],
+	pub depth: Option<&'a mut [f32]>,
+	pub width: i32,
+	pub height: i32,
+}
+
+/// Fixed function pipeline state of a draw call.
+pub(super) struct Pipeline<'a> {
+	pub vertices: &'a [u8],
+	pub layout: &'static crate::VertexLayout,
+	pub transform: Transform,
+	pub texture: Option<&'a SoftTexture2D>,
+	pub viewport: cvmath::Rect<i32>,
+	/// Clip rectangle as minimum inclusive and maximum exclusive pixel coordinates.
+	pub clip: [i32; 4],
+	pub blend_mode: crate::BlendMode,
+	pub depth_test: Option<crate::DepthTest>,
+	pub cull_mode: Option<crate::CullMode>,
+	pub fill_mode: crate::FillMode,
+}
+
+impl<'a> Pipeline<'a> {
+	/// Transforms the vertex at the index, returns `None` if it is out of bounds or behind the camera.
+	fn shade(&self, index: usize) -> Option<Varying> {
+		let stride = self.layout.size as usize;
+		let vertex = self.vertices.get(index * stride..index * stride + stride)?;
+		let attributes = self.layout.attributes;
+		let pos = decode(vertex, attributes.first()?);
+
+		// The color is the first normalized four component attribute, the texture coordinates the first two component float attribute
+		let color = attributes.iter().skip(1)
+			.find(|attr| attr.len == 4 && matches!(attr.format, crate::VertexAttributeFormat::U8Norm | crate::VertexAttributeFormat::U16Norm))
+			.map_or([1.0; 4], |attr| decode(vertex, attr));
+		let uv = attributes.iter().skip(1)
+			.find(|attr| attr.len == 2 && matches!(attr.format, crate::VertexAttributeFormat::F32))
+			.map_or([0.0; 2], |attr| { let v = decode(vertex, attr); [v[0], v[1]] });
+
+		let clip = match self.transform {
+			Transform::Identity => pos,
+			Transform::Affine(m) => [
+				m[0] * pos[0] + m[1] * pos[1] + m[2],
+				m[3] * pos[0] + m[4] * pos[1] + m[5],
+				pos[2],
+				pos[3],
+			],
+			Transform::Projective(m) => std::array::from_fn(|r| {
+				m[r * 4] * pos[0] + m[r * 4 + 1] * pos[1] + m[r * 4 + 2] * pos[2] + m[r * 4 + 3] * pos[3]
+			}),
+		};
+
+		// Primitives crossing the near plane are not clipped
+		if clip[3].is_nan() || clip[3] <= 1e-6 {
+			return None;
+		}
+		let inv_w = 1.0 / clip[3];
+		let vp = self.viewport;
+		Some(Varying {
+			x: vp.mins.x as f32 + (clip[0] * inv_w + 1.0) * 0.5 * vp.width() as f32,
+			y: vp.mins.y as f32 + (clip[1] * inv_w + 1.0) * 0.5 * vp.height() as f32,
+			z: (clip[2] * inv_w + 1.0) * 0.5,
+			inv_w,
+			color,
+			uv,
+		})
+	}
+
+	/// Assembles and rasterizes the primitives of the vertex indices.
+	///
+	/// The [`RESTART`] index starts a new strip, loop or fan.
+	pub fn draw(&self, target: &mut Target, prim_type: crate::PrimType, indices: &[usize]) {
+		if !prim_type.is_list() && indices.contains(&RESTART) {
+			for indices in indices.split(|&index| index == RESTART) {
+				self.draw(target, prim_type, indices);
+			}
+			return;
+		}
+		let verts: Vec<Option<Varying>> = indices.iter().map(|&index| self.shade(index)).collect();
+		let n = verts.len();
+		match prim_type {
+			crate::PrimType::Triangles => {
+				for tri in verts.chunks_exact(3) {
+					self.triangle(target, tri[0], tri[1], tri[2]);
+				}
+			}
+			crate::PrimType::TriangleStrip => {
+				for i in 0..n.saturating_sub(2) {
+					// Keep the winding of every other triangle
+					if i % 2 == 0 {
+						self.triangle(target, verts[i], verts[i + 1], verts[i + 2]);
+					}
+					else {
+						self.triangle(target, verts[i + 1], verts[i], verts[i + 2]);
+					}
+				}
+			}
+			crate::PrimType::TriangleFan => {
+				for i in 1..n.saturating_sub(1) {
+					self.triangle(target, verts[0], verts[i], verts[i + 1]);
+				}
+			}
+			crate::PrimType::Lines => {
+				for line in verts.chunks_exact(2) {
+					self.line(target, line[0], line[1]);
+				}
+			}
+			crate::PrimType::LineStrip => {
+				for line in verts.windows(2) {
+					self.line(target, line[0], line[1]);
+				}
+			}
+			crate::PrimType::LineLoop => {
+				for line in verts.windows(2) {
+					self.line(target, line[0], line[1]);
+				}
+				if n > 2 {
+					self.line(target, verts[n - 1], verts[0]);
+				}
+			}
+			crate::PrimType::Points => {
+				for &v in &verts {
+					self.point(target, v);
+				}
+			}
+		}
+	}
+
+	fn triangle(&self, target: &mut Target, a: Option<Varying>, b: Option<Varying>, c: Option<Varying>) {
+		let (Some(a), Some(mut b), Some(mut c)) = (a, b, c) else { return };
+		let area = edge(&a, &b, c.x, c.y);
+		if area.is_nan() || area == 0.0 {
+			return;
+		}
+		// Counter-clockwise triangles have a positive area with the y axis pointing up
+		match self.cull_mode {
+			Some(crate::CullMode::CCW) if area > 0.0 => return,
+			Some(crate::CullMode::CW) if area < 0.0 => return,
+			_ => (),
+		}
+		match self.fill_mode {
+			crate::FillMode::Solid => (),
+			crate::FillMode::Wireframe => {
+				self.line(target, Some(a), Some(b));
+				self.line(target, Some(b), Some(c));
+				self.line(target, Some(c), Some(a));
+				return;
+			}
+			crate::FillMode::Points => {
+				self.point(target, Some(a));
+				self.point(target, Some(b));
+				self.point(target, Some(c));
+				return;
+			}
+		}
+		if area < 0.0 {
+			mem::swap(&mut b, &mut c);
+		}
+		let area = area.abs();
+
+		// Level of detail from the texels covered per pixel, the derivatives are constant over the triangle
+		let lod = self.texture.map_or(0.0, |texture| {
+			let (width, height) = (texture.info.width as f32, texture.info.height as f32);
+			let (du1, dv1) = ((b.uv[0] - a.uv[0]) * width, (b.uv[1] - a.uv[1]) * height);
+			let (du2, dv2) = ((c.uv[0] - a.uv[0]) * width, (c.uv[1] - a.uv[1]) * height);
+			0.5 * ((du1 * dv2 - du2 * dv1).abs() / area).log2()
+		});
+
+		let x0 = cmp::max(self.clip[0], a.x.min(b.x).min(c.x).floor() as i32);
+		let y0 = cmp::max(self.clip[1], a.y.min(b.y).min(c.y).floor() as i32);
+		let x1 = cmp::min(self.clip[2], a.x.max(b.x).max(c.x).ceil() as i32);
+		let y1 = cmp::min(self.clip[3], a.y.max(b.y).max(c.y).ceil() as i32);
+
+		// Pixels on a shared edge are drawn by one triangle only
+		let (tl_a, tl_b, tl_c) = (is_top_left(&b, &c), is_top_left(&c, &a), is_top_left(&a, &b));
+		let inside = |w: f32, top_left: bool| w > 0.0 || (w == 0.0 && top_left);
+
+		for py in y0..y1 {
+			for px in x0..x1 {
+				let (cx, cy) = (px as f32 + 0.5, py as f32 + 0.5);
+				let wa = edge(&b, &c, cx, cy) / area;
+				let wb = edge(&c, &a, cx, cy) / area;
+				let wc = edge(&a, &b, cx, cy) / area;
+				if !(inside(wa, tl_a) && inside(wb, tl_b) && inside(wc, tl_c)) {
+					continue;
+				}
+				let z = wa * a.z + wb * b.z + wc * c.z;
+				// Perspective correct interpolation
+				let inv_w = wa * a.inv_w + wb * b.inv_w + wc * c.inv_w;
+				let (pa, pb, pc) = (wa * a.inv_w / inv_w, wb * b.inv_w / inv_w, wc * c.inv_w / inv_w);
+				let color = std::array::from_fn(|i| pa * a.color[i] + pb * b.color[i] + pc * c.color[i]);
+				let uv = std::array::from_fn(|i| pa * a.uv[i] + pb * b.uv[i] + pc * c.uv[i]);
+				self.fragment(target, [px, py], z, color, uv, lod);
+			}
+		}
+	}
+
+	fn line(&self, target: &mut Target, a: Option<Varying>, b: Option<Varying>) {
+		let (Some(a), Some(b)) = (a, b) else { return };
+		// Clip the segment first so the number of steps is bounded by the clip rectangle
+		let Some(((t0, [x0, y0]), (t1, [x1, y1]))) = clip_segment(&self.clip, &a, &b) else { return };
+		// Step between the clipped end points, stepping the whole segment loses the precision for far away end points
+		let lerp = |p: f32, q: f32, t: f32| p + (q - p) * t;
+		let steps = f32::max((x1 - x0).abs(), (y1 - y0).abs()).ceil();
+		if !steps.is_finite() {
+			return;
+		}
+		// The last pixel is left out so connected lines do not overlap
+		let steps = f32::max(1.0, steps) as i32;
+		for i in 0..steps {
+			let s = i as f32 / steps as f32;
+			let t = lerp(t0, t1, s);
+			let color = std::array::from_fn(|c| lerp(a.color[c], b.color[c], t));
+			let uv = std::array::from_fn(|c| lerp(a.uv[c], b.uv[c], t));
+			let (px, py) = (lerp(x0, x1, s).floor() as i32, lerp(y0, y1, s).floor() as i32);
+			if px >= self.clip[0] && px < self.clip[2] && py >= self.clip[1] && py < self.clip[3] {
+				self.fragment(target, [px, py], lerp(a.z, b.z, t), color, uv, 0.0);
+			}
+		}
+	}
+
+	fn point(&self, target: &mut Target, v: Option<Varying>) {
+		let Some(v) = v else { return };
+		let (px, py) = (v.x.floor() as i32, v.y.floor() as i32);
+		if px >= self.clip[0] && px < self.clip[2] && py >= self.clip[1] && py < self.clip[3] {
+			self.fragment(target, [px, py], v.z, v.color, v.uv, 0.0);
+		}
+	}
+
+	fn fragment(&self, target: &mut Target, [x, y]: [i32; 2], z: f32, color: [f32; 4], uv: [f32; 2], lod: f32) {
+		// Clip against the near and far planes
+		if !(0.0..=1.0).contains(&z) {
+			return;
+		}
+		if x < 0 || y < 0 || x >= target.width || y >= target.height {
+			return;
+		}
+		let index = (y * target.width + x) as usize;
+		if let (Some(test), Some(depth)) = (self.depth_test, target.depth.as_deref_mut()) {
+			if !depth_pass(test, z, depth[index]) {
+				return;
+			}
+			depth[index] = z;
+		}
+		let src = match self.texture {
+			Some(texture) => {
+				let texel = texture.sample(uv, lod);
+				std::array::from_fn(|i| color[i] * texel[i])
+			}
+			None => color,
+		};
+		let pixel = &mut target.color[index * 4..index * 4 + 4];
+		let dst = std::array::from_fn(|i| pixel[i] as f32 / 255.0);
+		let result = blend(self.blend_mode, src, dst);
+		for (out, value) in pixel.iter_mut().zip(result) {
+			*out = to_u8(value);
+		}
+	}
+}
+
+impl SoftTexture2D {
+	/// Samples the texture with its wrap modes and filters.
+	///
+	/// The level of detail is the log2 of the texels per pixel, the magnification filter is used below zero.
+	pub(super) fn sample(&self, uv: [f32; 2], lod: f32) -> [f32; 4] {
+		let (width, height) = (self.info.width, self.info.height);
+		if width <= 0 || height <= 0 || self.pixels.len() < (width * height * 4) as usize {
+			return [0.0, 0.0, 0.0, 1.0];
+		}
+		if lod.is_nan() || lod <= 0.0 {
+			return self.filter(0, self.info.filter_mag.base(), uv);
+		}
+		let filter = self.info.filter_min.base();
+		let lod = f32::min(lod, self.mips.len() as f32);
+		match self.info.filter_min {
+			crate::TextureFilter::Nearest | crate::TextureFilter::Linear => self.filter(0, filter, uv),
+			crate::TextureFilter::NearestMipmapNearest | crate::TextureFilter::LinearMipmapNearest => self.filter(lod.round() as usize, filter, uv),
+			crate::TextureFilter::NearestMipmapLinear | crate::TextureFilter::LinearMipmapLinear => {
+				let level = lod.floor();
+				let t = lod - level;
+				let a = self.filter(level as usize, filter, uv);
+				if t <= 0.0 {
+					return a;
+				}
+				let b = self.filter(level as usize + 1, filter, uv);
+				std::array::from_fn(|i| a[i] + (b[i] - a[i]) * t)
+			}
+		}
+	}
+
+	fn filter(&self, level: usize, filter: crate::TextureFilter, uv: [f32; 2]) -> [f32; 4] {
+		let (width, height, pixels) = self.level(level);
+		let x = uv[0] * width as f32;
+		let y = uv[1] * height as f32;
+		match filter {
+			crate::TextureFilter::Linear => {
+				let (x, y) = (x - 0.5, y - 0.5);
+				let (x0, y0) = (x.floor(), y.floor());
+				let (fx, fy) = (x - x0, y - y0);
+				let (x0, y0) = (x0 as i32, y0 as i32);
+				let texel = |x, y| self.texel(width, height, pixels, x, y);
+				let (t00, t10, t01, t11) = (texel(x0, y0), texel(x0 + 1, y0), texel(x0, y0 + 1), texel(x0 + 1, y0 + 1));
+				std::array::from_fn(|i| {
+					let top = t00[i] + (t10[i] - t00[i]) * fx;
+					let bottom = t01[i] + (t11[i] - t01[i]) * fx;
+					top + (bottom - top) * fy
+				})
+			}
+			_ => self.texel(width, height, pixels, x.floor() as i32, y.floor() as i32),
+		}
+	}
+
+	/// Returns the size and pixels of the mip level.
+	fn level(&self, level: usize) -> (i32, i32, &[u8]) {
+		match level.checked_sub(1).and_then(|index| self.mips.get(index)) {
+			Some(pixels) => (i32::max(1, self.info.width >> level), i32::max(1, self.info.height >> level), pixels),
+			None => (self.info.width, self.info.height, &self.pixels),
+		}
+	}
+
+	fn texel(&self, width: i32, height: i32, pixels: &[u8], x: i32, y: i32) -> [f32; 4] {
+		let (Some(x), Some(y)) = (wrap(self.info.wrap_u, x, width), wrap(self.info.wrap_v, y, height)) else {
+			return self.info.border_color.map(|c| c as f32 / 255.0);
+		};
+		let index = ((y * width + x) * 4) as usize;
+		std::array::from_fn(|i| pixels[index + i] as f32 / 255.0)
+	}
+
+	/// Generates the mip levels from the base level with a box filter.
+	pub(super) fn generate_mips(&mut self) {
+		self.mips.clear();
+		let (mut width, mut height) = (self.info.width as usize, self.info.height as usize);
+		if self.info.width <= 0 || self.info.height <= 0 || self.pixels.len() < width * height * 4 {
+			return;
+		}
+		let mut image = self.pixels[..width * height * 4].to_vec();
+		for _ in 1..self.info.mip_levels.count(self.info.width, self.info.height) {
+			(width, height) = crate::TextureQuality::Half.downscale(&mut image, width, height);
+			self.mips.push(image.clone());
+		}
+	}
+}
+
+/// Wraps the texel coordinate, `None` selects the border color.
+fn wrap(mode: crate::TextureWrap, x: i32, size: i32) -> Option<i32> {
+	match mode {
+		crate::TextureWrap::ClampEdge => Some(x.clamp(0, size - 1)),
+		crate::TextureWrap::ClampBorder => if x >= 0 && x < size { Some(x) } else { None },
+		crate::TextureWrap::Repeat => Some(x.rem_euclid(size)),
+		crate::TextureWrap::Mirror => {
+			let x = x.rem_euclid(size * 2);
+			Some(if x < size { x } else { size * 2 - 1 - x })
+		}
+	}
+}
+
+/// Parameter along the segment and position of a clipped end point.
+type ClipPoint = (f32, [f32; 2]);
+
+/// Clips the segment to the clip rectangle, returns the parameters and positions of the visible part.
+///
+/// An end point on a clip edge is snapped to the edge, the parameter alone loses the precision for far away end points.
+fn clip_segment(clip: &[i32; 4], a: &Varying, b: &Varying) -> Option<(ClipPoint, ClipPoint)> {
+	let (dx, dy) = (b.x - a.x, b.y - a.y);
+	let at = |t: f32, axis: usize, edge: i32| {
+		let mut pt = [a.x + dx * t, a.y + dy * t];
+		pt[axis] = edge as f32;
+		(t, pt)
+	};
+	let mut start = (0.0f32, [a.x, a.y]);
+	let mut end = (1.0f32, [b.x, b.y]);
+	for (p, q, axis, edge) in [
+		(-dx, a.x - clip[0] as f32, 0, clip[0]),
+		(dx, clip[2] as f32 - a.x, 0, clip[2]),
+		(-dy, a.y - clip[1] as f32, 1, clip[1]),
+		(dy, clip[3] as f32 - a.y, 1, clip[3]),
+	] {
+		if p == 0.0 {
+			// Parallel to the edge and outside
+			if q < 0.0 {
+				return None;
+			}
+		}
+		else if p < 0.0 {
+			if q / p > start.0 {
+				start = at(q / p, axis, edge);
+			}
+		}
+		else if q / p < end.0 {
+			end = at(q / p, axis, edge);
+		}
+	}
+	if start.0 > end.0 {
+		return None;
+	}
+	Some((start, end))
+}
+
+fn edge(a: &Varying, b: &Varying, x: f32, y: f32) -> f32 {
+	(b.x - a.x) * (y - a.y) - (b.y - a.y) * (x - a.x)
+}
+
+/// Top and left edges of a counter-clockwise triangle with the y axis pointing up.
+#[inline]
+fn is_top_left(a: &Varying, b: &Varying) -> bool {
+	(a.y == b.y && b.x < a.x) || b.y < a.y
+}
+
+fn depth_pass(test: crate::DepthTest, z: f32, depth: f32) -> bool {
+	match test {
+		crate::DepthTest::Never => false,
+		crate::DepthTest::Less => z < depth,
+		crate::DepthTest::Equal => z == depth,
+		crate::DepthTest::NotEqual => z != depth,
+		crate::DepthTest::LessEqual => z <= depth,
+		crate::DepthTest::Greater => z > depth,
+		crate::DepthTest::GreaterEqual => z >= depth,
+		crate::DepthTest::Always => true,
+	}
+}
+
+/// Blends the colors with the same factors as the OpenGL backend.
+fn blend(mode: crate::BlendMode, s: [f32; 4], d: [f32; 4]) -> [f32; 4] {
+	let sa = s[3];
+	match mode {
+		crate::BlendMode::Solid => s,
+		crate::BlendMode::Alpha => std::array::from_fn(|i| s[i] * sa + d[i] * (1.0 - sa)),
+		crate::BlendMode::PremultipliedAlpha => std::array::from_fn(|i| s[i] + d[i] * (1.0 - sa)),
+		crate::BlendMode::Additive => std::array::from_fn(|i| s[i] + d[i]),
+		crate::BlendMode::Lighten => std::array::from_fn(|i| f32::max(s[i], d[i])),
+		crate::BlendMode::Screen => std::array::from_fn(|i| s[i] + d[i] * (1.0 - s[i])),
+		crate::BlendMode::Darken => std::array::from_fn(|i| f32::min(s[i], d[i])),
+		crate::BlendMode::Multiply => std::array::from_fn(|i| s[i] * d[i]),
+	}
+}
+
+/// Decodes a vertex attribute, missing components default to `(0, 0, 0, 1)`.
+fn decode(vertex: &[u8], attr: &crate::VertexAttribute) -> [f32; 4] {
+	use crate::VertexAttributeFormat as F;
+	let size = match attr.format {
+		F::F64 => 8,
+		F::F32 | F::I32 | F::U32 => 4,
+		F::I16 | F::U16 | F::I16Norm | F::U16Norm => 2,
+		F::I8 | F::U8 | F::I8Norm | F::U8Norm => 1,
+	};
+	let mut result = [0.0, 0.0, 0.0, 1.0];
+	for (i, out) in result.iter_mut().enumerate().take(attr.len as usize) {
+		let start = attr.offset as usize + i * size;
+		let Some(b) = vertex.get(start..start + size) else { break };
+		*out = match attr.format {
+			F::F64 => f64::from_ne_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]) as f32,
+			F::F32 => f32::from_ne_bytes([b[0], b[1], b[2], b[3]]),
+			F::I32 => i32::from_ne_bytes([b[0], b[1], b[2], b[3]]) as f32,
+			F::U32 => u32::from_ne_bytes([b[0], b[1], b[2], b[3]]) as f32,
+			F::I16 => i16::from_ne_bytes([b[0], b[1]]) as f32,
+			F::U16 => u16::from_ne_bytes([b[0], b[1]]) as f32,
+			F::I8 => b[0] as i8 as f32,
+			F::U8 => b[0] as f32,
+			F::I16Norm => f32::max(-1.0, i16::from_ne_bytes([b[0], b[1]]) as f32 / 32767.0),
+			F::U16Norm => u16::from_ne_bytes([b[0], b[1]]) as f32 / 65535.0,
+			F::I8Norm => f32::max(-1.0, b[0] as i8 as f32 / 127.0),
+			F::U8Norm => b[0] as f32 / 255.0,
+		};
+	}
+	result
+}
+
+#[inline]
+pub(super) fn to_u8(value: f32) -> u8 {
+	(value.clamp(0.0, 1.0) * 255.0 + 0.5) as u8
+}
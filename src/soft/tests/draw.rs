@@ -0,0 +1,219 @@
+use super::*;
+
+#[test]
+fn triangles() {
+	let (mut soft, shader, uniforms) = setup();
+	let g = &mut *soft;
+	let vertices = g.vertex_buffer(None, &[
+		vertex(0.0, 0.0, 0.0, RED),
+		vertex(8.0, 0.0, 0.0, RED),
+		vertex(0.0, 8.0, 0.0, RED),
+	], BufferUsage::Static).unwrap();
+	g.draw(&draw_args(PrimType::Triangles, shader, vertices, uniforms, 3)).unwrap();
+	g.end().unwrap();
+
+	// The pixel centers on the diagonal are on the right edge of the triangle and left out
+
+	assert_eq!(coverage(&soft, RED), "\
+		........\n\
+		#.......\n\
+		##......\n\
+		###.....\n\
+		####....\n\
+		#####...\n\
+		######..\n\
+		#######.\n");
+}
+
+#[test]
+fn triangle_strip_culling() {
+	let (mut soft, shader, uniforms) = setup();
+	let g = &mut *soft;
+	let vertices = g.vertex_buffer(None, &[
+		vertex(0.0, 0.0, 0.0, RED),
+		vertex(8.0, 0.0, 0.0, RED),
+		vertex(0.0, 8.0, 0.0, RED),
+		vertex(8.0, 8.0, 0.0, RED),
+	], BufferUsage::Static).unwrap();
+	// Every triangle of the strip is counter-clockwise
+	g.draw(&DrawArgs { cull_mode: Some(CullMode::CCW), ..draw_args(PrimType::TriangleStrip, shader, vertices, uniforms, 4) }).unwrap();
+	assert_eq!(soft.read_pixel(Surface::BACK_BUFFER, 0, 0), Some([0, 0, 0, 255]));
+	let g = &mut *soft;
+	g.draw(&DrawArgs { cull_mode: Some(CullMode::CW), ..draw_args(PrimType::TriangleStrip, shader, vertices, uniforms, 4) }).unwrap();
+	g.end().unwrap();
+
+	assert_eq!(coverage(&soft, RED), "########\n".repeat(8));
+}
+
+#[test]
+fn triangle_fan_restart() {
+	let (mut soft, shader, uniforms) = setup();
+	let g = &mut *soft;
+	let vertices = g.vertex_buffer(None, &[
+		vertex(0.0, 0.0, 0.0, RED),
+		vertex(4.0, 0.0, 0.0, RED),
+		vertex(4.0, 4.0, 0.0, RED),
+		vertex(0.0, 4.0, 0.0, RED),
+		vertex(8.0, 8.0, 0.0, RED),
+		vertex(4.0, 8.0, 0.0, RED),
+		vertex(8.0, 4.0, 0.0, RED),
+	], BufferUsage::Static).unwrap();
+	let indices = g.index_buffer(None, &[0, 1, 2, 3, u32::MAX, 4, 5, 6], BufferUsage::Static).unwrap();
+	g.draw_indexed(&DrawIndexedArgs {
+		surface: Surface::BACK_BUFFER,
+		viewport: Rect::c(0, 0, 8, 8),
+		scissor: None,
+		blend_mode: BlendMode::Solid,
+		depth_test: None,
+		cull_mode: None,
+		fill_mode: FillMode::Solid,
+		alpha_to_coverage: false,
+		stencil: None,
+		prim_type: PrimType::TriangleFan,
+		shader,
+		vertices,
+		indices,
+		uniforms,
+		vertex_start: 0,
+		vertex_end: 7,
+		index_start: 0,
+		index_end: 8,
+		base_vertex: 0,
+		uniform_index: 0,
+		instances: -1,
+	}).unwrap();
+	g.end().unwrap();
+
+	// The restart index separates the quad from the triangle
+	assert_eq!(coverage(&soft, RED), "\
+		....####\n\
+		.....###\n\
+		......##\n\
+		.......#\n\
+		####....\n\
+		####....\n\
+		####....\n\
+		####....\n");
+}
+
+#[test]
+fn lines() {
+	let (mut soft, shader, uniforms) = setup();
+	let g = &mut *soft;
+	let vertices = g.vertex_buffer(None, &[
+		vertex(0.0, 1.5, 0.0, RED),
+		vertex(8.0, 1.5, 0.0, RED),
+		// Far outside the viewport, clipped before stepping
+		vertex(-1e30, 5.5, 0.0, RED),
+		vertex(1e30, 5.5, 0.0, RED),
+		vertex(6.5, -1e30, 0.0, RED),
+		vertex(6.5, 1e30, 0.0, RED),
+	], BufferUsage::Static).unwrap();
+	g.draw(&draw_args(PrimType::Lines, shader, vertices, uniforms, 6)).unwrap();
+	g.end().unwrap();
+
+	assert_eq!(coverage(&soft, RED), "\
+		......#.\n\
+		......#.\n\
+		########\n\
+		......#.\n\
+		......#.\n\
+		......#.\n\
+		########\n\
+		......#.\n");
+}
+
+#[test]
+fn points() {
+	let (mut soft, shader, uniforms) = setup();
+	let g = &mut *soft;
+	let vertices = g.vertex_buffer(None, &[
+		vertex(0.5, 0.5, 0.0, RED),
+		vertex(7.5, 3.5, 0.0, RED),
+		vertex(9.5, 3.5, 0.0, RED),
+	], BufferUsage::Static).unwrap();
+	g.draw(&draw_args(PrimType::Points, shader, vertices, uniforms, 3)).unwrap();
+	g.end().unwrap();
+
+	assert_eq!(coverage(&soft, RED), "\
+		........\n\
+		........\n\
+		........\n\
+		........\n\
+		.......#\n\
+		........\n\
+		........\n\
+		#.......\n");
+}
+
+#[test]
+fn depth_test() {
+	let (mut soft, shader, uniforms) = setup();
+	let g = &mut *soft;
+	let quad = |z: f32, color: [u8; 4]| [
+		vertex(0.0, 0.0, z, color),
+		vertex(8.0, 0.0, z, color),
+		vertex(0.0, 8.0, z, color),
+		vertex(8.0, 8.0, z, color),
+	];
+	let red = g.vertex_buffer(None, &quad(0.0, RED), BufferUsage::Static).unwrap();
+	let green = g.vertex_buffer(None, &quad(0.5, GREEN), BufferUsage::Static).unwrap();
+	let blue = g.vertex_buffer(None, &quad(-0.5, BLUE), BufferUsage::Static).unwrap();
+
+	let args = |vertices, depth_test| DrawArgs {
+		depth_test: Some(depth_test),
+		..draw_args(PrimType::TriangleStrip, shader, vertices, uniforms, 4)
+	};
+	g.draw(&args(red, DepthTest::Less)).unwrap();
+	// Behind the red quad
+	g.draw(&args(green, DepthTest::Less)).unwrap();
+	assert_eq!(coverage(&soft, RED), "########\n".repeat(8));
+
+	let g = &mut *soft;
+	g.draw(&args(green, DepthTest::Greater)).unwrap();
+	assert_eq!(coverage(&soft, GREEN), "########\n".repeat(8));
+
+	// In front of the green quad
+	let g = &mut *soft;
+	g.draw(&args(blue, DepthTest::LessEqual)).unwrap();
+	g.draw(&args(red, DepthTest::Never)).unwrap();
+	g.end().unwrap();
+	assert_eq!(coverage(&soft, BLUE), "########\n".repeat(8));
+}
+
+#[test]
+fn blending() {
+	let (mut soft, shader, uniforms) = setup();
+	let g = &mut *soft;
+	g.clear(&ClearArgs {
+		surface: Surface::BACK_BUFFER,
+		color: Some(Vec4(0.0, 0.0, 1.0, 1.0)),
+		..Default::default()
+	}).unwrap();
+	let half_red = [255, 0, 0, 128];
+	let vertices = g.vertex_buffer(None, &[
+		vertex(0.0, 0.0, 0.0, half_red),
+		vertex(8.0, 0.0, 0.0, half_red),
+		vertex(0.0, 8.0, 0.0, half_red),
+		vertex(8.0, 8.0, 0.0, half_red),
+	], BufferUsage::Static).unwrap();
+
+	let args = |blend_mode| DrawArgs {
+		blend_mode,
+		scissor: Some(Rect::c(0, 0, 1, 1)),
+		..draw_args(PrimType::TriangleStrip, shader, vertices, uniforms, 4)
+	};
+	g.draw(&args(BlendMode::Alpha)).unwrap();
+	assert_eq!(soft.read_pixel(Surface::BACK_BUFFER, 0, 0), Some([128, 0, 127, 191]));
+	// Outside the scissor rectangle
+	assert_eq!(soft.read_pixel(Surface::BACK_BUFFER, 1, 0), Some(BLUE));
+
+	let g = &mut *soft;
+	g.draw(&DrawArgs { scissor: Some(Rect::c(1, 0, 2, 1)), ..args(BlendMode::Additive) }).unwrap();
+	assert_eq!(soft.read_pixel(Surface::BACK_BUFFER, 1, 0), Some([255, 0, 255, 255]));
+
+	let g = &mut *soft;
+	g.draw(&DrawArgs { scissor: Some(Rect::c(2, 0, 3, 1)), ..args(BlendMode::Solid) }).unwrap();
+	g.end().unwrap();
+	assert_eq!(soft.read_pixel(Surface::BACK_BUFFER, 2, 0), Some(half_red));
+}
@@ -0,0 +1,94 @@
+use super::*;
+use crate::cvmath::{Rect, Vec4};
+use crate::*;
+
+mod draw;
+mod texture;
+mod unsupported;
+
+const RED: [u8; 4] = [255, 0, 0, 255];
+const GREEN: [u8; 4] = [0, 255, 0, 255];
+const BLUE: [u8; 4] = [0, 0, 255, 255];
+
+#[derive(Copy, Clone, Default, dataview::Pod)]
+#[repr(C)]
+struct MockVertex {
+	pos: [f32; 3],
+	color: [u8; 4],
+}
+
+unsafe impl TVertex for MockVertex {
+	const VERTEX_LAYOUT: &'static VertexLayout = &VertexLayout {
+		size: mem::size_of::<MockVertex>() as u16,
+		alignment: mem::align_of::<MockVertex>() as u16,
+		instance_divisor: 0,
+		attributes: &[
+			VertexAttribute { format: VertexAttributeFormat::F32, len: 3, offset: 0 },
+			VertexAttribute { format: VertexAttributeFormat::U8Norm, len: 4, offset: 12 },
+		],
+	};
+}
+
+#[derive(Copy, Clone, Default, dataview::Pod)]
+#[repr(C)]
+struct MockUniform {}
+
+unsafe impl TUniform for MockUniform {
+	const UNIFORM_LAYOUT: &'static UniformLayout = &UniformLayout { size: 0, alignment: 1, attributes: &[] };
+}
+
+/// Vertex in window coordinates of the 8x8 back buffer.
+fn vertex(x: f32, y: f32, z: f32, color: [u8; 4]) -> MockVertex {
+	MockVertex { pos: [x / 4.0 - 1.0, y / 4.0 - 1.0, z], color }
+}
+
+/// Creates an 8x8 back buffer cleared to black with a depth of 1.0, ready to draw.
+fn setup() -> (SoftGraphics, Shader, UniformBuffer) {
+	let mut soft = SoftGraphics::new(8, 8);
+	let g = &mut *soft;
+	let shader = g.shader_create(None).unwrap();
+	let uniforms = g.uniform_buffer(None, &[MockUniform {}]).unwrap();
+	g.begin().unwrap();
+	g.clear(&ClearArgs {
+		surface: Surface::BACK_BUFFER,
+		color: Some(Vec4(0.0, 0.0, 0.0, 1.0)),
+		depth: Some(1.0),
+		..Default::default()
+	}).unwrap();
+	(soft, shader, uniforms)
+}
+
+/// Draws all the vertices with the default state.
+fn draw_args(prim_type: PrimType, shader: Shader, vertices: VertexBuffer, uniforms: UniformBuffer, count: u32) -> DrawArgs {
+	DrawArgs {
+		surface: Surface::BACK_BUFFER,
+		viewport: Rect::c(0, 0, 8, 8),
+		scissor: None,
+		blend_mode: BlendMode::Solid,
+		depth_test: None,
+		cull_mode: None,
+		fill_mode: FillMode::Solid,
+		alpha_to_coverage: false,
+		stencil: None,
+		prim_type,
+		shader,
+		vertices,
+		uniforms,
+		vertex_start: 0,
+		vertex_end: count,
+		uniform_index: 0,
+		instances: -1,
+	}
+}
+
+/// Returns the pixels of the back buffer matching the color as a string, rows from top to bottom.
+fn coverage(soft: &SoftGraphics, color: [u8; 4]) -> String {
+	let mut s = String::new();
+	for y in (0..8).rev() {
+		for x in 0..8 {
+			s.push(if soft.read_pixel(Surface::BACK_BUFFER, x, y) == Some(color) { '#' } else { '.' });
+		}
+		s.push('\n');
+	}
+	s
+}
@@ -0,0 +1,106 @@
+use super::*;
+
+#[derive(Copy, Clone, Default, dataview::Pod)]
+#[repr(C)]
+struct TexVertex {
+	pos: [f32; 2],
+	uv: [f32; 2],
+}
+
+unsafe impl TVertex for TexVertex {
+	const VERTEX_LAYOUT: &'static VertexLayout = &VertexLayout {
+		size: mem::size_of::<TexVertex>() as u16,
+		alignment: mem::align_of::<TexVertex>() as u16,
+		instance_divisor: 0,
+		attributes: &[
+			VertexAttribute { format: VertexAttributeFormat::F32, len: 2, offset: 0 },
+			VertexAttribute { format: VertexAttributeFormat::F32, len: 2, offset: 8 },
+		],
+	};
+}
+
+#[derive(Copy, Clone, Default, dataview::Pod)]
+#[repr(C)]
+struct TexUniform {
+	texture: Texture2D,
+}
+
+unsafe impl TUniform for TexUniform {
+	const UNIFORM_LAYOUT: &'static UniformLayout = &UniformLayout {
+		size: mem::size_of::<TexUniform>() as u16,
+		alignment: mem::align_of::<TexUniform>() as u16,
+		attributes: &[UniformAttribute { name: "u_texture", ty: UniformType::Sampler2D(0), offset: 0, len: 1 }],
+	};
+}
+
+const WHITE: [u8; 4] = [255, 255, 255, 255];
+
+/// Draws a viewport filling quad with the 2x2 texture mapped to `0.0..uv`.
+fn draw_textured(wrap: TextureWrap, uv: f32) -> SoftGraphics {
+	let (mut soft, shader, _) = setup();
+	let g = &mut *soft;
+	let texture = g.texture2d_create(None, &Texture2DInfo {
+		width: 2,
+		height: 2,
+		filter_min: TextureFilter::Nearest,
+		filter_mag: TextureFilter::Nearest,
+		wrap_u: wrap,
+		wrap_v: wrap,
+		..Default::default()
+	}).unwrap();
+	g.texture2d_set_data(texture, &[RED, GREEN, BLUE, WHITE].concat()).unwrap();
+	let vertices = g.vertex_buffer(None, &[
+		TexVertex { pos: [-1.0, -1.0], uv: [0.0, 0.0] },
+		TexVertex { pos: [1.0, -1.0], uv: [uv, 0.0] },
+		TexVertex { pos: [-1.0, 1.0], uv: [0.0, uv] },
+		TexVertex { pos: [1.0, 1.0], uv: [uv, uv] },
+	], BufferUsage::Static).unwrap();
+	let uniforms = g.uniform_buffer(None, &[TexUniform { texture }]).unwrap();
+	g.draw(&draw_args(PrimType::TriangleStrip, shader, vertices, uniforms, 4)).unwrap();
+	g.end().unwrap();
+	soft
+}
+
+#[test]
+fn texture_sampling() {
+	let soft = draw_textured(TextureWrap::ClampEdge, 1.0);
+	// The first texel row is at the bottom
+	assert_eq!(soft.read_pixel(Surface::BACK_BUFFER, 1, 1), Some(RED));
+	assert_eq!(soft.read_pixel(Surface::BACK_BUFFER, 6, 1), Some(GREEN));
+	assert_eq!(soft.read_pixel(Surface::BACK_BUFFER, 1, 6), Some(BLUE));
+	assert_eq!(soft.read_pixel(Surface::BACK_BUFFER, 6, 6), Some(WHITE));
+	assert_eq!(coverage(&soft, RED), "\
+		........\n\
+		........\n\
+		........\n\
+		........\n\
+		####....\n\
+		####....\n\
+		####....\n\
+		####....\n");
+}
+
+#[test]
+fn texture_wrap() {
+	let soft = draw_textured(TextureWrap::Repeat, 2.0);
+	assert_eq!(coverage(&soft, RED), "\
+		........\n\
+		........\n\
+		##..##..\n\
+		##..##..\n\
+		........\n\
+		........\n\
+		##..##..\n\
+		##..##..\n");
+
+	let soft = draw_textured(TextureWrap::ClampEdge, 2.0);
+	assert_eq!(coverage(&soft, WHITE), "\
+		..######\n\
+		..######\n\
+		..######\n\
+		..######\n\
+		..######\n\
+		..######\n\
+		........\n\
+		........\n");
+}
@@ -0,0 +1,83 @@
+use super::*;
+
+#[test]
+fn draw_unsupported() {
+	let (mut soft, shader, uniforms) = setup();
+	let g = &mut *soft;
+	let vertices = g.vertex_buffer(None, &[vertex(0.5, 0.5, 0.0, RED)], BufferUsage::Static).unwrap();
+	let args = || draw_args(PrimType::Points, shader, vertices, uniforms, 1);
+
+	assert_eq!(g.draw(&DrawArgs { instances: 1, ..args() }), Err(GfxError::Unsupported("instancing")));
+	assert_eq!(g.draw(&DrawArgs { stencil: Some(StencilMode::Cover), ..args() }), Err(GfxError::Unsupported("stencil")));
+	assert_eq!(g.clear(&ClearArgs { color_int: Some(Vec4(1, 0, 0, 1)), ..Default::default() }), Err(GfxError::Unsupported("integer attachments")));
+	assert_eq!(g.clear(&ClearArgs { color_uint: Some(Vec4(1, 0, 0, 1)), ..Default::default() }), Err(GfxError::Unsupported("integer attachments")));
+	g.end().unwrap();
+
+	// Nothing was drawn by the failed calls
+	assert_eq!(soft.read_pixel(Surface::BACK_BUFFER, 0, 0), Some([0, 0, 0, 255]));
+
+	// Draw calls outside of begin and end
+	let g = &mut *soft;
+	assert_eq!(g.draw(&args()), Err(GfxError::InvalidDrawCallTime));
+}
+
+#[test]
+fn texture_data() {
+	let mut soft = SoftGraphics::new(8, 8);
+	let g = &mut *soft;
+	let texture = g.texture2d_create(None, &Texture2DInfo { width: 2, height: 2, ..Default::default() }).unwrap();
+	assert_eq!(g.texture2d_set_data(texture, &[0; 8]), Err(GfxError::IndexOutOfBounds));
+	assert_eq!(g.texture2d_set_data(Texture2D::INVALID, &[0; 16]), Err(GfxError::InvalidTexture2DHandle));
+}
+
+#[test]
+fn texture_unsupported() {
+	let mut soft = SoftGraphics::new(8, 8);
+	let g = &mut *soft;
+	let texture = g.texture2d_create(None, &Texture2DInfo {
+		format: TextureFormat::BC1,
+		width: 4,
+		height: 4,
+		..Default::default()
+	}).unwrap();
+	assert_eq!(g.texture2d_set_compressed_data(texture, 0, &[0; 8]), Err(GfxError::Unsupported("texture2d_set_compressed_data")));
+	assert_eq!(g.texture2d_set_compressed_data(Texture2D::INVALID, 0, &[]), Err(GfxError::InvalidTexture2DHandle));
+
+	let texture = g.texture2d_create(None, &Texture2DInfo {
+		format: TextureFormat::R16G16B16A16F,
+		width: 2,
+		height: 2,
+		..Default::default()
+	}).unwrap();
+	assert_eq!(g.texture2d_set_data(texture, &[0; 32]), Err(GfxError::Unsupported("texture2d_set_data with a format other than R8G8B8A8")));
+}
+
+#[test]
+fn texture_format_unsupported() {
+	let mut soft = SoftGraphics::new(8, 8);
+	let g = &mut *soft;
+	let info = TextureCubeInfo { format: TextureFormat::R16G16B16A16F, size: 2, ..Default::default() };
+	assert_eq!(g.texturecube_create(None, &info), Err(GfxError::Unsupported("texturecube_create with a format other than R8G8B8A8")));
+
+	let info = Texture3DInfo { format: TextureFormat::R32G32B32A32F, width: 2, height: 2, depth: 2, ..Default::default() };
+	assert_eq!(g.texture3d_create(None, &info), Err(GfxError::Unsupported("texture3d_create with a format other than R8G8B8A8")));
+
+	let info = Texture2DArrayInfo { format: TextureFormat::BC1, width: 4, height: 4, layers: 2, ..Default::default() };
+	assert_eq!(g.texture2darray_create(None, &info), Err(GfxError::Unsupported("texture2darray_create with a format other than R8G8B8A8")));
+}
+
+#[test]
+fn surface_unsupported() {
+	let mut soft = SoftGraphics::new(8, 8);
+	let g = &mut *soft;
+	let info = SurfaceInfo {
+		offscreen: true,
+		has_depth: false,
+		has_texture: false,
+		format: SurfaceFormat::R8G8B8A8,
+		depth_format: DepthFormat::D24,
+		width: 4,
+		height: 4,
+	};
+	assert_eq!(g.surface_set_info(Surface::BACK_BUFFER, &info), Err(GfxError::Unsupported("surface_set_info")));
+}
@@ -0,0 +1,222 @@
+/*!
+Progressive texture streaming.
+
+Textures are first uploaded as a small placeholder mip level and replaced by the higher resolution levels over the next frames.
+The uploads are limited by a per-frame budget in bytes so entering a texture-heavy area does not cause a hitch.
+*/
+
+use crate::{Graphics, GfxError, MipLevels, Texture2D, Texture2DInfo, TextureProps};
+
+/// Largest size of the placeholder level uploaded when a texture is inserted.
+pub const PLACEHOLDER_SIZE: i32 = 16;
+
+/// Identifies a texture in the streamer.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct StreamId(u64);
+
+struct Stream {
+	id: StreamId,
+	/// Info of the base level.
+	info: Texture2DInfo,
+	/// Data of the mip levels, starting with the base level.
+	///
+	/// The levels of uncompressed textures are 8-bit Rgba pixels down to the placeholder, the smaller mips are generated when uploading.
+	levels: Vec<Vec<u8>>,
+	/// Highest level streamed in, the top levels are dropped for the texture quality.
+	top: usize,
+	/// Level of the resident texture.
+	resident: usize,
+	texture: Texture2D,
+}
+
+impl Stream {
+	#[inline]
+	fn level_size(&self, level: usize) -> (i32, i32) {
+		(i32::max(1, self.info.width >> level), i32::max(1, self.info.height >> level))
+	}
+
+	/// Returns the number of bytes uploaded for the level.
+	fn upload_size(&self, level: usize) -> usize {
+		if self.info.format.is_compressed() {
+			self.levels[level..].iter().map(Vec::len).sum()
+		}
+		else {
+			self.levels[level].len()
+		}
+	}
+
+	/// Replaces the resident texture by a texture starting at the level.
+	fn upload(&mut self, g: &mut Graphics, level: usize) -> Result<(), GfxError> {
+		let mut info = self.info;
+		(info.width, info.height) = self.level_size(level);
+		let compressed = info.format.is_compressed();
+		if compressed {
+			info.mip_levels = MipLevels::Max((self.levels.len() - level) as u8);
+		}
+
+		let texture = g.texture2d_create(None, &info)?;
+		let result = if compressed {
+			self.levels[level..].iter().enumerate().try_for_each(|(i, data)| g.texture2d_set_compressed_data(texture, i as u32, data))
+		}
+		else {
+			g.texture2d_set_data(texture, &self.levels[level])
+		};
+		if let Err(err) = result {
+			let _ = g.texture2d_delete(texture, true);
+			return Err(err);
+		}
+
+		if self.texture != Texture2D::INVALID {
+			g.texture2d_delete(self.texture, true)?;
+		}
+		self.texture = texture;
+		self.resident = level;
+
+		// Free the data no longer uploaded, compressed textures upload the smaller levels again with every level
+		if level == self.top {
+			self.levels = Vec::new();
+		}
+		else if !compressed {
+			self.levels.truncate(level + 1);
+		}
+		Ok(())
+	}
+}
+
+/// Progressive texture streamer.
+///
+/// Inserting a texture uploads a placeholder no larger than [`PLACEHOLDER_SIZE`] right away.
+/// Call [`update`](TextureStreamer::update) once per frame, the next higher resolution mip levels are uploaded until the budget is used up.
+/// The top mip levels are never uploaded according to the [`texture_quality`](crate::texture_quality).
+///
+/// Every upload replaces the texture, look up the resident [`texture`](TextureStreamer::texture) when drawing.
+pub struct TextureStreamer {
+	/// Upload budget per frame in bytes.
+	pub budget: usize,
+	streams: Vec<Stream>,
+	next_id: u64,
+}
+
+impl TextureStreamer {
+	/// Creates a new streamer with the upload budget per frame in bytes.
+	pub fn new(budget: usize) -> TextureStreamer {
+		TextureStreamer {
+			budget,
+			streams: Vec::new(),
+			next_id: 0,
+		}
+	}
+
+	/// Inserts a texture with 8-bit Rgba pixels.
+	///
+	/// The lower resolution levels are downscaled from the pixels, the mip levels of the properties are generated for every uploaded level.
+	pub fn insert_rgba(&mut self, g: &mut Graphics, pixels: Vec<u8>, width: i32, height: i32, props: &TextureProps) -> Result<StreamId, GfxError> {
+		let (width, height) = (i32::max(1, width), i32::max(1, height));
+		if pixels.len() < width as usize * height as usize * 4 {
+			return Err(GfxError::IndexOutOfBounds);
+		}
+		let mut info = Texture2DInfo { width, height, ..Default::default() };
+		info.set_props(props);
+
+		let (mut w, mut h) = (width as usize, height as usize);
+		let mut levels = vec![pixels];
+		while w > PLACEHOLDER_SIZE as usize || h > PLACEHOLDER_SIZE as usize {
+			let (level, new_w, new_h) = crate::texture::halve(&levels[levels.len() - 1], w, h);
+			levels.push(level);
+			(w, h) = (new_w, new_h);
+		}
+		let placeholder = levels.len() - 1;
+		self.insert(g, info, levels, placeholder)
+	}
+
+	/// Inserts a compressed image.
+	///
+	/// The placeholder is the largest level no larger than [`PLACEHOLDER_SIZE`], the levels of the image are uploaded as the mip levels.
+	/// The [`mip_levels`](TextureProps::mip_levels) of the properties are ignored.
+	pub fn insert_compressed(&mut self, g: &mut Graphics, image: &crate::image::CompressedImage, props: &TextureProps) -> Result<StreamId, GfxError> {
+		if image.levels.is_empty() {
+			return Err(GfxError::IndexOutOfBounds);
+		}
+		let mut info = Texture2DInfo {
+			format: image.format,
+			width: i32::max(1, image.width),
+			height: i32::max(1, image.height),
+			..Default::default()
+		};
+		info.set_props(props);
+
+		let levels: Vec<Vec<u8>> = image.levels.iter().map(|level| level.to_vec()).collect();
+		let placeholder = (0..levels.len())
+			.find(|&level| (info.width >> level) <= PLACEHOLDER_SIZE && (info.height >> level) <= PLACEHOLDER_SIZE)
+			.unwrap_or(levels.len() - 1);
+		self.insert(g, info, levels, placeholder)
+	}
+
+	fn insert(&mut self, g: &mut Graphics, info: Texture2DInfo, levels: Vec<Vec<u8>>, placeholder: usize) -> Result<StreamId, GfxError> {
+		let top = usize::min(crate::texture_quality().mip_bias() as usize, placeholder);
+		let id = StreamId(self.next_id);
+		let mut stream = Stream { id, info, levels, top, resident: placeholder, texture: Texture2D::INVALID };
+		stream.upload(g, placeholder)?;
+		self.next_id += 1;
+		self.streams.push(stream);
+		Ok(id)
+	}
+
+	/// Removes a texture and releases its resident texture.
+	///
+	/// Returns false if the texture is not found.
+	pub fn remove(&mut self, g: &mut Graphics, id: StreamId) -> Result<bool, GfxError> {
+		let Some(index) = self.streams.iter().position(|stream| stream.id == id) else { return Ok(false) };
+		let stream = self.streams.remove(index);
+		g.texture2d_delete(stream.texture, true)?;
+		Ok(true)
+	}
+
+	/// Returns the resident texture.
+	pub fn texture(&self, id: StreamId) -> Option<Texture2D> {
+		self.streams.iter().find(|stream| stream.id == id).map(|stream| stream.texture)
+	}
+
+	/// Returns true if the texture is streamed in up to its highest level.
+	pub fn is_complete(&self, id: StreamId) -> bool {
+		self.streams.iter().any(|stream| stream.id == id && stream.resident == stream.top)
+	}
+
+	/// Returns the number of textures.
+	#[inline]
+	pub fn len(&self) -> usize {
+		self.streams.len()
+	}
+
+	/// Returns true if there are no textures.
+	#[inline]
+	pub fn is_empty(&self) -> bool {
+		self.streams.is_empty()
+	}
+
+	/// Uploads the next mip levels until the budget is used up.
+	///
+	/// The texture with the lowest resident resolution is upgraded first so all textures sharpen evenly.
+	/// At least one level is uploaded if there is one, a level larger than the budget is uploaded alone in a frame.
+	/// Returns the number of bytes uploaded.
+	pub fn update(&mut self, g: &mut Graphics) -> Result<usize, GfxError> {
+		let mut uploaded = 0;
+		loop {
+			let next = self.streams.iter_mut()
+				.filter(|stream| stream.resident > stream.top)
+				.min_by_key(|stream| {
+					let (width, height) = stream.level_size(stream.resident);
+					width as i64 * height as i64
+				});
+			let Some(stream) = next else { break };
+			let level = stream.resident - 1;
+			let size = stream.upload_size(level);
+			if uploaded > 0 && uploaded + size > self.budget {
+				break;
+			}
+			stream.upload(g, level)?;
+			uploaded += size;
+		}
+		Ok(uploaded)
+	}
+}
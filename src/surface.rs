@@ -10,6 +10,23 @@ impl Surface {
 pub enum SurfaceFormat {
 	R8G8B8A8,
 	R8G8B8,
+	/// Half float color for HDR rendering.
+	R16G16B16A16F,
+	/// Float color for HDR rendering.
+	R32G32B32A32F,
+	/// Packed unsigned float color without alpha for HDR rendering.
+	///
+	/// Half the memory of [`R16G16B16A16F`](Self::R16G16B16A16F) for render targets which do not need alpha or negative colors.
+	R11G11B10F,
+}
+
+/// Surface depth format.
+#[derive(Copy, Clone, Debug, PartialEq, Hash)]
+pub enum DepthFormat {
+	D24,
+	/// Depth with 8 bits of stencil.
+	D24S8,
+	D32F,
 }
 
 /// Surface information.
@@ -19,6 +36,8 @@ pub struct SurfaceInfo {
 	pub has_depth: bool,
 	pub has_texture: bool,
 	pub format: SurfaceFormat,
+	/// Format of the depth attachment if `has_depth` is set.
+	pub depth_format: DepthFormat,
 	pub width: i32,
 	pub height: i32,
 }
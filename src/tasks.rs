@@ -0,0 +1,132 @@
+/*!
+Time-sliced scheduling of expensive CPU work.
+
+Long running work such as atlas repacks, mesh simplification or lightmap baking is split into small steps.
+The scheduler runs steps of its tasks each frame until the frame budget is used up so the work does not cause hitches.
+*/
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Result of a task step.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Step {
+	/// The task has more work to do.
+	Continue,
+	/// The task is finished and is removed from the scheduler.
+	Done,
+}
+
+/// Incremental task.
+pub trait ITask {
+	/// Performs a small unit of work.
+	///
+	/// Keep the steps short, the scheduler only checks the budget between steps.
+	fn step(&mut self) -> Step;
+}
+
+impl<F: FnMut() -> Step> ITask for F {
+	#[inline]
+	fn step(&mut self) -> Step {
+		self()
+	}
+}
+
+/// Identifies a task in the scheduler.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct TaskId(u64);
+
+/// Time-slicing scheduler.
+///
+/// Call [`run`](Scheduler::run) once per frame, the tasks are stepped in turn until the budget is used up.
+///
+/// # Example
+///
+/// ```rust
+/// use shade::tasks::{Scheduler, Step};
+///
+/// let mut scheduler = Scheduler::new(2.0);
+/// let mut remaining = 1000;
+/// let task = scheduler.spawn(move || {
+///     remaining -= 1;
+///     if remaining == 0 { Step::Done } else { Step::Continue }
+/// });
+///
+/// while scheduler.contains(task) {
+///     // Render the frame...
+///     scheduler.run();
+/// }
+/// assert!(scheduler.is_empty());
+/// ```
+pub struct Scheduler {
+	/// Time budget per frame.
+	pub budget: Duration,
+	tasks: VecDeque<(TaskId, Box<dyn ITask>)>,
+	next_id: u64,
+}
+
+impl Scheduler {
+	/// Creates a new scheduler with the budget per frame in milliseconds.
+	pub fn new(budget_ms: f32) -> Scheduler {
+		Scheduler {
+			budget: Duration::from_secs_f32(f32::max(0.0, budget_ms) / 1000.0),
+			tasks: VecDeque::new(),
+			next_id: 0,
+		}
+	}
+
+	/// Adds a task.
+	pub fn spawn<T: ITask + 'static>(&mut self, task: T) -> TaskId {
+		let id = TaskId(self.next_id);
+		self.next_id += 1;
+		self.tasks.push_back((id, Box::new(task)));
+		id
+	}
+
+	/// Removes a task before it is finished.
+	///
+	/// Returns false if the task is not found.
+	pub fn cancel(&mut self, id: TaskId) -> bool {
+		let len = self.tasks.len();
+		self.tasks.retain(|(task_id, _)| *task_id != id);
+		self.tasks.len() != len
+	}
+
+	/// Returns true if the task is not finished.
+	pub fn contains(&self, id: TaskId) -> bool {
+		self.tasks.iter().any(|(task_id, _)| *task_id == id)
+	}
+
+	/// Returns the number of unfinished tasks.
+	#[inline]
+	pub fn len(&self) -> usize {
+		self.tasks.len()
+	}
+
+	/// Returns true if there are no unfinished tasks.
+	#[inline]
+	pub fn is_empty(&self) -> bool {
+		self.tasks.is_empty()
+	}
+
+	/// Runs the tasks until the budget is used up.
+	///
+	/// The tasks are stepped round robin, at least one step is run if there are tasks.
+	/// With a budget smaller than a single step only one task advances per frame, the others wait their turn in the next frames.
+	/// Returns the number of steps run.
+	pub fn run(&mut self) -> u32 {
+		let start = Instant::now();
+		let mut steps = 0;
+		while let Some((id, mut task)) = self.tasks.pop_front() {
+			steps += 1;
+			if task.step() == Step::Continue {
+				// Round robin between the tasks
+				self.tasks.push_back((id, task));
+			}
+			if start.elapsed() >= self.budget {
+				break;
+			}
+		}
+		steps
+	}
+}
@@ -0,0 +1,134 @@
+use std::io;
+use super::*;
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, dataview::Pod)]
+#[repr(C)]
+struct Vertex {
+	position: [f32; 3],
+}
+
+unsafe impl TVertex for Vertex {
+	const VERTEX_LAYOUT: &'static VertexLayout = &VertexLayout {
+		size: mem::size_of::<Vertex>() as u16,
+		alignment: mem::align_of::<Vertex>() as u16,
+		instance_divisor: 0,
+		attributes: &[VertexAttribute { format: VertexAttributeFormat::F32, len: 3, offset: 0 }],
+	};
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, dataview::Pod)]
+#[repr(C)]
+struct OtherVertex {
+	position: [i32; 3],
+}
+
+unsafe impl TVertex for OtherVertex {
+	const VERTEX_LAYOUT: &'static VertexLayout = &VertexLayout {
+		size: mem::size_of::<OtherVertex>() as u16,
+		alignment: mem::align_of::<OtherVertex>() as u16,
+		instance_divisor: 0,
+		attributes: &[VertexAttribute { format: VertexAttributeFormat::I32, len: 3, offset: 0 }],
+	};
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, dataview::Pod)]
+#[repr(C, align(16))]
+struct AlignedVertex {
+	position: [f32; 4],
+}
+
+unsafe impl TVertex for AlignedVertex {
+	const VERTEX_LAYOUT: &'static VertexLayout = &VertexLayout {
+		size: mem::size_of::<AlignedVertex>() as u16,
+		alignment: mem::align_of::<AlignedVertex>() as u16,
+		instance_divisor: 0,
+		attributes: &[VertexAttribute { format: VertexAttributeFormat::F32, len: 4, offset: 0 }],
+	};
+}
+
+const VERTICES: [Vertex; 2] = [Vertex { position: [1.0, 2.0, 3.0] }, Vertex { position: [4.0, 5.0, 6.0] }];
+
+fn file() -> Vec<u8> {
+	let mut file = Vec::new();
+	write_vertices(&mut file, &VERTICES).unwrap();
+	file
+}
+
+/// Copies the bytes to a 16 byte aligned buffer at the offset.
+fn aligned(bytes: &[u8], offset: usize) -> Box<AlignedBytes<[u8; 128]>> {
+	let mut buf = Box::new(AlignedBytes([0u8; 128]));
+	buf.0[offset..offset + bytes.len()].copy_from_slice(bytes);
+	buf
+}
+
+#[test]
+fn roundtrip() {
+	let file = file();
+	assert_eq!(file.len(), MESH_FILE_HEADER_SIZE + 2 * 12);
+	assert_eq!(read_vertices::<Vertex, _>(&mut &file[..]).unwrap(), VERTICES);
+
+	let buf = aligned(&file, 0);
+	assert_eq!(vertices_from_bytes::<Vertex>(&buf.0[..file.len()]).unwrap(), VERTICES);
+}
+
+#[test]
+fn aligned_data() {
+	let vertices = [AlignedVertex { position: [1.0, 2.0, 3.0, 4.0] }];
+	let mut file = Vec::new();
+	write_vertices(&mut file, &vertices).unwrap();
+
+	// The header keeps 16 byte aligned vertices aligned
+	let buf = aligned(&file, 0);
+	assert_eq!(vertices_from_bytes::<AlignedVertex>(&buf.0[..file.len()]).unwrap(), vertices);
+}
+
+#[test]
+fn invalid_magic() {
+	let mut file = file();
+	file[0] = b'X';
+	assert!(matches!(read_vertices::<Vertex, _>(&mut &file[..]), Err(MeshFileError::InvalidMagic)));
+
+	let buf = aligned(&file, 0);
+	assert!(matches!(vertices_from_bytes::<Vertex>(&buf.0[..file.len()]), Err(MeshFileError::InvalidMagic)));
+}
+
+#[test]
+fn layout_mismatch() {
+	let file = file();
+	assert!(matches!(read_vertices::<OtherVertex, _>(&mut &file[..]), Err(MeshFileError::LayoutMismatch)));
+
+	let buf = aligned(&file, 0);
+	assert!(matches!(vertices_from_bytes::<OtherVertex>(&buf.0[..file.len()]), Err(MeshFileError::LayoutMismatch)));
+}
+
+#[test]
+fn truncated() {
+	let file = file();
+	let truncated = &file[..file.len() - 1];
+	assert!(matches!(read_vertices::<Vertex, _>(&mut &truncated[..]), Err(MeshFileError::Io(e)) if e.kind() == io::ErrorKind::UnexpectedEof));
+	assert!(matches!(read_vertices::<Vertex, _>(&mut &file[..10]), Err(MeshFileError::Io(e)) if e.kind() == io::ErrorKind::UnexpectedEof));
+
+	let buf = aligned(&file, 0);
+	assert!(matches!(vertices_from_bytes::<Vertex>(&buf.0[..file.len() - 1]), Err(MeshFileError::InvalidSize)));
+	assert!(matches!(vertices_from_bytes::<Vertex>(&buf.0[..10]), Err(MeshFileError::InvalidSize)));
+}
+
+#[test]
+fn corrupt_count() {
+	// A huge vertex count fails at the end of the data without allocating the count
+	let mut file = file();
+	file[8..16].copy_from_slice(&(u64::MAX / 2).to_le_bytes());
+	assert!(matches!(read_vertices::<Vertex, _>(&mut &file[..]), Err(MeshFileError::Io(e)) if e.kind() == io::ErrorKind::UnexpectedEof));
+
+	let buf = aligned(&file, 0);
+	assert!(matches!(vertices_from_bytes::<Vertex>(&buf.0[..file.len()]), Err(MeshFileError::InvalidSize)));
+}
+
+#[test]
+fn misaligned() {
+	let file = file();
+	let buf = aligned(&file, 1);
+	assert!(matches!(vertices_from_bytes::<Vertex>(&buf.0[1..1 + file.len()]), Err(MeshFileError::Misaligned)));
+	assert!(matches!(vertices_from_raw_bytes::<Vertex>(&buf.0[1..1 + 12]), Err(MeshFileError::Misaligned)));
+	assert!(matches!(vertices_from_raw_bytes::<Vertex>(&buf.0[0..10]), Err(MeshFileError::InvalidSize)));
+}
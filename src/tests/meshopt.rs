@@ -0,0 +1,81 @@
+use super::*;
+use cvmath::Vec3;
+
+/// Grid of `n` by `n` quads over `x0..x0 + n`, the vertices are in row-major order.
+fn grid(n: u32, x0: f32, height: impl Fn(f32, f32) -> f32) -> (Vec<Vec3<f32>>, Vec<u32>) {
+	let mut positions = Vec::new();
+	for y in 0..=n {
+		for x in 0..=n {
+			let (x, y) = (x0 + x as f32, y as f32);
+			positions.push(Vec3(x, y, height(x, y)));
+		}
+	}
+	let mut indices = Vec::new();
+	for y in 0..n {
+		for x in 0..n {
+			let i = y * (n + 1) + x;
+			indices.extend_from_slice(&[i, i + 1, i + n + 2, i, i + n + 2, i + n + 1]);
+		}
+	}
+	(positions, indices)
+}
+
+fn normal(positions: &[Vec3<f32>], corners: &[u32]) -> Vec3<f32> {
+	let [a, b, c] = [0, 1, 2].map(|i| positions[corners[i] as usize]);
+	(b - a).cross(c - a)
+}
+
+#[test]
+fn simplify_keep_all() {
+	let (positions, indices) = grid(4, 0.0, |_, _| 0.0);
+	assert_eq!(simplify(&indices, &positions, 1.0), indices);
+	assert_eq!(simplify(&indices[..4], &positions, 1.0), &indices[..3]);
+}
+
+#[test]
+fn simplify_flat() {
+	let (positions, indices) = grid(8, 0.0, |_, _| 0.0);
+	let simplified = simplify(&indices, &positions, 0.0);
+	assert!(simplified.len() < indices.len() / 2);
+
+	// The border vertices are locked
+	for (i, p) in positions.iter().enumerate() {
+		if p.x == 0.0 || p.y == 0.0 || p.x == 8.0 || p.y == 8.0 {
+			assert!(simplified.contains(&(i as u32)), "border vertex {i} was collapsed");
+		}
+	}
+	for corners in simplified.chunks_exact(3) {
+		assert!(normal(&positions, corners).z > 0.0);
+	}
+}
+
+#[test]
+fn simplify_curved() {
+	let (positions, indices) = grid(8, 0.0, |x, y| (x * 0.5).sin() + (y * 0.5).cos());
+	let simplified = simplify(&indices, &positions, 0.5);
+	assert!(simplified.len() < indices.len());
+	assert!(simplified.len() >= indices.len() / 2);
+
+	// No triangle is flipped
+	for corners in simplified.chunks_exact(3) {
+		assert!(normal(&positions, corners).z > 0.0);
+	}
+}
+
+#[test]
+fn simplify_seam() {
+	// Two grids side by side with the vertices along the seam split as for a uv seam
+	let (mut positions, mut indices) = grid(4, 0.0, |_, _| 0.0);
+	let (right_positions, right_indices) = grid(4, 4.0, |_, _| 0.0);
+	let offset = positions.len() as u32;
+	positions.extend_from_slice(&right_positions);
+	indices.extend(right_indices.iter().map(|&index| index + offset));
+
+	let simplified = simplify(&indices, &positions, 0.0);
+	assert!(simplified.len() < indices.len());
+	for (i, p) in positions.iter().enumerate() {
+		if p.x == 4.0 {
+			assert!(simplified.contains(&(i as u32)), "seam vertex {i} was collapsed");
+		}
+	}
+}
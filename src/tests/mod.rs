@@ -0,0 +1,7 @@
+use super::*;
+
+mod queue;
+mod meshfile;
+mod meshopt;
+#[cfg(feature = "soft")]
+mod streaming;
@@ -0,0 +1,41 @@
+use super::*;
+use crate::handle::Handle;
+use crate::queue::sort_key;
+
+#[test]
+fn opaque_order() {
+	// Grouped by shader, then texture, then front to back
+	let mut keys = [
+		sort_key(RenderBucket::Opaque, Shader::create(2), Texture2D::create(1), 0.1),
+		sort_key(RenderBucket::Opaque, Shader::create(1), Texture2D::create(2), 0.1),
+		sort_key(RenderBucket::Opaque, Shader::create(1), Texture2D::create(1), 0.9),
+		sort_key(RenderBucket::Opaque, Shader::create(1), Texture2D::create(1), 0.2),
+	];
+	let expected = [keys[3], keys[2], keys[1], keys[0]];
+	keys.sort();
+	assert_eq!(keys, expected);
+}
+
+#[test]
+fn transparent_order() {
+	// Back to front regardless of state, ties grouped by shader and texture
+	let far = sort_key(RenderBucket::Transparent, Shader::create(2), Texture2D::create(2), 0.9);
+	let near = sort_key(RenderBucket::Transparent, Shader::create(1), Texture2D::create(1), 0.1);
+	let tie_a = sort_key(RenderBucket::Transparent, Shader::create(1), Texture2D::create(2), 0.5);
+	let tie_b = sort_key(RenderBucket::Transparent, Shader::create(1), Texture2D::create(1), 0.5);
+	let mut keys = [near, tie_a, far, tie_b];
+	keys.sort();
+	assert_eq!(keys, [far, tie_b, tie_a, near]);
+}
+
+#[test]
+fn bucket_order() {
+	let shader = Shader::create(1);
+	let texture = Texture2D::create(1);
+	let opaque = sort_key(RenderBucket::Opaque, shader, texture, 1.0);
+	let alpha_test = sort_key(RenderBucket::AlphaTest, shader, texture, 0.0);
+	let transparent = sort_key(RenderBucket::Transparent, shader, texture, 1.0);
+	let overlay = sort_key(RenderBucket::Overlay, shader, texture, 0.0);
+	assert!(opaque < alpha_test && alpha_test < transparent && transparent < overlay);
+	assert_eq!(RenderQueue::default().len(), 0);
+}
@@ -0,0 +1,68 @@
+use super::*;
+use crate::streaming::*;
+
+fn checkerboard(size: i32) -> Vec<u8> {
+	(0..size * size).flat_map(|i| if (i + i / size) % 2 == 0 { [255; 4] } else { [0, 0, 0, 255] }).collect()
+}
+
+#[test]
+fn progressive() {
+	let mut soft = crate::soft::SoftGraphics::new(8, 8);
+	let g = &mut *soft;
+	let mut streamer = TextureStreamer::new(32 * 32 * 4);
+	let id = streamer.insert_rgba(g, checkerboard(64), 64, 64, &TextureProps::default()).unwrap();
+
+	// The placeholder is uploaded right away
+	let placeholder = streamer.texture(id).unwrap();
+	assert_eq!(g.texture2d_get_info(placeholder).unwrap().width, PLACEHOLDER_SIZE);
+	assert!(!streamer.is_complete(id));
+
+	assert_eq!(streamer.update(g), Ok(32 * 32 * 4));
+	let texture = streamer.texture(id).unwrap();
+	assert_eq!(g.texture2d_get_info(texture).unwrap().width, 32);
+	assert_eq!(g.texture2d_get_info(placeholder), Err(GfxError::InvalidTexture2DHandle));
+
+	// Larger than the budget, uploaded alone
+	assert_eq!(streamer.update(g), Ok(64 * 64 * 4));
+	let texture = streamer.texture(id).unwrap();
+	assert_eq!(g.texture2d_get_info(texture).unwrap().width, 64);
+	assert!(streamer.is_complete(id));
+	assert_eq!(streamer.update(g), Ok(0));
+
+	assert_eq!(streamer.remove(g, id), Ok(true));
+	assert_eq!(streamer.remove(g, id), Ok(false));
+	assert_eq!(g.texture2d_get_info(texture), Err(GfxError::InvalidTexture2DHandle));
+	assert!(streamer.is_empty());
+}
+
+#[test]
+fn lowest_resolution_first() {
+	let mut soft = crate::soft::SoftGraphics::new(8, 8);
+	let g = &mut *soft;
+	let mut streamer = TextureStreamer::new(0);
+	let large = streamer.insert_rgba(g, checkerboard(64), 64, 64, &TextureProps::default()).unwrap();
+	let small = streamer.insert_rgba(g, checkerboard(32), 32, 32, &TextureProps::default()).unwrap();
+	let width = |g: &mut Graphics, streamer: &TextureStreamer, id| g.texture2d_get_info(streamer.texture(id).unwrap()).unwrap().width;
+
+	streamer.update(g).unwrap();
+	assert_eq!((width(g, &streamer, large), width(g, &streamer, small)), (32, 16));
+	streamer.update(g).unwrap();
+	assert_eq!((width(g, &streamer, large), width(g, &streamer, small)), (32, 32));
+	assert!(streamer.is_complete(small));
+	streamer.update(g).unwrap();
+	assert_eq!((width(g, &streamer, large), width(g, &streamer, small)), (64, 32));
+	assert!(streamer.is_complete(large));
+}
+
+#[test]
+fn small_texture() {
+	let mut soft = crate::soft::SoftGraphics::new(8, 8);
+	let g = &mut *soft;
+	let mut streamer = TextureStreamer::new(1024);
+	let id = streamer.insert_rgba(g, checkerboard(8), 8, 8, &TextureProps::default()).unwrap();
+	assert!(streamer.is_complete(id));
+	assert_eq!(streamer.update(g), Ok(0));
+
+	assert_eq!(streamer.insert_rgba(g, vec![0; 4], 8, 8, &TextureProps::default()), Err(GfxError::IndexOutOfBounds));
+	assert_eq!(streamer.len(), 1);
+}
@@ -1,9 +1,78 @@
+use std::hash::{Hash, Hasher};
+
 define_handle!(Texture2D);
+define_handle!(TextureCube);
+define_handle!(Texture3D);
+define_handle!(Texture2DArray);
 
 /// Texture format.
+///
+/// Cube, 3D and array textures accept the uncompressed formats, including the float formats.
+/// The software renderer only supports [`R8G8B8A8`](Self::R8G8B8A8).
+///
+/// The compressed formats are uploaded with [`texture2d_set_compressed_data`](crate::IGraphics::texture2d_set_compressed_data),
+/// check [`Caps::texture_format`](crate::Caps::texture_format) before creating textures with them.
 #[derive(Copy, Clone, Debug, PartialEq, Hash)]
 pub enum TextureFormat {
 	R8G8B8A8,
+	/// Half float Rgba for HDR, the data is 16-bit floats.
+	R16G16B16A16F,
+	/// Float Rgba for HDR, the data is 32-bit floats.
+	R32G32B32A32F,
+	/// Packed unsigned float Rgb for HDR without alpha, the data is 32-bit words with 11, 11 and 10 bit floats from the low bits up.
+	R11G11B10F,
+	/// BC1 (DXT1) compressed Rgb with 1-bit alpha, 8 bytes per 4x4 block.
+	BC1,
+	/// BC2 (DXT3) compressed Rgba with explicit alpha, 16 bytes per 4x4 block.
+	BC2,
+	/// BC3 (DXT5) compressed Rgba with interpolated alpha, 16 bytes per 4x4 block.
+	BC3,
+	/// ETC2 compressed Rgb, 8 bytes per 4x4 block.
+	Etc2Rgb8,
+	/// ETC2 compressed Rgba, 16 bytes per 4x4 block.
+	Etc2Rgba8,
+	/// ASTC compressed Rgba, 16 bytes per 4x4 block.
+	Astc4x4,
+}
+
+impl TextureFormat {
+	/// Returns if the format is block compressed.
+	#[inline]
+	pub fn is_compressed(self) -> bool {
+		matches!(self,
+			TextureFormat::BC1 | TextureFormat::BC2 | TextureFormat::BC3 |
+			TextureFormat::Etc2Rgb8 | TextureFormat::Etc2Rgba8 | TextureFormat::Astc4x4)
+	}
+
+	/// Returns if the format stores floating point colors.
+	#[inline]
+	pub fn is_float(self) -> bool {
+		matches!(self, TextureFormat::R16G16B16A16F | TextureFormat::R32G32B32A32F | TextureFormat::R11G11B10F)
+	}
+
+	/// Returns the size in bytes of an image of the given size.
+	///
+	/// Compressed images are stored in whole 4x4 blocks.
+	///
+	/// ```rust
+	/// use shade::TextureFormat;
+	///
+	/// assert_eq!(TextureFormat::R8G8B8A8.data_size(5, 3), 60);
+	/// assert_eq!(TextureFormat::R16G16B16A16F.data_size(5, 3), 120);
+	/// assert_eq!(TextureFormat::BC1.data_size(5, 3), 16);
+	/// assert_eq!(TextureFormat::BC3.data_size(1, 1), 16);
+	/// ```
+	pub fn data_size(self, width: i32, height: i32) -> usize {
+		let (width, height) = (i32::max(0, width) as usize, i32::max(0, height) as usize);
+		let block_size = match self {
+			TextureFormat::R8G8B8A8 | TextureFormat::R11G11B10F => return width * height * 4,
+			TextureFormat::R16G16B16A16F => return width * height * 8,
+			TextureFormat::R32G32B32A32F => return width * height * 16,
+			TextureFormat::BC1 | TextureFormat::Etc2Rgb8 => 8,
+			TextureFormat::BC2 | TextureFormat::BC3 | TextureFormat::Etc2Rgba8 | TextureFormat::Astc4x4 => 16,
+		};
+		width.div_ceil(4) * height.div_ceil(4) * block_size
+	}
 }
 
 /// Texture wrap mode.
@@ -16,14 +85,154 @@ pub enum TextureWrap {
 }
 
 /// Texture filter mode.
+///
+/// The mipmapped modes select the mip levels for minification, they filter like their base mode for magnification.
 #[derive(Copy, Clone, Debug, PartialEq, Hash)]
 pub enum TextureFilter {
 	Nearest,
 	Linear,
+	/// Nearest texel of the nearest mip level.
+	NearestMipmapNearest,
+	/// Bilinear filtering of the nearest mip level.
+	LinearMipmapNearest,
+	/// Nearest texel blended between the two nearest mip levels.
+	NearestMipmapLinear,
+	/// Trilinear filtering.
+	LinearMipmapLinear,
+}
+
+impl TextureFilter {
+	/// Returns the filter without mip level selection.
+	#[inline]
+	pub fn base(self) -> TextureFilter {
+		match self {
+			TextureFilter::Nearest | TextureFilter::NearestMipmapNearest | TextureFilter::NearestMipmapLinear => TextureFilter::Nearest,
+			TextureFilter::Linear | TextureFilter::LinearMipmapNearest | TextureFilter::LinearMipmapLinear => TextureFilter::Linear,
+		}
+	}
+}
+
+/// Mip levels of a texture.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub enum MipLevels {
+	/// Only the base level.
+	#[default]
+	None,
+	/// Complete mip chain down to a single texel.
+	Full,
+	/// At most this many levels, including the base level.
+	Max(u8),
+}
+
+impl MipLevels {
+	/// Returns the number of levels, including the base level, for a texture of the given size.
+	///
+	/// ```rust
+	/// use shade::MipLevels;
+	///
+	/// assert_eq!(MipLevels::None.count(256, 64), 1);
+	/// assert_eq!(MipLevels::Full.count(256, 64), 9);
+	/// assert_eq!(MipLevels::Max(4).count(256, 64), 4);
+	/// ```
+	pub fn count(self, width: i32, height: i32) -> u32 {
+		let full = 32 - (i32::max(1, i32::max(width, height)) as u32).leading_zeros();
+		match self {
+			MipLevels::None => 1,
+			MipLevels::Full => full,
+			MipLevels::Max(levels) => (levels as u32).clamp(1, full),
+		}
+	}
+}
+
+/// Texture quality setting.
+///
+/// Image loaders drop the top mip levels at decode time before uploading,
+/// the same assets can be used on devices with little texture memory.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub enum TextureQuality {
+	/// Full resolution.
+	#[default]
+	Full = 0,
+	/// Half resolution.
+	Half = 1,
+	/// Quarter resolution.
+	Quarter = 2,
+}
+
+static TEXTURE_QUALITY: std::sync::atomic::AtomicU8 = std::sync::atomic::AtomicU8::new(TextureQuality::Full as u8);
+
+/// Sets the global texture quality honored by the image loaders.
+///
+/// Affects textures loaded afterwards.
+pub fn set_texture_quality(quality: TextureQuality) {
+	TEXTURE_QUALITY.store(quality as u8, std::sync::atomic::Ordering::Relaxed);
+}
+
+/// Returns the global texture quality.
+pub fn texture_quality() -> TextureQuality {
+	match TEXTURE_QUALITY.load(std::sync::atomic::Ordering::Relaxed) {
+		0 => TextureQuality::Full,
+		1 => TextureQuality::Half,
+		_ => TextureQuality::Quarter,
+	}
+}
+
+impl TextureQuality {
+	/// Returns the number of top mip levels dropped.
+	#[inline]
+	pub fn mip_bias(self) -> u32 {
+		self as u32
+	}
+
+	/// Downscales 8-bit Rgba pixels with a box filter, halving the size for every dropped mip level.
+	///
+	/// The size is not reduced below one pixel. Returns the new width and height.
+	///
+	/// ```rust
+	/// use shade::TextureQuality;
+	///
+	/// let mut image = [0, 0, 0, 255, 255, 255, 255, 255].repeat(4 * 2);
+	/// let size = TextureQuality::Half.downscale(&mut image, 4, 4);
+	/// assert_eq!(size, (2, 2));
+	/// assert_eq!(image, [128, 128, 128, 255].repeat(2 * 2));
+	/// ```
+	pub fn downscale(self, image: &mut Vec<u8>, width: usize, height: usize) -> (usize, usize) {
+		let (mut width, mut height) = (width, height);
+		for _ in 0..self.mip_bias() {
+			if width <= 1 && height <= 1 {
+				break;
+			}
+			(*image, width, height) = halve(image, width, height);
+		}
+		(width, height)
+	}
+}
+
+/// Halves the size of 8-bit Rgba pixels with a box filter, the size is not reduced below one pixel.
+///
+/// Returns the new pixels, width and height.
+pub(crate) fn halve(image: &[u8], width: usize, height: usize) -> (Vec<u8>, usize, usize) {
+	let new_width = usize::max(1, width / 2);
+	let new_height = usize::max(1, height / 2);
+	let mut new_image = Vec::with_capacity(new_width * new_height * 4);
+	for y in 0..new_height {
+		let (y0, y1) = (y * 2, usize::min(y * 2 + 1, height - 1));
+		for x in 0..new_width {
+			let (x0, x1) = (x * 2, usize::min(x * 2 + 1, width - 1));
+			for c in 0..4 {
+				let sum = image[(y0 * width + x0) * 4 + c] as u32
+					+ image[(y0 * width + x1) * 4 + c] as u32
+					+ image[(y1 * width + x0) * 4 + c] as u32
+					+ image[(y1 * width + x1) * 4 + c] as u32;
+				new_image.push(((sum + 2) / 4) as u8);
+			}
+		}
+	}
+	(new_image, new_width, new_height)
 }
 
 /// Texture2D information.
-#[derive(Copy, Clone, Debug, PartialEq, Hash)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub struct Texture2DInfo {
 	pub format: TextureFormat,
 	pub width: i32,
@@ -33,6 +242,21 @@ pub struct Texture2DInfo {
 	pub wrap_u: TextureWrap,
 	pub wrap_v: TextureWrap,
 	pub border_color: [u8; 4],
+	/// Mip levels generated when the data is set.
+	pub mip_levels: MipLevels,
+	/// Maximum anisotropy of the filtering, `1.0` disables anisotropic filtering.
+	///
+	/// Clamped to [`Caps::max_anisotropy`](crate::Caps::max_anisotropy), ignored by the software renderer.
+	pub anisotropy: f32,
+}
+
+impl Hash for Texture2DInfo {
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		self.format.hash(state);
+		self.width.hash(state);
+		self.height.hash(state);
+		self.props().hash(state);
+	}
 }
 
 impl Default for Texture2DInfo {
@@ -46,6 +270,163 @@ impl Default for Texture2DInfo {
 			wrap_u: TextureWrap::ClampEdge,
 			wrap_v: TextureWrap::ClampEdge,
 			border_color: [0, 0, 0, 0],
+			mip_levels: MipLevels::None,
+			anisotropy: 1.0,
 		}
 	}
 }
+
+/// TextureCube information.
+///
+/// Cube maps always clamp to the edge of the faces and are sampled across the seams.
+#[derive(Copy, Clone, Debug, PartialEq, Hash)]
+pub struct TextureCubeInfo {
+	pub format: TextureFormat,
+	/// Width and height of the faces.
+	pub size: i32,
+	pub filter_min: TextureFilter,
+	pub filter_mag: TextureFilter,
+}
+
+impl Default for TextureCubeInfo {
+	fn default() -> Self {
+		Self {
+			format: TextureFormat::R8G8B8A8,
+			size: 0,
+			filter_min: TextureFilter::Linear,
+			filter_mag: TextureFilter::Linear,
+		}
+	}
+}
+
+/// Texture3D information.
+#[derive(Copy, Clone, Debug, PartialEq, Hash)]
+pub struct Texture3DInfo {
+	pub format: TextureFormat,
+	pub width: i32,
+	pub height: i32,
+	pub depth: i32,
+	pub filter_min: TextureFilter,
+	pub filter_mag: TextureFilter,
+	pub wrap_u: TextureWrap,
+	pub wrap_v: TextureWrap,
+	pub wrap_w: TextureWrap,
+}
+
+impl Default for Texture3DInfo {
+	fn default() -> Self {
+		Self {
+			format: TextureFormat::R8G8B8A8,
+			width: 0,
+			height: 0,
+			depth: 0,
+			filter_min: TextureFilter::Linear,
+			filter_mag: TextureFilter::Linear,
+			wrap_u: TextureWrap::ClampEdge,
+			wrap_v: TextureWrap::ClampEdge,
+			wrap_w: TextureWrap::ClampEdge,
+		}
+	}
+}
+
+/// Texture2DArray information.
+#[derive(Copy, Clone, Debug, PartialEq, Hash)]
+pub struct Texture2DArrayInfo {
+	pub format: TextureFormat,
+	pub width: i32,
+	pub height: i32,
+	/// Number of layers.
+	pub layers: i32,
+	/// Generate the mipmaps of the layers when their data is set.
+	pub mipmaps: bool,
+	pub filter_min: TextureFilter,
+	pub filter_mag: TextureFilter,
+	pub wrap_u: TextureWrap,
+	pub wrap_v: TextureWrap,
+}
+
+impl Default for Texture2DArrayInfo {
+	fn default() -> Self {
+		Self {
+			format: TextureFormat::R8G8B8A8,
+			width: 0,
+			height: 0,
+			layers: 0,
+			mipmaps: false,
+			filter_min: TextureFilter::Linear,
+			filter_mag: TextureFilter::Linear,
+			wrap_u: TextureWrap::ClampEdge,
+			wrap_v: TextureWrap::ClampEdge,
+		}
+	}
+}
+
+/// Region copied by [texture2d_copy](crate::IGraphics::texture2d_copy).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TextureCopy {
+	/// Rectangle in the source texture.
+	pub src_rect: cvmath::Rect<i32>,
+	/// Position of the rectangle in the destination texture.
+	pub dst_pos: cvmath::Vec2<i32>,
+}
+
+/// Texture sampling properties.
+///
+/// Can be changed after the texture is created without uploading the data again.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TextureProps {
+	pub filter_min: TextureFilter,
+	pub filter_mag: TextureFilter,
+	pub wrap_u: TextureWrap,
+	pub wrap_v: TextureWrap,
+	pub border_color: [u8; 4],
+	pub mip_levels: MipLevels,
+	pub anisotropy: f32,
+}
+
+impl Hash for TextureProps {
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		self.filter_min.hash(state);
+		self.filter_mag.hash(state);
+		self.wrap_u.hash(state);
+		self.wrap_v.hash(state);
+		self.border_color.hash(state);
+		self.mip_levels.hash(state);
+		// Adding zero normalizes negative zero so equal values hash the same
+		(self.anisotropy + 0.0).to_bits().hash(state);
+	}
+}
+
+impl Default for TextureProps {
+	fn default() -> Self {
+		Texture2DInfo::default().props()
+	}
+}
+
+impl Texture2DInfo {
+	/// Returns the sampling properties.
+	#[inline]
+	pub fn props(&self) -> TextureProps {
+		TextureProps {
+			filter_min: self.filter_min,
+			filter_mag: self.filter_mag,
+			wrap_u: self.wrap_u,
+			wrap_v: self.wrap_v,
+			border_color: self.border_color,
+			mip_levels: self.mip_levels,
+			anisotropy: self.anisotropy,
+		}
+	}
+
+	/// Sets the sampling properties.
+	#[inline]
+	pub fn set_props(&mut self, props: &TextureProps) {
+		self.filter_min = props.filter_min;
+		self.filter_mag = props.filter_mag;
+		self.wrap_u = props.wrap_u;
+		self.wrap_v = props.wrap_v;
+		self.border_color = props.border_color;
+		self.mip_levels = props.mip_levels;
+		self.anisotropy = props.anisotropy;
+	}
+}
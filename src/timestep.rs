@@ -0,0 +1,64 @@
+/// Fixed timestep accumulator.
+///
+/// Runs the simulation at a fixed rate independent of the display rate.
+/// Draw code blends the previous and current simulation states with [`alpha`](FixedTimestep::alpha) to avoid judder.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct FixedTimestep {
+	/// Duration of a simulation tick in seconds.
+	pub step: f64,
+	/// Maximum number of ticks per frame, drops time when the simulation falls behind.
+	pub max_ticks: u32,
+	accumulator: f64,
+}
+
+impl FixedTimestep {
+	/// Creates a new fixed timestep with the tick duration in seconds.
+	#[inline]
+	pub fn new(step: f64) -> FixedTimestep {
+		FixedTimestep { step, max_ticks: 8, accumulator: 0.0 }
+	}
+
+	/// Advances the frame time in seconds.
+	///
+	/// Returns the number of simulation ticks to run this frame.
+	pub fn advance(&mut self, frame_time: f64) -> u32 {
+		if self.step.is_nan() || self.step <= 0.0 {
+			return 0;
+		}
+		self.accumulator += f64::max(frame_time, 0.0);
+		let mut ticks = 0;
+		while self.accumulator >= self.step {
+			self.accumulator -= self.step;
+			ticks += 1;
+			if ticks >= self.max_ticks {
+				// Drop the remaining time to avoid a spiral of death
+				self.accumulator = f64::min(self.accumulator, self.step);
+				break;
+			}
+		}
+		ticks
+	}
+
+	/// Returns the interpolation factor between the previous and current simulation states.
+	#[inline]
+	pub fn alpha(&self) -> f32 {
+		if self.step > 0.0 {
+			f64::min(self.accumulator / self.step, 1.0) as f32
+		}
+		else {
+			1.0
+		}
+	}
+
+	/// Resets the accumulated time.
+	#[inline]
+	pub fn reset(&mut self) {
+		self.accumulator = 0.0;
+	}
+}
+
+/// Linearly interpolates between the previous and current state.
+#[inline]
+pub fn lerp<T: Copy + std::ops::Add<Output = T> + std::ops::Sub<Output = T> + std::ops::Mul<f32, Output = T>>(prev: T, curr: T, alpha: f32) -> T {
+	prev + (curr - prev) * alpha
+}
@@ -1,6 +1,10 @@
 define_handle!(UniformBuffer);
 
 /// Defines a type containing uniform data.
+///
+/// # Safety
+///
+/// The uniform layout must describe the memory layout of the type, the attributes are read from its bytes at their offsets.
 pub unsafe trait TUniform: Copy + Default + dataview::Pod {
 	const UNIFORM_LAYOUT: &'static UniformLayout;
 }
@@ -23,6 +27,9 @@ pub enum UniformType {
 	Mat3x2 { order: UniformMatOrder }, Mat3x3 { order: UniformMatOrder }, Mat3x4 { order: UniformMatOrder },
 	Mat4x2 { order: UniformMatOrder }, Mat4x3 { order: UniformMatOrder }, Mat4x4 { order: UniformMatOrder },
 	Sampler2D(u8),
+	SamplerCube(u8),
+	Sampler3D(u8),
+	Sampler2DArray(u8),
 }
 
 /// Uniform attribute.
@@ -39,3 +46,147 @@ pub struct UniformLayout {
 	pub alignment: u16,
 	pub attributes: &'static [UniformAttribute],
 }
+
+impl UniformType {
+	/// Returns the size of a scalar in bytes, the number of vectors and the number of components per vector.
+	///
+	/// Matrices are stored as vectors in their declared order. Samplers are not part of uniform blocks.
+	fn vector_shape(self) -> Option<(usize, usize, usize)> {
+		let mat = |cols: usize, rows: usize, order: UniformMatOrder| match order {
+			UniformMatOrder::ColumnMajor => Some((4, cols, rows)),
+			UniformMatOrder::RowMajor => Some((4, rows, cols)),
+		};
+		match self {
+			UniformType::D1 => Some((8, 1, 1)),
+			UniformType::D2 => Some((8, 1, 2)),
+			UniformType::D3 => Some((8, 1, 3)),
+			UniformType::D4 => Some((8, 1, 4)),
+			UniformType::F1 | UniformType::I1 | UniformType::U1 | UniformType::B1 => Some((4, 1, 1)),
+			UniformType::F2 | UniformType::I2 | UniformType::U2 | UniformType::B2 => Some((4, 1, 2)),
+			UniformType::F3 | UniformType::I3 | UniformType::U3 | UniformType::B3 => Some((4, 1, 3)),
+			UniformType::F4 | UniformType::I4 | UniformType::U4 | UniformType::B4 => Some((4, 1, 4)),
+			UniformType::Mat2x2 { order } => mat(2, 2, order),
+			UniformType::Mat2x3 { order } => mat(2, 3, order),
+			UniformType::Mat2x4 { order } => mat(2, 4, order),
+			UniformType::Mat3x2 { order } => mat(3, 2, order),
+			UniformType::Mat3x3 { order } => mat(3, 3, order),
+			UniformType::Mat3x4 { order } => mat(3, 4, order),
+			UniformType::Mat4x2 { order } => mat(4, 2, order),
+			UniformType::Mat4x3 { order } => mat(4, 3, order),
+			UniformType::Mat4x4 { order } => mat(4, 4, order),
+			UniformType::Sampler2D(_) | UniformType::SamplerCube(_) | UniformType::Sampler3D(_) | UniformType::Sampler2DArray(_) => None,
+		}
+	}
+}
+
+impl UniformLayout {
+	/// Returns the size of the std140 uniform block in bytes.
+	pub fn std140_size(&self) -> usize {
+		self.std140(None, &mut Vec::new())
+	}
+
+	/// Packs the data of a single uniform into a std140 uniform block.
+	///
+	/// The members of the block are declared in the order of the attributes, samplers are skipped.
+	/// Row-major matrices must be declared with `layout(row_major)` in the block.
+	///
+	/// ```rust
+	/// use shade::{UniformAttribute, UniformLayout, UniformType};
+	///
+	/// static LAYOUT: UniformLayout = UniformLayout {
+	///     size: 16,
+	///     alignment: 4,
+	///     attributes: &[
+	///         UniformAttribute { name: "u_alpha", ty: UniformType::F1, offset: 0, len: 1 },
+	///         UniformAttribute { name: "u_color", ty: UniformType::F3, offset: 4, len: 1 },
+	///     ],
+	/// };
+	///
+	/// let data: Vec<u8> = [0.5f32, 1.0, 2.0, 3.0].iter().flat_map(|f| f.to_ne_bytes()).collect();
+	/// let mut block = Vec::new();
+	/// LAYOUT.pack_std140(&data, &mut block);
+	///
+	/// // The vec3 is aligned to 16 bytes
+	/// assert_eq!(block.len(), 32);
+	/// assert_eq!(&block[16..20], &1.0f32.to_ne_bytes());
+	/// ```
+	///
+	/// Matrix columns and array elements are padded to a vec4 stride, doubles are aligned to 8 bytes:
+	///
+	/// ```rust
+	/// use shade::{UniformAttribute, UniformLayout, UniformMatOrder, UniformType};
+	///
+	/// static LAYOUT: UniformLayout = UniformLayout {
+	///     size: 72,
+	///     alignment: 8,
+	///     attributes: &[
+	///         UniformAttribute { name: "u_transform", ty: UniformType::Mat3x3 { order: UniformMatOrder::ColumnMajor }, offset: 0, len: 1 },
+	///         UniformAttribute { name: "u_weights", ty: UniformType::F1, offset: 36, len: 3 },
+	///         UniformAttribute { name: "u_origin", ty: UniformType::D3, offset: 48, len: 1 },
+	///     ],
+	/// };
+	///
+	/// let mut data: Vec<u8> = (1..=12).flat_map(|i| (i as f32).to_ne_bytes()).collect();
+	/// data.extend([13.0f64, 14.0, 15.0].iter().flat_map(|d| d.to_ne_bytes()));
+	/// let mut block = Vec::new();
+	/// LAYOUT.pack_std140(&data, &mut block);
+	///
+	/// assert_eq!(block.len(), LAYOUT.std140_size());
+	/// assert_eq!(block.len(), 128);
+	/// // The mat3 columns have a 16 byte stride
+	/// assert_eq!(&block[16..20], &4.0f32.to_ne_bytes());
+	/// assert_eq!(&block[28..32], &[0; 4]);
+	/// assert_eq!(&block[32..36], &7.0f32.to_ne_bytes());
+	/// // The float array elements have a 16 byte stride
+	/// assert_eq!(&block[48..52], &10.0f32.to_ne_bytes());
+	/// assert_eq!(&block[64..68], &11.0f32.to_ne_bytes());
+	/// assert_eq!(&block[80..84], &12.0f32.to_ne_bytes());
+	/// // The dvec3 is aligned to 32 bytes
+	/// assert_eq!(&block[96..104], &13.0f64.to_ne_bytes());
+	/// assert_eq!(&block[112..120], &15.0f64.to_ne_bytes());
+	/// ```
+	pub fn pack_std140(&self, data: &[u8], out: &mut Vec<u8>) {
+		self.std140(Some(data), out);
+	}
+
+	fn std140(&self, data: Option<&[u8]>, out: &mut Vec<u8>) -> usize {
+		let round_up = |value: usize, align: usize| value.div_ceil(align) * align;
+		let start = out.len();
+		let mut offset = 0;
+		for attr in self.attributes {
+			let Some((scalar, vectors, components)) = attr.ty.vector_shape() else { continue };
+			let vec_size = scalar * components;
+			let vec_align = match components {
+				1 => scalar,
+				2 => scalar * 2,
+				_ => scalar * 4,
+			};
+			// Arrays and matrix vectors are aligned to vec4
+			let count = attr.len as usize * vectors;
+			let (align, stride) = if count > 1 {
+				let align = round_up(vec_align, 16);
+				(align, round_up(vec_size, align))
+			}
+			else {
+				(vec_align, vec_size)
+			};
+			offset = round_up(offset, align);
+			if let Some(data) = data {
+				out.resize(start + offset + stride * count, 0);
+				for i in 0..count {
+					let src = attr.offset as usize + i * vec_size;
+					let dst = start + offset + i * stride;
+					if let Some(bytes) = data.get(src..src + vec_size) {
+						out[dst..dst + vec_size].copy_from_slice(bytes);
+					}
+				}
+			}
+			offset += stride * count;
+		}
+		let size = round_up(offset, 16);
+		if data.is_some() {
+			out.resize(start + size, 0);
+		}
+		size
+	}
+}
@@ -1,5 +1,9 @@
 
 /// Defines a vertex type.
+///
+/// # Safety
+///
+/// The vertex layout must describe the memory layout of the type, the attributes are read from its bytes at their offsets.
 pub unsafe trait TVertex: Copy + Default + dataview::Pod {
 	const VERTEX_LAYOUT: &'static VertexLayout;
 }
@@ -11,6 +15,10 @@ pub enum VertexAttributeFormat {
 	F32,
 
 	/// 64-bit floating point number.
+	///
+	/// Read as double precision attributes (`double`, `dvec*`) when [`Caps::f64`](crate::Caps::f64) is supported,
+	/// otherwise the values are converted to single precision (`float`, `vec*`) and lose precision.
+	/// Use [`split_f64`] to keep the precision of large coordinates on any device.
 	F64,
 
 	/// 32-bit signed integer.
@@ -65,5 +73,30 @@ pub struct VertexAttribute {
 pub struct VertexLayout {
 	pub size: u16,
 	pub alignment: u16,
+	/// Advance the attributes once per this many instances, zero advances them per vertex.
+	///
+	/// Instanced attributes are read from a buffer bound in place of the vertices.
+	pub instance_divisor: u32,
 	pub attributes: &'static [VertexAttribute],
 }
+
+/// Splits a double into a high and low single precision part.
+///
+/// Emulates double precision vertex attributes on devices without [`Caps::f64`](crate::Caps::f64).
+/// Store both parts as [`F32`](VertexAttributeFormat::F32) attributes and subtract the split camera position in the shader,
+/// the large parts cancel out before the small ones are added: `(pos_hi - eye_hi) + (pos_lo - eye_lo)`.
+///
+/// # Examples
+///
+/// ```rust
+/// let value = 1234567.891;
+/// let (hi, lo) = shade::split_f64(value);
+/// assert_eq!(hi, value as f32);
+/// assert!((hi as f64 + lo as f64 - value).abs() < 1e-6);
+/// ```
+#[inline]
+pub fn split_f64(value: f64) -> (f32, f32) {
+	let hi = value as f32;
+	let lo = (value - hi as f64) as f32;
+	(hi, lo)
+}